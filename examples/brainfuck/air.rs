@@ -1,140 +1,244 @@
+use crate::constraints::mem_val_range_check;
 use crate::tables;
 use crate::tables::Challenge;
-use crate::tables::EvaluationArgumentHint;
+use crate::tables::InputHint;
+use crate::tables::InstructionHint;
+use crate::tables::MemoryHint;
+use crate::tables::OutputHint;
+use crate::tables::ProcessorHint;
+use crate::tables::Table;
 use crate::vm::compile;
 use ark_ff::Field;
-use ark_ff::One;
 use ark_ff::Zero;
-use ark_poly::EvaluationDomain;
-use ark_poly::Radix2EvaluationDomain;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
+use core::marker::PhantomData;
 use gpu_poly::fields::p18446744069414584321::Fp;
 use gpu_poly::fields::p18446744069414584321::Fq3;
 use ministark::challenges::Challenges;
 use ministark::constraints::AlgebraicExpression;
-use ministark::constraints::FieldConstant;
+use ministark::constraints::ExecutionTraceColumn;
 use ministark::constraints::Hint;
 use ministark::constraints::VerifierChallenge;
+use ministark::display::Names;
 use ministark::hints::Hints;
+use ministark::multi::MultiAir;
+use ministark::multi::TableAir;
 use ministark::Air;
 use ministark::ProofOptions;
 use ministark::TraceInfo;
 
-#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Default)]
 pub struct ExecutionInfo {
     pub source_code: String,
     pub input: Vec<u8>,
     pub output: Vec<u8>,
 }
 
-pub struct BrainfuckAir {
-    options: ProofOptions,
-    trace_info: TraceInfo,
-    execution_info: ExecutionInfo,
-    constraints: Vec<AlgebraicExpression<Fp, Fq3>>,
-}
+/// Constraints and display names of one of the brainfuck tables
+pub trait BrainfuckTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>>;
 
-impl Air for BrainfuckAir {
-    type Fp = Fp;
-    type Fq = Fq3;
-    type PublicInputs = ExecutionInfo;
+    fn display_names() -> Names;
+}
 
-    fn new(trace_info: TraceInfo, execution_info: ExecutionInfo, options: ProofOptions) -> Self {
-        use AlgebraicExpression::*;
-        let one = FieldConstant::Fp(Fp::one());
-        let trace_len = trace_info.trace_len;
-        let trace_xs = Radix2EvaluationDomain::<Fp>::new(trace_len).unwrap();
-        let first_trace_x = FieldConstant::Fp(trace_xs.element(0));
-        let last_trace_x = FieldConstant::Fp(trace_xs.element(trace_len - 1));
+pub struct ProcessorTable;
 
+impl BrainfuckTable for ProcessorTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>> {
         let transition_constraints = [
             tables::ProcessorBaseColumn::transition_constraints(),
             tables::ProcessorExtensionColumn::transition_constraints(),
-            tables::MemoryBaseColumn::transition_constraints(),
-            tables::MemoryExtensionColumn::transition_constraints(),
-            tables::InstructionBaseColumn::transition_constraints(),
-            tables::InstructionExtensionColumn::transition_constraints(),
-            tables::InputExtensionColumn::transition_constraints(),
-            tables::OutputExtensionColumn::transition_constraints(),
         ]
         .into_iter()
         .flatten()
-        .map(|constraint| {
-            // ensure constraints hold in all rows except the last
-            // multiply by `(x - t_(n-1))` to remove the last term
-            // NOTE: `x^trace_len - 1 = (x - t_0)(x - t_1)...(x - t_(n-1))`
-            // NOTE: `t^(n-1) = t^(-1)`
-            constraint * ((X - last_trace_x) / (X.pow(trace_len) - one))
-        });
+        .map(|constraint| constraint.on_transition(trace_len));
 
         let boundary_constraints = [
             tables::ProcessorBaseColumn::boundary_constraints(),
             tables::ProcessorExtensionColumn::boundary_constraints(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|constraint| constraint.on_first_row(trace_len));
+
+        let terminal_constraints = tables::ProcessorExtensionColumn::terminal_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_last_row(trace_len));
+
+        transition_constraints
+            .chain(boundary_constraints)
+            .chain(terminal_constraints)
+            .chain(mem_val_range_check().constraints(trace_len))
+            .collect()
+    }
+
+    fn display_names() -> Names {
+        Names::new()
+            .columns(tables::ProcessorBaseColumn::VARIANTS)
+            .columns(tables::ProcessorExtensionColumn::VARIANTS)
+            .hints(ProcessorHint::VARIANTS)
+    }
+}
+
+pub struct MemoryTable;
+
+impl BrainfuckTable for MemoryTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>> {
+        let transition_constraints = [
+            tables::MemoryBaseColumn::transition_constraints(),
+            tables::MemoryExtensionColumn::transition_constraints(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|constraint| constraint.on_transition(trace_len));
+
+        let boundary_constraints = [
             tables::MemoryBaseColumn::boundary_constraints(),
-            tables::InstructionBaseColumn::boundary_constraints(),
-            tables::InstructionExtensionColumn::boundary_constraints(),
-            tables::InputExtensionColumn::boundary_constraints(),
-            tables::OutputExtensionColumn::boundary_constraints(),
+            tables::MemoryExtensionColumn::boundary_constraints(),
         ]
         .into_iter()
         .flatten()
-        .map(|constraint| {
-            // ensure constraint holds in the first row
-            // symbolically divide `(x - t_0)`
-            constraint / (X - first_trace_x)
-        });
-
-        let terminal_constraints = [
-            tables::ProcessorExtensionColumn::terminal_constraints(),
-            tables::InstructionExtensionColumn::terminal_constraints(),
-            tables::InputExtensionColumn::terminal_constraints(),
-            tables::OutputExtensionColumn::terminal_constraints(),
+        .map(|constraint| constraint.on_first_row(trace_len));
+
+        let terminal_constraints = tables::MemoryExtensionColumn::terminal_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_last_row(trace_len));
+
+        transition_constraints
+            .chain(boundary_constraints)
+            .chain(terminal_constraints)
+            .collect()
+    }
+
+    fn display_names() -> Names {
+        Names::new()
+            .columns(tables::MemoryBaseColumn::VARIANTS)
+            .columns(tables::MemoryExtensionColumn::VARIANTS)
+            .hints(MemoryHint::VARIANTS)
+    }
+}
+
+pub struct InstructionTable;
+
+impl BrainfuckTable for InstructionTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>> {
+        let transition_constraints = [
+            tables::InstructionBaseColumn::transition_constraints(),
+            tables::InstructionExtensionColumn::transition_constraints(),
         ]
         .into_iter()
         .flatten()
-        .map(|constraint| {
-            // ensure constraint holds in the last row
-            // symbolically divide `(x - t_(n-1))`
-            // NOTE: `t^(n-1) = t^(-1)`
-            constraint / (X - last_trace_x)
-        });
+        .map(|constraint| constraint.on_transition(trace_len));
 
-        BrainfuckAir {
-            options,
-            trace_info,
-            execution_info,
-            constraints: transition_constraints
-                .chain(boundary_constraints)
-                .chain(terminal_constraints)
-                .collect(),
-        }
+        let boundary_constraints = [
+            tables::InstructionBaseColumn::boundary_constraints(),
+            tables::InstructionExtensionColumn::boundary_constraints(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|constraint| constraint.on_first_row(trace_len));
+
+        let terminal_constraints = tables::InstructionExtensionColumn::terminal_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_last_row(trace_len));
+
+        transition_constraints
+            .chain(boundary_constraints)
+            .chain(terminal_constraints)
+            .collect()
     }
 
-    fn get_hints(&self, challenges: &Challenges<Self::Fq>) -> Hints<Self::Fq> {
-        use Challenge::*;
-        use EvaluationArgumentHint::*;
+    fn display_names() -> Names {
+        Names::new()
+            .columns(tables::InstructionBaseColumn::VARIANTS)
+            .columns(tables::InstructionExtensionColumn::VARIANTS)
+            .hints(InstructionHint::VARIANTS)
+    }
+}
 
-        let ExecutionInfo {
-            source_code,
-            input,
-            output,
-        } = &self.execution_info;
-        let trace_len = self.trace_info().trace_len;
+pub struct InputTable;
+
+impl BrainfuckTable for InputTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>> {
+        let transition_constraints = tables::InputExtensionColumn::transition_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_transition(trace_len));
+
+        let boundary_constraints = tables::InputExtensionColumn::boundary_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_first_row(trace_len));
+
+        let terminal_constraints = tables::InputExtensionColumn::terminal_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_last_row(trace_len));
+
+        transition_constraints
+            .chain(boundary_constraints)
+            .chain(terminal_constraints)
+            .collect()
+    }
+
+    fn display_names() -> Names {
+        Names::new()
+            .columns(tables::InputBaseColumn::VARIANTS)
+            .columns(tables::InputExtensionColumn::VARIANTS)
+            .hints(InputHint::VARIANTS)
+    }
+}
+
+pub struct OutputTable;
+
+impl BrainfuckTable for OutputTable {
+    fn constraints(trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq3>> {
+        let transition_constraints = tables::OutputExtensionColumn::transition_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_transition(trace_len));
+
+        let boundary_constraints = tables::OutputExtensionColumn::boundary_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_first_row(trace_len));
 
-        let (input_eval_arg, input_eval_offset) =
-            io_terminal_helper(input, challenges[Gamma.index()], trace_len);
-        let (output_eval_arg, output_eval_offset) =
-            io_terminal_helper(output, challenges[Delta.index()], trace_len);
-        let instruction_eval_arg = compute_instruction_evaluation_argument(source_code, challenges);
+        let terminal_constraints = tables::OutputExtensionColumn::terminal_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_last_row(trace_len));
 
-        Hints::new(vec![
-            (Instruction.index(), instruction_eval_arg),
-            (Input.index(), input_eval_arg),
-            (InputOffset.index(), input_eval_offset),
-            (Output.index(), output_eval_arg),
-            (OutputOffset.index(), output_eval_offset),
-        ])
+        transition_constraints
+            .chain(boundary_constraints)
+            .chain(terminal_constraints)
+            .collect()
+    }
+
+    fn display_names() -> Names {
+        Names::new()
+            .columns(tables::OutputBaseColumn::VARIANTS)
+            .columns(tables::OutputExtensionColumn::VARIANTS)
+            .hints(OutputHint::VARIANTS)
+    }
+}
+
+/// AIR of a single brainfuck table. Hints are provided by [BrainfuckAir].
+pub struct BrainfuckTableAir<T> {
+    options: ProofOptions,
+    trace_info: TraceInfo,
+    execution_info: ExecutionInfo,
+    constraints: Vec<AlgebraicExpression<Fp, Fq3>>,
+    _table: PhantomData<T>,
+}
+
+impl<T: BrainfuckTable> Air for BrainfuckTableAir<T> {
+    type Fp = Fp;
+    type Fq = Fq3;
+    type PublicInputs = ExecutionInfo;
+
+    fn new(trace_info: TraceInfo, execution_info: ExecutionInfo, options: ProofOptions) -> Self {
+        BrainfuckTableAir {
+            options,
+            constraints: T::constraints(trace_info.trace_len),
+            trace_info,
+            execution_info,
+            _table: PhantomData,
+        }
     }
 
     fn options(&self) -> &ProofOptions {
@@ -152,6 +256,160 @@ impl Air for BrainfuckAir {
     fn trace_info(&self) -> &TraceInfo {
         &self.trace_info
     }
+
+    fn display_names(&self) -> Names {
+        T::display_names().challenges(Challenge::VARIANTS)
+    }
+}
+
+/// Boxes the AIR of a table for [BrainfuckAir]
+fn table_air<T: BrainfuckTable + 'static>(
+    trace_info: TraceInfo,
+    execution_info: ExecutionInfo,
+    options: ProofOptions,
+) -> Box<TableAir<BrainfuckAir>> {
+    Box::new(BrainfuckTableAir::<T>::new(
+        trace_info,
+        execution_info,
+        options,
+    ))
+}
+
+pub struct BrainfuckAir {
+    options: ProofOptions,
+    execution_info: ExecutionInfo,
+    tables: Vec<Box<TableAir<Self>>>,
+}
+
+impl MultiAir for BrainfuckAir {
+    type Fp = Fp;
+    type Fq = Fq3;
+    type PublicInputs = ExecutionInfo;
+
+    fn id() -> &'static str {
+        "brainfuck"
+    }
+
+    fn new(
+        trace_infos: Vec<TraceInfo>,
+        execution_info: ExecutionInfo,
+        options: ProofOptions,
+    ) -> Self {
+        // in the order of `Table`
+        let table_airs = [
+            table_air::<ProcessorTable>,
+            table_air::<MemoryTable>,
+            table_air::<InstructionTable>,
+            table_air::<InputTable>,
+            table_air::<OutputTable>,
+        ];
+        let tables = trace_infos
+            .into_iter()
+            .zip(table_airs)
+            .map(|(trace_info, new_table)| new_table(trace_info, execution_info.clone(), options))
+            .collect();
+        BrainfuckAir {
+            options,
+            execution_info,
+            tables,
+        }
+    }
+
+    fn tables(&self) -> &[Box<TableAir<Self>>] {
+        &self.tables
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &self.execution_info
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    /// Running products of the processor's permutation arguments with the
+    /// instruction and memory tables
+    fn terminal_columns(&self) -> Vec<(usize, usize)> {
+        vec![
+            (
+                Table::Processor as usize,
+                tables::ProcessorExtensionColumn::InstructionPermutation.index(),
+            ),
+            (
+                Table::Processor as usize,
+                tables::ProcessorExtensionColumn::MemoryPermutation.index(),
+            ),
+        ]
+    }
+
+    fn get_hints(
+        &self,
+        table: usize,
+        challenges: &Challenges<Fq3>,
+        terminals: &[Fq3],
+    ) -> Hints<Fq3> {
+        use Challenge::*;
+
+        let ExecutionInfo {
+            source_code,
+            input,
+            output,
+        } = &self.execution_info;
+        let [instruction_permutation, memory_permutation] = terminals else {
+            unreachable!("brainfuck proofs have two terminals")
+        };
+        let trace_len = self.tables[table].trace_len();
+
+        match table {
+            t if t == Table::Processor as usize => {
+                let input_eval_arg = io_evaluation_argument(input, challenges[Gamma.index()]);
+                let output_eval_arg = io_evaluation_argument(output, challenges[Delta.index()]);
+                Hints::new(vec![
+                    (
+                        ProcessorHint::InstructionPermutation.index(),
+                        *instruction_permutation,
+                    ),
+                    (
+                        ProcessorHint::MemoryPermutation.index(),
+                        *memory_permutation,
+                    ),
+                    (ProcessorHint::Input.index(), input_eval_arg),
+                    (ProcessorHint::Output.index(), output_eval_arg),
+                ])
+            }
+            t if t == Table::Memory as usize => {
+                Hints::new(vec![(MemoryHint::Permutation.index(), *memory_permutation)])
+            }
+            t if t == Table::Instruction as usize => {
+                let program_eval_arg =
+                    compute_instruction_evaluation_argument(source_code, challenges);
+                Hints::new(vec![
+                    (
+                        InstructionHint::ProcessorPermutation.index(),
+                        *instruction_permutation,
+                    ),
+                    (InstructionHint::Program.index(), program_eval_arg),
+                ])
+            }
+            t if t == Table::Input as usize => {
+                let (eval_arg, offset) =
+                    io_terminal_helper(input, challenges[Gamma.index()], trace_len);
+                Hints::new(vec![
+                    (InputHint::Evaluation.index(), eval_arg),
+                    (InputHint::Offset.index(), offset),
+                ])
+            }
+            t if t == Table::Output as usize => {
+                let (eval_arg, offset) =
+                    io_terminal_helper(output, challenges[Delta.index()], trace_len);
+                Hints::new(vec![
+                    (OutputHint::Evaluation.index(), eval_arg),
+                    (OutputHint::Offset.index(), offset),
+                ])
+            }
+            _ => unreachable!("brainfuck proofs have five tables"),
+        }
+    }
 }
 
 // Computes the evaluation terminal for the instruction table
@@ -175,14 +433,19 @@ fn compute_instruction_evaluation_argument(source_code: &str, challenges: &Chall
     acc
 }
 
-// Computes the evaluation terminal for the input and output table
-// output is of the form `(evaluatoin_argument, evaluation_offset)`
-fn io_terminal_helper<F: Field>(symbols: &[u8], challenge: F, trace_len: usize) -> (F, F) {
+// Computes the evaluation argument of the input or output symbols
+fn io_evaluation_argument<F: Field>(symbols: &[u8], challenge: F) -> F {
     let mut acc = F::zero();
     for symbol in symbols {
         acc = challenge * acc + F::from(*symbol as u64);
     }
-    let evaluation_argument = acc;
+    acc
+}
+
+// Computes the evaluation terminal for the input and output table
+// output is of the form `(evaluatoin_argument, evaluation_offset)`
+fn io_terminal_helper<F: Field>(symbols: &[u8], challenge: F, trace_len: usize) -> (F, F) {
+    let evaluation_argument = io_evaluation_argument(symbols, challenge);
     // from BrainSTARK
     // In every additional row, the running evaluation variable is
     // multiplied by another `challenge` factor. So we multiply by
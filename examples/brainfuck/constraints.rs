@@ -1,28 +1,32 @@
 use crate::tables::Challenge;
-use crate::tables::EvaluationArgumentHint;
 use crate::tables::InputBaseColumn;
 use crate::tables::InputExtensionColumn;
+use crate::tables::InputHint;
 use crate::tables::InstructionBaseColumn;
 use crate::tables::InstructionExtensionColumn;
+use crate::tables::InstructionHint;
 use crate::tables::MemoryBaseColumn;
 use crate::tables::MemoryExtensionColumn;
+use crate::tables::MemoryHint;
 use crate::tables::OutputBaseColumn;
 use crate::tables::OutputExtensionColumn;
+use crate::tables::OutputHint;
 use crate::tables::ProcessorBaseColumn;
 use crate::tables::ProcessorExtensionColumn;
+use crate::tables::ProcessorHint;
 use crate::vm::OpCode;
 use ark_ff::FftField;
-use gpu_poly::GpuFftField;
 use ministark::constraints::AlgebraicExpression;
 use ministark::constraints::ExecutionTraceColumn;
 use ministark::constraints::FieldConstant;
 use ministark::constraints::Hint;
 use ministark::constraints::VerifierChallenge;
+use ministark::range_check::RangeCheck;
 use ministark::StarkExtensionOf;
 use std::borrow::Borrow;
 
 impl ProcessorBaseColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use ProcessorBaseColumn::*;
         vec![
@@ -35,7 +39,7 @@ impl ProcessorBaseColumn {
         ]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use ProcessorBaseColumn::*;
         let one = FieldConstant::Fp(Fp::one());
@@ -125,105 +129,46 @@ impl ProcessorBaseColumn {
     }
 }
 
+/// Checks the processor's memory values are bytes
+pub fn mem_val_range_check<Fp: FftField, Fq: StarkExtensionOf<Fp>>() -> RangeCheck<Fp, Fq> {
+    RangeCheck::new(
+        ProcessorBaseColumn::MemVal,
+        8,
+        ProcessorExtensionColumn::MemValTable,
+        Challenge::Zeta,
+    )
+}
+
 impl ProcessorExtensionColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use ProcessorExtensionColumn::*;
-        vec![InputEvaluation.curr(), OutputEvaluation.curr()]
+        let one = FieldConstant::Fp(Fp::one());
+        vec![
+            InstructionPermutation.curr() - one,
+            MemoryPermutation.curr() - one,
+            InputEvaluation.curr(),
+            OutputEvaluation.curr(),
+        ]
     }
 
-    pub fn terminal_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn terminal_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
-        use Challenge::Alpha;
-        use Challenge::Beta;
-        use Challenge::A;
-        use Challenge::B;
-        use Challenge::C;
-        use ProcessorBaseColumn::*;
         use ProcessorExtensionColumn::*;
-        let one = FieldConstant::Fp(Fp::one());
+        // the last row is always padding so the running products are complete
         vec![
             // instruction permutation:
-            // 1. instruction and processor are not padding
-            InstructionBaseColumn::CurrInstr.curr()
-                * (Dummy.curr() - one)
-                * (InstructionExtensionColumn::ProcessorPermutation.curr()
-                    * (Alpha.challenge()
-                        - A.challenge() * InstructionBaseColumn::Ip.curr()
-                        - B.challenge() * InstructionBaseColumn::CurrInstr.curr()
-                        - C.challenge() * InstructionBaseColumn::NextInstr.curr())
-                    - InstructionPermutation.curr()
-                        * (Alpha.challenge()
-                            - A.challenge() * Ip.curr()
-                            - B.challenge() * CurrInstr.curr()
-                            - C.challenge() * NextInstr.curr()))
-                // 2. instruction is padding but processor is not
-                + instr_zerofier(InstructionBaseColumn::CurrInstr.curr())
-                    * (Dummy.curr() - one)
-                    * (InstructionExtensionColumn::ProcessorPermutation.curr()
-                        - InstructionPermutation.curr()
-                            * (Alpha.challenge()
-                                - A.challenge() * Ip.curr()
-                                - B.challenge() * CurrInstr.curr()
-                                - C.challenge() * NextInstr.curr()))
-                // 3. processor is padding but instruction is not
-                + InstructionBaseColumn::CurrInstr.curr()
-                    * Dummy.curr()
-                    * (InstructionExtensionColumn::ProcessorPermutation.curr()
-                        * (Alpha.challenge()
-                            - A.challenge() * InstructionBaseColumn::Ip.curr()
-                            - B.challenge() * InstructionBaseColumn::CurrInstr.curr()
-                            - C.challenge() * InstructionBaseColumn::NextInstr.curr())
-                        - InstructionPermutation.curr())
-                // 4. processor and instruction are padding
-                + instr_zerofier(InstructionBaseColumn::CurrInstr.curr())
-                * Dummy.curr()
-                * (InstructionExtensionColumn::ProcessorPermutation.curr()
-                    - InstructionPermutation.curr()),
+            InstructionPermutation.curr() - ProcessorHint::InstructionPermutation.hint(),
             // memory permutation:
-            // 1. memory and processor are not padding
-            (MemoryBaseColumn::Dummy.curr() - one)
-                * (Dummy.curr() - one)
-                * (MemoryExtensionColumn::Permutation.curr()
-                    * (Beta.challenge()
-                        - Challenge::D.challenge() * MemoryBaseColumn::Cycle.curr()
-                        - Challenge::E.challenge() * MemoryBaseColumn::Mp.curr()
-                        - Challenge::F.challenge() * MemoryBaseColumn::MemVal.curr())
-                    - MemoryPermutation.curr()
-                        * (Beta.challenge()
-                            - Challenge::D.challenge() * Cycle.curr()
-                            - Challenge::E.challenge() * Mp.curr()
-                            - Challenge::F.challenge() * MemVal.curr()))
-                // 2. memory table is padding but processor table is not
-                + MemoryBaseColumn::Dummy.curr()
-                    * (Dummy.curr() - one)
-                    * (MemoryExtensionColumn::Permutation.curr()
-                        - MemoryPermutation.curr()
-                            * (Beta.challenge()
-                                - Challenge::D.challenge() * Cycle.curr()
-                                - Challenge::E.challenge() * Mp.curr()
-                                - Challenge::F.challenge() * MemVal.curr()))
-                // 3. processor is padding but memory table is not
-                + (MemoryBaseColumn::Dummy.curr() - one)
-                    * Dummy.curr()
-                    * (MemoryExtensionColumn::Permutation.curr()
-                        * (Beta.challenge()
-                            - Challenge::D.challenge() * MemoryBaseColumn::Cycle.curr()
-                            - Challenge::E.challenge() * MemoryBaseColumn::Mp.curr()
-                            - Challenge::F.challenge() * MemoryBaseColumn::MemVal.curr())
-                        - MemoryPermutation.curr())
-                // 4. processor and instruction are padding
-                + MemoryBaseColumn::Dummy.curr()
-                    * Dummy.curr()
-                    * (MemoryExtensionColumn::Permutation.curr() - MemoryPermutation.curr()),
+            MemoryPermutation.curr() - ProcessorHint::MemoryPermutation.hint(),
             // input evaluation:
-            InputEvaluation.curr() - EvaluationArgumentHint::Input.hint(),
+            InputEvaluation.curr() - ProcessorHint::Input.hint(),
             // output evaluation:
-            OutputEvaluation.curr() - EvaluationArgumentHint::Output.hint(),
+            OutputEvaluation.curr() - ProcessorHint::Output.hint(),
         ]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::Alpha;
         use Challenge::Beta;
@@ -253,8 +198,7 @@ impl ProcessorExtensionColumn {
                         - Challenge::E.challenge() * Mp.curr()
                         - Challenge::F.challenge() * MemVal.curr())
                     - MemoryPermutation.next())
-                * Dummy.curr()
-                * (MemoryPermutation.curr() - MemoryPermutation.next()),
+                + Dummy.curr() * (MemoryPermutation.curr() - MemoryPermutation.next()),
             // running evaluation for input tape
             CurrInstr.curr()
                 * if_not_instr(OpCode::Read, CurrInstr.curr())
@@ -276,13 +220,13 @@ impl ProcessorExtensionColumn {
 }
 
 impl MemoryBaseColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use MemoryBaseColumn::*;
         vec![Cycle.curr(), Mp.curr(), MemVal.curr()]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use MemoryBaseColumn::*;
         let one = FieldConstant::Fp(Fp::one());
@@ -309,7 +253,31 @@ impl MemoryBaseColumn {
 }
 
 impl MemoryExtensionColumn {
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    ) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        use MemoryExtensionColumn::*;
+        vec![Permutation.curr() - FieldConstant::Fp(Fp::one())]
+    }
+
+    pub fn terminal_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    ) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        use Challenge::Beta;
+        use MemoryBaseColumn::*;
+        use MemoryExtensionColumn::*;
+        // the last row is only in the running product if it isn't a dummy row
+        vec![
+            (Permutation.curr()
+                * (Beta.challenge()
+                    - Challenge::D.challenge() * Cycle.curr()
+                    - Challenge::E.challenge() * Mp.curr()
+                    - Challenge::F.challenge() * MemVal.curr())
+                - MemoryHint::Permutation.hint())
+                * (Dummy.curr() - FieldConstant::Fp(Fp::one()))
+                + (Permutation.curr() - MemoryHint::Permutation.hint()) * Dummy.curr(),
+        ]
+    }
+
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::Beta;
         use MemoryBaseColumn::*;
@@ -329,13 +297,13 @@ impl MemoryExtensionColumn {
 }
 
 impl InstructionBaseColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use InstructionBaseColumn::*;
         vec![Ip.curr()]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use InstructionBaseColumn::*;
         let one = FieldConstant::Fp(Fp::one());
@@ -361,7 +329,7 @@ impl InstructionBaseColumn {
 }
 
 impl InstructionExtensionColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::A;
         use Challenge::B;
@@ -369,6 +337,7 @@ impl InstructionExtensionColumn {
         use InstructionBaseColumn::*;
         use InstructionExtensionColumn::*;
         vec![
+            ProcessorPermutation.curr() - FieldConstant::Fp(Fp::one()),
             ProgramEvaluation.curr()
                 - A.challenge() * Ip.curr()
                 - B.challenge() * CurrInstr.curr()
@@ -376,13 +345,16 @@ impl InstructionExtensionColumn {
         ]
     }
 
-    pub fn terminal_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn terminal_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use InstructionExtensionColumn::*;
-        vec![ProgramEvaluation.curr() - EvaluationArgumentHint::Instruction.hint()]
+        vec![
+            ProcessorPermutation.curr() - InstructionHint::ProcessorPermutation.hint(),
+            ProgramEvaluation.curr() - InstructionHint::Program.hint(),
+        ]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::Alpha;
         use Challenge::Eta;
@@ -422,23 +394,20 @@ impl InstructionExtensionColumn {
 }
 
 impl InputExtensionColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use InputBaseColumn::*;
         use InputExtensionColumn::*;
         vec![Evaluation.curr() - Value.curr()]
     }
 
-    pub fn terminal_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn terminal_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use InputExtensionColumn::*;
-        vec![
-            Evaluation.curr()
-                - EvaluationArgumentHint::Input.hint() * EvaluationArgumentHint::InputOffset.hint(),
-        ]
+        vec![Evaluation.curr() - InputHint::Evaluation.hint() * InputHint::Offset.hint()]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::Gamma;
         use InputBaseColumn::*;
@@ -448,24 +417,20 @@ impl InputExtensionColumn {
 }
 
 impl OutputExtensionColumn {
-    pub fn boundary_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn boundary_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use OutputBaseColumn::*;
         use OutputExtensionColumn::*;
         vec![Evaluation.curr() - Value.curr()]
     }
 
-    pub fn terminal_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn terminal_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use OutputExtensionColumn::*;
-        vec![
-            Evaluation.curr()
-                - EvaluationArgumentHint::Output.hint()
-                    * EvaluationArgumentHint::OutputOffset.hint(),
-        ]
+        vec![Evaluation.curr() - OutputHint::Evaluation.hint() * OutputHint::Offset.hint()]
     }
 
-    pub fn transition_constraints<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    pub fn transition_constraints<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     ) -> Vec<AlgebraicExpression<Fp, Fq>> {
         use Challenge::Delta;
         use OutputBaseColumn::*;
@@ -474,7 +439,7 @@ impl OutputExtensionColumn {
     }
 }
 
-fn instr_zerofier<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+fn instr_zerofier<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     instr: impl Borrow<AlgebraicExpression<Fp, Fq>>,
 ) -> AlgebraicExpression<Fp, Fq> {
     OpCode::VALUES
@@ -485,7 +450,7 @@ fn instr_zerofier<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
 
 /// returns a polynomial in X that evaluates to 0 in all instructions except
 /// for one provided
-fn if_not_instr<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+fn if_not_instr<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     instr: OpCode,
     indeterminate: impl Borrow<AlgebraicExpression<Fp, Fq>>,
 ) -> AlgebraicExpression<Fp, Fq> {
@@ -501,7 +466,7 @@ fn if_not_instr<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
         .product()
 }
 
-fn if_instr<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+fn if_instr<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     instr: OpCode,
     indeterminate: impl Borrow<AlgebraicExpression<Fp, Fq>>,
 ) -> AlgebraicExpression<Fp, Fq> {
@@ -1,12 +1,13 @@
 #![feature(allocator_api)]
 
 use air::BrainfuckAir;
+use air::ExecutionInfo;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
-use ministark::Proof;
+use ministark::multi::MultiProof;
+use ministark::multi::MultiProver;
 use ministark::ProofOptions;
-use ministark::Prover;
-use ministark::Trace;
+use ministark::VerificationPolicy;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
@@ -23,6 +24,9 @@ mod tables;
 mod trace;
 mod vm;
 
+/// Proofs with less conjectured security than this are rejected
+const MIN_SECURITY_LEVEL: usize = 100;
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "BrainSTARK", about = "miniSTARK brainfuck prover and verifier")]
 enum BrainfuckOptions {
@@ -77,10 +81,19 @@ fn prove(options: ProofOptions, source_code_path: PathBuf, input: String, output
 
     let now = Instant::now();
     let trace = simulate(source_code, &mut input.as_bytes(), &mut output);
+    let tables = trace.tables();
     println!(
-        "Generated execution trace (cols={}, rows={}) in {:.0?}",
-        trace.base_columns().num_cols(),
-        trace.base_columns().num_rows(),
+        "Generated execution trace (tables={}, cols={}, rows={}) in {:.0?}",
+        tables.len(),
+        tables
+            .iter()
+            .map(|t| t.base_columns().num_cols())
+            .sum::<usize>(),
+        tables
+            .iter()
+            .map(|t| t.base_columns().num_rows())
+            .max()
+            .unwrap(),
         now.elapsed(),
     );
     println!(
@@ -88,8 +101,8 @@ fn prove(options: ProofOptions, source_code_path: PathBuf, input: String, output
         String::from_utf8(output.clone()).unwrap()
     );
 
-    let prover = prover::BrainfuckProver::new(options);
-    let proof = pollster::block_on(prover.generate_proof(trace));
+    let prover = prover::BrainfuckProver::with_meta(options, trace.meta());
+    let proof = pollster::block_on(prover.generate_proof(&tables));
     println!("Proof generated in: {:.0?}", now.elapsed());
     let proof = proof.unwrap();
     println!(
@@ -115,13 +128,17 @@ fn verify(
 ) {
     let source_code = fs::read_to_string(source_code_path).unwrap();
     let proof_bytes = fs::read(proof_path).unwrap();
-    let proof: Proof<BrainfuckAir> = Proof::deserialize_compressed(proof_bytes.as_slice()).unwrap();
-    assert_eq!(input.as_bytes(), proof.public_inputs.input);
-    assert_eq!(output.as_bytes(), proof.public_inputs.output);
-    assert_eq!(source_code, proof.public_inputs.source_code);
+    let proof: MultiProof<BrainfuckAir> =
+        MultiProof::deserialize_compressed(proof_bytes.as_slice()).unwrap();
     assert_eq!(options, proof.options);
+    let execution_info = ExecutionInfo {
+        source_code,
+        input: input.into_bytes(),
+        output: output.into_bytes(),
+    };
+    let policy = VerificationPolicy::new(MIN_SECURITY_LEVEL);
 
     let now = Instant::now();
-    proof.verify().unwrap();
+    proof.verify(&execution_info, &policy).unwrap();
     println!("Proof verified in: {:?}", now.elapsed());
 }
@@ -1,33 +1,45 @@
 use crate::air::BrainfuckAir;
 use crate::air::ExecutionInfo;
-use crate::trace::BrainfuckTrace;
+use crate::trace::TraceMeta;
 use gpu_poly::fields::p18446744069414584321::Fp;
 use gpu_poly::fields::p18446744069414584321::Fq3;
+use ministark::multi::MultiProver;
+use ministark::multi::TableTrace;
 use ministark::ProofOptions;
-use ministark::Prover;
+use sha2::Sha256;
 
-pub struct BrainfuckProver(ProofOptions);
+/// Proves the execution described by its [ExecutionInfo]. Table traces don't
+/// carry the source code or IO so the prover is given them up front.
+pub struct BrainfuckProver(ProofOptions, ExecutionInfo);
 
-impl Prover for BrainfuckProver {
+impl BrainfuckProver {
+    pub fn with_meta(options: ProofOptions, meta: &TraceMeta) -> Self {
+        BrainfuckProver(
+            options,
+            ExecutionInfo {
+                source_code: meta.source_code.to_string(),
+                input: meta.input.to_vec(),
+                output: meta.output.to_vec(),
+            },
+        )
+    }
+}
+
+impl MultiProver for BrainfuckProver {
     type Fp = Fp;
     type Fq = Fq3;
     type Air = BrainfuckAir;
-    type Trace = BrainfuckTrace;
+    type Digest = Sha256;
 
     fn new(options: ProofOptions) -> Self {
-        BrainfuckProver(options)
+        BrainfuckProver(options, ExecutionInfo::default())
     }
 
     fn options(&self) -> ProofOptions {
         self.0
     }
 
-    fn get_pub_inputs(&self, trace: &BrainfuckTrace) -> ExecutionInfo {
-        let meta = trace.meta();
-        ExecutionInfo {
-            source_code: meta.source_code.to_string(),
-            input: meta.input.to_vec(),
-            output: meta.output.to_vec(),
-        }
+    fn get_pub_inputs(&self, _traces: &[&dyn TableTrace<Fp, Fq3>]) -> ExecutionInfo {
+        self.1.clone()
     }
 }
@@ -1,10 +1,8 @@
-pub trait BrainfuckColumn {
-    const FIRST_TRACE_COL_INDEX: usize;
-    const LAST_TRACE_COL_INDEX: usize;
-    const NUM_TRACE_COLUMNS: usize = Self::LAST_TRACE_COL_INDEX - Self::FIRST_TRACE_COL_INDEX + 1;
-}
+use ministark::Challenge;
+use ministark::Column;
+use ministark::Hint;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Challenge)]
 pub enum Challenge {
     A,
     B,
@@ -17,30 +15,54 @@ pub enum Challenge {
     Gamma,
     Delta,
     Eta,
+    /// Challenge of the `MemVal` range check
+    Zeta,
 }
 
-impl ministark::constraints::VerifierChallenge for Challenge {
-    fn index(&self) -> usize {
-        *self as usize
-    }
-}
-
+/// Position of each table in a brainfuck proof
 #[derive(Clone, Copy)]
-pub enum EvaluationArgumentHint {
+pub enum Table {
+    Processor,
+    Memory,
     Instruction,
     Input,
-    InputOffset,
     Output,
-    OutputOffset,
 }
 
-impl ministark::constraints::Hint for EvaluationArgumentHint {
-    fn index(&self) -> usize {
-        *self as usize
-    }
+/// Hints of the processor table. The permutation hints are the terminals
+/// shared with the instruction and memory tables.
+#[derive(Clone, Copy, Hint)]
+pub enum ProcessorHint {
+    InstructionPermutation,
+    MemoryPermutation,
+    Input,
+    Output,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Hint)]
+pub enum MemoryHint {
+    Permutation,
+}
+
+#[derive(Clone, Copy, Hint)]
+pub enum InstructionHint {
+    ProcessorPermutation,
+    Program,
+}
+
+#[derive(Clone, Copy, Hint)]
+pub enum InputHint {
+    Evaluation,
+    Offset,
+}
+
+#[derive(Clone, Copy, Hint)]
+pub enum OutputHint {
+    Evaluation,
+    Offset,
+}
+
+#[derive(Clone, Copy, Column)]
 pub enum ProcessorBaseColumn {
     Cycle,
     Ip, // instruction pointer
@@ -52,15 +74,20 @@ pub enum ProcessorBaseColumn {
     Dummy, // indicate if a row is padding
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
+#[column(offset = ProcessorBaseColumn::LAST_TRACE_COL_INDEX + 1)]
 pub enum ProcessorExtensionColumn {
-    InstructionPermutation, // 18
+    InstructionPermutation,
     MemoryPermutation,
     InputEvaluation,
     OutputEvaluation,
+    // columns of the `MemVal` range check
+    MemValTable,
+    MemValMultiplicity,
+    MemValRunningSum,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
 pub enum MemoryBaseColumn {
     Cycle,
     Mp,
@@ -68,118 +95,45 @@ pub enum MemoryBaseColumn {
     Dummy,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
+#[column(offset = MemoryBaseColumn::LAST_TRACE_COL_INDEX + 1)]
 pub enum MemoryExtensionColumn {
     Permutation,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
 pub enum InstructionBaseColumn {
     Ip,
-    CurrInstr, // 13
+    CurrInstr,
     NextInstr,
     // Dummy, // indicate if a row is padding
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
+#[column(offset = InstructionBaseColumn::LAST_TRACE_COL_INDEX + 1)]
 pub enum InstructionExtensionColumn {
     ProcessorPermutation,
     ProgramEvaluation,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
 pub enum InputBaseColumn {
     Value,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
+#[column(offset = InputBaseColumn::LAST_TRACE_COL_INDEX + 1)]
 pub enum InputExtensionColumn {
     Evaluation,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
 pub enum OutputBaseColumn {
     Value,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Column)]
+#[column(offset = OutputBaseColumn::LAST_TRACE_COL_INDEX + 1)]
 pub enum OutputExtensionColumn {
     Evaluation,
 }
-
-impl BrainfuckColumn for ProcessorBaseColumn {
-    const FIRST_TRACE_COL_INDEX: usize = ProcessorBaseColumn::Cycle as usize;
-    const LAST_TRACE_COL_INDEX: usize = ProcessorBaseColumn::Dummy as usize;
-}
-
-impl BrainfuckColumn for MemoryBaseColumn {
-    const FIRST_TRACE_COL_INDEX: usize = ProcessorBaseColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Dummy as usize;
-}
-
-impl BrainfuckColumn for InstructionBaseColumn {
-    const FIRST_TRACE_COL_INDEX: usize = MemoryBaseColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::NextInstr as usize;
-}
-
-impl BrainfuckColumn for InputBaseColumn {
-    const FIRST_TRACE_COL_INDEX: usize = InstructionBaseColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Value as usize;
-}
-
-impl BrainfuckColumn for OutputBaseColumn {
-    const FIRST_TRACE_COL_INDEX: usize = InputBaseColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Value as usize;
-}
-
-impl BrainfuckColumn for ProcessorExtensionColumn {
-    const FIRST_TRACE_COL_INDEX: usize = OutputBaseColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize =
-        Self::FIRST_TRACE_COL_INDEX + Self::OutputEvaluation as usize;
-}
-
-impl BrainfuckColumn for MemoryExtensionColumn {
-    const FIRST_TRACE_COL_INDEX: usize = ProcessorExtensionColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Permutation as usize;
-}
-
-impl BrainfuckColumn for InstructionExtensionColumn {
-    const FIRST_TRACE_COL_INDEX: usize = MemoryExtensionColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize =
-        Self::FIRST_TRACE_COL_INDEX + Self::ProgramEvaluation as usize;
-}
-
-impl BrainfuckColumn for InputExtensionColumn {
-    const FIRST_TRACE_COL_INDEX: usize = InstructionExtensionColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Evaluation as usize;
-}
-
-impl BrainfuckColumn for OutputExtensionColumn {
-    const FIRST_TRACE_COL_INDEX: usize = InputExtensionColumn::LAST_TRACE_COL_INDEX + 1;
-    const LAST_TRACE_COL_INDEX: usize = Self::FIRST_TRACE_COL_INDEX + Self::Evaluation as usize;
-}
-
-macro_rules! impl_column {
-    ($t:ty) => {
-        impl ministark::constraints::ExecutionTraceColumn for $t {
-            fn index(&self) -> usize {
-                Self::FIRST_TRACE_COL_INDEX + *self as usize
-            }
-        }
-    };
-}
-
-impl_column!(ProcessorBaseColumn);
-impl_column!(ProcessorExtensionColumn);
-
-impl_column!(MemoryBaseColumn);
-impl_column!(MemoryExtensionColumn);
-
-impl_column!(InstructionBaseColumn);
-impl_column!(InstructionExtensionColumn);
-
-impl_column!(InputBaseColumn);
-impl_column!(InputExtensionColumn);
-
-impl_column!(OutputBaseColumn);
-impl_column!(OutputExtensionColumn);
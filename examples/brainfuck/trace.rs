@@ -1,4 +1,4 @@
-use crate::tables::BrainfuckColumn;
+use crate::constraints::mem_val_range_check;
 use crate::tables::Challenge;
 use crate::tables::InputBaseColumn;
 use crate::tables::InputExtensionColumn;
@@ -14,16 +14,14 @@ use crate::vm::OpCode;
 use ark_ff::Field;
 use ark_ff::One;
 use ark_ff::PrimeField;
-use ark_ff::UniformRand;
 use ark_ff::Zero;
-use ark_std::rand;
 use gpu_poly::allocator::PageAlignedAllocator;
 use gpu_poly::fields::p18446744069414584321::Fp;
 use gpu_poly::fields::p18446744069414584321::Fq3;
 use gpu_poly::GpuVec;
 use ministark::challenges::Challenges;
 use ministark::constraints::VerifierChallenge;
-// use ministark::constraint::Challenge as _;
+use ministark::multi::TableTrace;
 use ministark::Matrix;
 use ministark::Trace;
 
@@ -33,14 +31,68 @@ pub struct TraceMeta {
     pub source_code: String,
 }
 
+/// Defines the trace of a single brainfuck table from its base columns
+macro_rules! table_trace {
+    ($name:ident, $base:ident, $extension:ident, $gen_extension:ident) => {
+        pub struct $name(Matrix<Fp>);
+
+        impl Trace for $name {
+            type Fp = Fp;
+            type Fq = Fq3;
+
+            const NUM_BASE_COLUMNS: usize = $base::NUM_TRACE_COLUMNS;
+            const NUM_EXTENSION_COLUMNS: usize = $extension::NUM_TRACE_COLUMNS;
+
+            fn base_columns(&self) -> &Matrix<Fp> {
+                &self.0
+            }
+
+            fn build_extension_columns(&self, challenges: &Challenges<Fq3>) -> Option<Matrix<Fq3>> {
+                Some($gen_extension(challenges, &self.0))
+            }
+        }
+    };
+}
+
+table_trace!(
+    ProcessorTrace,
+    ProcessorBaseColumn,
+    ProcessorExtensionColumn,
+    gen_processor_ext_matrix
+);
+table_trace!(
+    MemoryTrace,
+    MemoryBaseColumn,
+    MemoryExtensionColumn,
+    gen_memory_ext_matrix
+);
+table_trace!(
+    InstructionTrace,
+    InstructionBaseColumn,
+    InstructionExtensionColumn,
+    gen_instruction_ext_matrix
+);
+table_trace!(
+    InputTrace,
+    InputBaseColumn,
+    InputExtensionColumn,
+    gen_input_ext_matrix
+);
+table_trace!(
+    OutputTrace,
+    OutputBaseColumn,
+    OutputExtensionColumn,
+    gen_output_ext_matrix
+);
+
+/// Traces of all the brainfuck tables. Each table is padded to its own length.
 pub struct BrainfuckTrace {
     meta: TraceMeta,
-    processor_base_trace: Matrix<Fp>,
-    memory_base_trace: Matrix<Fp>,
-    instruction_base_trace: Matrix<Fp>,
-    input_base_trace: Matrix<Fp>,
-    output_base_trace: Matrix<Fp>,
-    base_trace: Matrix<Fp>,
+    processor: ProcessorTrace,
+    memory: MemoryTrace,
+    instruction: InstructionTrace,
+    input: InputTrace,
+    output: OutputTrace,
 }
 
 impl BrainfuckTrace {
@@ -52,88 +104,40 @@ impl BrainfuckTrace {
         input_base_trace: Matrix<Fp>,
         output_base_trace: Matrix<Fp>,
     ) -> Self {
-        let base_trace = Matrix::join(vec![
-            processor_base_trace.clone(),
-            memory_base_trace.clone(),
-            instruction_base_trace.clone(),
-            input_base_trace.clone(),
-            output_base_trace.clone(),
-        ]);
         BrainfuckTrace {
             meta,
-            processor_base_trace,
-            memory_base_trace,
-            instruction_base_trace,
-            input_base_trace,
-            output_base_trace,
-            base_trace,
+            processor: ProcessorTrace(processor_base_trace),
+            memory: MemoryTrace(memory_base_trace),
+            instruction: InstructionTrace(instruction_base_trace),
+            input: InputTrace(input_base_trace),
+            output: OutputTrace(output_base_trace),
         }
     }
 
     pub fn meta(&self) -> &TraceMeta {
         &self.meta
     }
-}
 
-impl Trace for BrainfuckTrace {
-    type Fp = Fp;
-    type Fq = Fq3;
-
-    const NUM_BASE_COLUMNS: usize = 17;
-    const NUM_EXTENSION_COLUMNS: usize = 9;
-
-    fn build_extension_columns(
-        &self,
-        challenges: &Challenges<Self::Fq>,
-    ) -> Option<Matrix<Self::Fq>> {
-        let Self {
-            processor_base_trace,
-            memory_base_trace,
-            instruction_base_trace,
-            input_base_trace,
-            output_base_trace,
-            ..
-        } = self;
-
-        let mut rng = rand::thread_rng();
-        let instr_initial = Fq3::rand(&mut rng);
-        let mem_initial = Fq3::rand(&mut rng);
-
-        let processor_matrix =
-            gen_processor_ext_matrix(instr_initial, mem_initial, challenges, processor_base_trace);
-        let memory_matrix = gen_memory_ext_matrix(mem_initial, challenges, memory_base_trace);
-        let instruction_matrix =
-            gen_instruction_ext_matrix(instr_initial, challenges, instruction_base_trace);
-        let input_matrix = gen_input_ext_matrix(challenges, input_base_trace);
-        let output_matrix = gen_output_ext_matrix(challenges, output_base_trace);
-
-        Some(Matrix::join(vec![
-            processor_matrix,
-            memory_matrix,
-            instruction_matrix,
-            input_matrix,
-            output_matrix,
-        ]))
-    }
-
-    fn base_columns(&self) -> &Matrix<Self::Fp> {
-        &self.base_trace
+    /// Traces of the tables in the order of [Table](crate::tables::Table)
+    pub fn tables(&self) -> [&dyn TableTrace<Fp, Fq3>; 5] {
+        [
+            &self.processor,
+            &self.memory,
+            &self.instruction,
+            &self.input,
+            &self.output,
+        ]
     }
 }
 
-fn gen_processor_ext_matrix(
-    instruction_permutation_initial: Fq3,
-    memory_permutation_initial: Fq3,
-    challenges: &Challenges<Fq3>,
-    base_matrix: &Matrix<Fp>,
-) -> Matrix<Fq3> {
+fn gen_processor_ext_matrix(challenges: &Challenges<Fq3>, base_matrix: &Matrix<Fp>) -> Matrix<Fq3> {
     use Challenge::*;
     use ProcessorBaseColumn::*;
     use ProcessorExtensionColumn::*;
 
     // prepare
-    let mut instr_permutation_running_product = instruction_permutation_initial;
-    let mut mem_permutation_running_product = memory_permutation_initial;
+    let mut instr_permutation_running_product = Fq3::one();
+    let mut mem_permutation_running_product = Fq3::one();
     let mut input_running_evaluation = Fq3::zero();
     let mut output_running_evaluation = Fq3::zero();
 
@@ -142,7 +146,8 @@ fn gen_processor_ext_matrix(
     for row in 0..base_matrix.num_rows() {
         let curr_base_row = base_matrix.get_row(row).unwrap();
         let next_base_row = base_matrix.get_row(row + 1);
-        let mut extension_row = [Fq3::zero(); ProcessorExtensionColumn::NUM_TRACE_COLUMNS];
+        // the range check's columns are built separately
+        let mut extension_row = [Fq3::zero(); MemValTable as usize];
 
         // Permutations columns
         extension_row[InstructionPermutation as usize] = instr_permutation_running_product;
@@ -176,20 +181,18 @@ fn gen_processor_ext_matrix(
         extension_rows.push(extension_row);
     }
 
-    Matrix::new(into_columns(extension_rows))
+    let mut extension_matrix = Matrix::new(into_columns(extension_rows));
+    extension_matrix.append(mem_val_range_check().build_columns(base_matrix, challenges));
+    extension_matrix
 }
 
-fn gen_memory_ext_matrix(
-    memory_permutation_initial: Fq3,
-    challenges: &Challenges<Fq3>,
-    base_matrix: &Matrix<Fp>,
-) -> Matrix<Fq3> {
+fn gen_memory_ext_matrix(challenges: &Challenges<Fq3>, base_matrix: &Matrix<Fp>) -> Matrix<Fq3> {
     use Challenge::*;
     use MemoryBaseColumn::*;
     use MemoryExtensionColumn::*;
 
     // prepare
-    let mut mem_permutation_running_product = memory_permutation_initial;
+    let mut mem_permutation_running_product = Fq3::one();
 
     // loop over all rows
     let mut extension_rows = Vec::new();
@@ -210,7 +213,6 @@ fn gen_memory_ext_matrix(
 }
 
 fn gen_instruction_ext_matrix(
-    instruction_permutation_initial: Fq3,
     challenges: &Challenges<Fq3>,
     base_matrix: &Matrix<Fp>,
 ) -> Matrix<Fq3> {
@@ -219,7 +221,7 @@ fn gen_instruction_ext_matrix(
     use InstructionExtensionColumn::*;
 
     // prepare
-    let mut permutation_running_product = instruction_permutation_initial;
+    let mut permutation_running_product = Fq3::one();
     let mut evaluation_running_sum = Fq3::zero();
     let mut previous_address = -Fp::one();
 
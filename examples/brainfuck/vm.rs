@@ -1,4 +1,3 @@
-use crate::tables::BrainfuckColumn;
 use crate::tables::InputBaseColumn;
 use crate::tables::InstructionBaseColumn;
 use crate::tables::MemoryBaseColumn;
@@ -10,11 +9,10 @@ use crate::BrainfuckTrace;
 use ark_ff::Field;
 use ark_ff::One;
 use ark_ff::Zero;
+use gpu_poly::fields::p18446744069414584321::Fp;
 use ministark::Matrix;
 use ministark::TraceInfo;
 
-type Fp = <BrainfuckTrace as ministark::Trace>::Fp;
-
 /// Opcodes determined by the lexer
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OpCode {
@@ -246,26 +244,12 @@ pub fn simulate(
 
     let mut memory_rows = derive_memory_rows(&processor_rows);
 
-    let padding_len = {
-        let max_length = [
-            processor_rows.len(),
-            memory_rows.len(),
-            instruction_rows.len(),
-            input_rows.len(),
-            output_rows.len(),
-            TraceInfo::MIN_TRACE_LENGTH,
-        ]
-        .into_iter()
-        .max()
-        .unwrap();
-        ceil_power_of_two(max_length)
-    };
-
-    pad_processor_rows(&mut processor_rows, padding_len);
-    pad_memory_rows(&mut memory_rows, padding_len);
-    pad_instruction_rows(&mut instruction_rows, padding_len);
-    pad_input_rows(&mut input_rows, padding_len);
-    pad_output_rows(&mut output_rows, padding_len);
+    // each table is padded to its own length
+    pad_processor_rows(&mut processor_rows, padded_len(processor_rows.len()));
+    pad_memory_rows(&mut memory_rows, padded_len(memory_rows.len()));
+    pad_instruction_rows(&mut instruction_rows, padded_len(instruction_rows.len()));
+    pad_input_rows(&mut input_rows, padded_len(input_rows.len()));
+    pad_output_rows(&mut output_rows, padded_len(output_rows.len()));
 
     let processor_base_trace = Matrix::new(into_columns(processor_rows));
     let memory_base_trace = Matrix::new(into_columns(memory_rows));
@@ -390,11 +374,9 @@ fn derive_memory_rows(
     memory_rows
 }
 
-/// Rounds the input value up the the nearest power of two
-fn ceil_power_of_two(value: usize) -> usize {
-    if value.is_power_of_two() {
-        value
-    } else {
-        value.next_power_of_two()
-    }
+/// Rounds the number of rows in a table up to a valid trace length
+fn padded_len(num_rows: usize) -> usize {
+    num_rows
+        .max(TraceInfo::MIN_TRACE_LENGTH)
+        .next_power_of_two()
 }
@@ -1,9 +1,6 @@
 #![feature(allocator_api)]
 
 use ark_ff::One;
-use ark_poly::EvaluationDomain;
-use ark_poly::Radix2EvaluationDomain;
-use ark_serialize::CanonicalSerialize;
 use gpu_poly::allocator::PageAlignedAllocator;
 use gpu_poly::fields::p18446744069414584321::Fp;
 use ministark::constraints::AlgebraicExpression;
@@ -15,6 +12,8 @@ use ministark::ProofOptions;
 use ministark::Prover;
 use ministark::Trace;
 use ministark::TraceInfo;
+use ministark::VerificationPolicy;
+use sha2::Sha256;
 use std::time::Instant;
 
 struct FibTrace(Matrix<Fp>);
@@ -87,43 +86,24 @@ impl Air for FibAir {
     type Fq = Fp;
     type PublicInputs = Fp;
 
+    fn id() -> &'static str {
+        "fibonacci"
+    }
+
     fn new(trace_info: TraceInfo, public_input: Fp, options: ProofOptions) -> Self {
-        use AlgebraicExpression::*;
         let trace_len = trace_info.trace_len;
-        let trace_xs = Radix2EvaluationDomain::<Fp>::new(trace_len).unwrap();
-        // NOTE: =1
-        let first_trace_x = FieldConstant::Fp(trace_xs.element(0));
-        // NOTE: =trace_xs.group_gen_inv()
-        let last_trace_x = FieldConstant::Fp(trace_xs.element(trace_len - 1));
-
-        let boundary_constraints =
-            Self::generate_boundary_constraints()
-                .into_iter()
-                .map(|constraint| {
-                    // ensure constraint holds in the first row
-                    // symbolically divide `(x - t_0)`
-                    constraint / (X - first_trace_x)
-                });
-
-        let transition_constraints =
-            Self::generate_transition_constraints()
-                .into_iter()
-                .map(|constraint| {
-                    // ensure constraints hold in all rows except the last
-                    // multiply by `(x - t_(n-1))` to remove the last term
-                    // NOTE: `x^trace_len - 1 = (x - t_0)(x - t_1)...(x - t_(n-1))`
-                    // NOTE: `t^(n-1) = t^(-1)`
-                    constraint
-                        * ((X - last_trace_x) / (X.pow(trace_len) - FieldConstant::Fp(Fp::one())))
-                });
+
+        let boundary_constraints = Self::generate_boundary_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_first_row(trace_len));
+
+        let transition_constraints = Self::generate_transition_constraints()
+            .into_iter()
+            .map(|constraint| constraint.on_transition(trace_len));
 
         let terminal_constraints = Self::generate_terminal_constraints(public_input)
             .into_iter()
-            .map(|constraint| {
-                // ensure constraint holds in the last row
-                // symbolically divide `(x - t_0)`
-                constraint / (X - last_trace_x)
-            });
+            .map(|constraint| constraint.on_last_row(trace_len));
 
         FibAir {
             options,
@@ -160,6 +140,7 @@ impl Prover for FibProver {
     type Fq = Fp;
     type Air = FibAir;
     type Trace = FibTrace;
+    type Digest = Sha256;
 
     fn new(options: ProofOptions) -> Self {
         FibProver(options)
@@ -231,12 +212,14 @@ fn main() {
     let trace = gen_trace(1048576 * 32);
     println!("Trace generated in: {:?}", now.elapsed());
 
+    let pub_inputs = prover.get_pub_inputs(&trace);
     let now = Instant::now();
     let proof = pollster::block_on(prover.generate_proof(trace)).unwrap();
     println!("Proof generated in: {:?}", now.elapsed());
-    let mut proof_bytes = Vec::new();
-    proof.serialize_compressed(&mut proof_bytes).unwrap();
+    let proof_bytes = proof.to_bytes();
     println!("Result: {:?}", proof_bytes.len());
 
-    proof.verify().unwrap();
+    proof
+        .verify(&pub_inputs, &VerificationPolicy::new(0))
+        .unwrap();
 }
@@ -0,0 +1,205 @@
+//! Derive macros for the column, challenge and hint enums of an AIR.
+//!
+//! Each derive only applies to enums with unit variants. The `i`th variant
+//! maps to index `offset + i` where the offset defaults to zero and can be set
+//! with an attribute e.g. `#[column(offset = ProcessorBaseColumn::NUM_TRACE_COLUMNS)]`
+//! for the columns of a table that follow the columns of another table.
+//! Each derive also adds a `VARIANTS` array of all the variants in order e.g.
+//! for naming the columns of an AIR with `Names::columns`.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::parse_macro_input;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Expr;
+use syn::Fields;
+use syn::Ident;
+use syn::LitStr;
+use syn::Token;
+
+/// Implements `ExecutionTraceColumn` and `Display` for a column enum. Also
+/// adds `FIRST_TRACE_COL_INDEX`, `LAST_TRACE_COL_INDEX` and
+/// `NUM_TRACE_COLUMNS` constants. The offset is set with `#[column(offset =
+/// ...)]`.
+#[proc_macro_derive(Column, attributes(column))]
+pub fn derive_column(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, "column", |ident, offset, position, num_variants| {
+        quote! {
+            #[allow(dead_code)]
+            impl #ident {
+                pub const FIRST_TRACE_COL_INDEX: usize = #offset;
+                pub const LAST_TRACE_COL_INDEX: usize =
+                    Self::FIRST_TRACE_COL_INDEX + Self::NUM_TRACE_COLUMNS - 1;
+                pub const NUM_TRACE_COLUMNS: usize = #num_variants;
+            }
+
+            impl ::ministark::constraints::ExecutionTraceColumn for #ident {
+                fn index(&self) -> usize {
+                    Self::FIRST_TRACE_COL_INDEX + #position
+                }
+            }
+        }
+    })
+}
+
+/// Implements `VerifierChallenge` and `Display` for a challenge enum. Also adds
+/// a `NUM_CHALLENGES` constant. The offset is set with `#[challenge(offset =
+/// ...)]`.
+#[proc_macro_derive(Challenge, attributes(challenge))]
+pub fn derive_challenge(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(
+        &input,
+        "challenge",
+        |ident, offset, position, num_variants| {
+            quote! {
+                #[allow(dead_code)]
+                impl #ident {
+                    pub const NUM_CHALLENGES: usize = #num_variants;
+                }
+
+                impl ::ministark::constraints::VerifierChallenge for #ident {
+                    fn index(&self) -> usize {
+                        #offset + #position
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Implements `Hint` and `Display` for a hint enum. Also adds a `NUM_HINTS`
+/// constant. The offset is set with `#[hint(offset = ...)]`.
+#[proc_macro_derive(Hint, attributes(hint))]
+pub fn derive_hint(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, "hint", |ident, offset, position, num_variants| {
+        quote! {
+            #[allow(dead_code)]
+            impl #ident {
+                pub const NUM_HINTS: usize = #num_variants;
+            }
+
+            impl ::ministark::constraints::Hint for #ident {
+                fn index(&self) -> usize {
+                    #offset + #position
+                }
+            }
+        }
+    })
+}
+
+/// Generates the trait impls and `Display` impl of an enum. `impls` gets the
+/// enum's name, the offset expression, the position of `self` among the
+/// variants and the number of variants.
+fn expand(
+    input: &DeriveInput,
+    attr_name: &str,
+    impls: impl FnOnce(&Ident, TokenStream2, TokenStream2, usize) -> TokenStream2,
+) -> TokenStream {
+    try_expand(input, attr_name, impls)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn try_expand(
+    input: &DeriveInput,
+    attr_name: &str,
+    impls: impl FnOnce(&Ident, TokenStream2, TokenStream2, usize) -> TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let variants = unit_variants(input)?;
+    let offset = match offset(input, attr_name)? {
+        Some(offset) => quote!((#offset)),
+        None => quote!(0),
+    };
+    let ident = &input.ident;
+    let positions = 0..variants.len();
+    let position = quote! {
+        match self {
+            #(Self::#variants => #positions,)*
+        }
+    };
+    let names = variants
+        .iter()
+        .map(|variant| LitStr::new(&format!("{ident}::{variant}"), variant.span()));
+    let num_variants = variants.len();
+    let impls = impls(ident, offset, position, num_variants);
+    Ok(quote! {
+        #impls
+
+        #[allow(dead_code)]
+        impl #ident {
+            pub const VARIANTS: [Self; #num_variants] = [#(Self::#variants,)*];
+        }
+
+        impl ::core::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(match self {
+                    #(Self::#variants => #names,)*
+                })
+            }
+        }
+    })
+}
+
+/// Returns the variants of a non-generic enum with only unit variants
+fn unit_variants(input: &DeriveInput) -> syn::Result<Vec<Ident>> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new(Span::call_site(), "expected an enum")),
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic enums aren't supported",
+        ));
+    }
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "expected at least one variant",
+        ));
+    }
+    data.variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            Fields::Unit => Ok(variant.ident.clone()),
+            fields => Err(Error::new_spanned(fields, "expected a unit variant")),
+        })
+        .collect()
+}
+
+/// Parses the offset of an `#[<attr_name>(offset = ...)]` attribute
+fn offset(input: &DeriveInput, attr_name: &str) -> syn::Result<Option<Expr>> {
+    let mut offset = None;
+    for attr in &input.attrs {
+        if !attr.path.is_ident(attr_name) {
+            continue;
+        }
+        if offset.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate offset"));
+        }
+        offset = Some(attr.parse_args::<OffsetAttr>()?.0);
+    }
+    Ok(offset)
+}
+
+/// Arguments of an offset attribute i.e. `offset = <expr>`
+struct OffsetAttr(Expr);
+
+impl Parse for OffsetAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        if key != "offset" {
+            return Err(Error::new(key.span(), "expected `offset`"));
+        }
+        input.parse::<Token![=]>()?;
+        Ok(OffsetAttr(input.parse()?))
+    }
+}
@@ -1,33 +1,55 @@
 use crate::challenges::Challenges;
-use crate::composer::DeepCompositionCoeffs;
 use crate::constraints::AlgebraicExpression;
+#[cfg(feature = "prover")]
+use crate::degree;
+#[cfg(feature = "prover")]
+use crate::degree::ConstraintDegree;
+use crate::display::Names;
+use crate::hash::HashFn;
 use crate::hints::Hints;
+use crate::periodic::PeriodicColumn;
+#[cfg(feature = "prover")]
+use crate::prover::ProvingError;
 use crate::random::PublicCoin;
 use crate::utils;
+#[cfg(feature = "prover")]
+use crate::GpuStarkExtensionOf;
 use crate::ProofOptions;
 use crate::StarkExtensionOf;
 use crate::TraceInfo;
 use alloc::collections::BTreeSet;
 use alloc::vec::Vec;
 use ark_ff::FftField;
-use ark_ff::UniformRand;
+use ark_ff::Field;
 use ark_poly::EvaluationDomain;
 use ark_poly::Radix2EvaluationDomain;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
-use digest::Digest;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuFftField;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 pub trait Air {
-    type Fp: GpuFftField<FftField = Self::Fp> + FftField;
+    type Fp: FftField;
     type Fq: StarkExtensionOf<Self::Fp>;
     // TODO: consider removing clone requirement
     type PublicInputs: CanonicalSerialize + CanonicalDeserialize + Clone;
 
+    /// Identifier of the AIR that is recorded in serialized proofs. Defaults to
+    /// the type name which isn't guaranteed to be stable between compiler
+    /// versions so implementers should override it.
+    fn id() -> &'static str
+    where
+        Self: Sized,
+    {
+        core::any::type_name::<Self>()
+    }
+
     // TODO: could make this borrow info and options if so inclined
-    fn new(info: TraceInfo, inputs: Self::PublicInputs, options: ProofOptions) -> Self;
+    fn new(info: TraceInfo, inputs: Self::PublicInputs, options: ProofOptions) -> Self
+    where
+        Self: Sized;
 
     fn pub_inputs(&self) -> &Self::PublicInputs;
 
@@ -45,22 +67,44 @@ pub trait Air {
         len
     }
 
+    /// Number of random coefficients added to each trace polynomial when
+    /// zero-knowledge is enabled. Each trace polynomial is opened at the query
+    /// positions (and their offsets through the composition trace) as well as
+    /// the out-of-domain point so it needs at least that many random values.
+    fn num_trace_randomizers(&self) -> usize {
+        let options = self.options();
+        if !options.zero_knowledge {
+            return 0;
+        }
+
+        let num_offsets = self
+            .trace_arguments()
+            .into_iter()
+            .map(|(_, offset)| offset)
+            .collect::<BTreeSet<isize>>()
+            .len();
+        (options.num_queries as usize + 1) * (num_offsets + 1)
+    }
+
+    /// Degree of the trace polynomials
+    fn trace_degree(&self) -> usize {
+        self.trace_len() - 1 + self.num_trace_randomizers()
+    }
+
     /// Constraint evaluation blowup factor
     /// Must be a power of two.
     fn ce_blowup_factor(&self) -> usize {
-        let trace_degree = self.trace_len() - 1;
-        let ret = utils::ceil_power_of_two(
-            self.constraints()
-                .iter()
-                .map(|constraint| {
-                    let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
-                    numerator_degree - denominator_degree
-                })
-                .max()
-                // TODO: ceil_power_of_two might not be correct here. check the math
-                .map_or(0, |degree| utils::ceil_power_of_two(degree) / trace_degree),
-        );
-        ret
+        let trace_degree = self.trace_degree();
+        let max_degree = self
+            .constraints()
+            .iter()
+            .map(|constraint| {
+                let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
+                numerator_degree - denominator_degree
+            })
+            .max()
+            .unwrap_or(0);
+        utils::ce_blowup_factor(max_degree, self.trace_len())
     }
 
     /// Returns a degree that all constraints polynomials must be normalized to.
@@ -75,14 +119,69 @@ pub trait Air {
     }
 
     /// Validate properties of this air
-    fn validate(&self) {
+    #[cfg(feature = "prover")]
+    fn validate(&self) -> Result<(), ProvingError> {
+        let num_columns = self.trace_info().num_columns();
+        let periodic_columns = self.periodic_columns();
+        let num_periodic_columns = periodic_columns.len();
+        for (i, constraint) in self.constraints().iter().enumerate() {
+            let mut column_out_of_range = None;
+            let mut periodic_column_out_of_range = None;
+            constraint.traverse(&mut |node| match *node {
+                AlgebraicExpression::Trace(column, _) if column >= num_columns => {
+                    column_out_of_range.get_or_insert(column);
+                }
+                AlgebraicExpression::Periodic(column) if column >= num_periodic_columns => {
+                    periodic_column_out_of_range.get_or_insert(column);
+                }
+                _ => {}
+            });
+            if let Some(column) = column_out_of_range {
+                return Err(ProvingError::ColumnOutOfRange {
+                    constraint: i,
+                    column,
+                    num_columns,
+                });
+            }
+            if let Some(column) = periodic_column_out_of_range {
+                return Err(ProvingError::PeriodicColumnOutOfRange {
+                    constraint: i,
+                    column,
+                    num_periodic_columns,
+                });
+            }
+        }
+
+        let trace_len = self.trace_len();
+        for (column, periodic_column) in periodic_columns.iter().enumerate() {
+            let period = periodic_column.period();
+            if period > trace_len {
+                return Err(ProvingError::PeriodTooLong {
+                    column,
+                    period,
+                    trace_len,
+                });
+            }
+        }
+
         let ce_blowup_factor = self.ce_blowup_factor();
         let lde_blowup_factor = self.lde_blowup_factor();
-        assert!(
-            ce_blowup_factor <= lde_blowup_factor,
-            "constraint evaluation blowup factor {ce_blowup_factor} is 
-            larger than the lde blowup factor {lde_blowup_factor}"
-        );
+        if ce_blowup_factor > lde_blowup_factor {
+            return Err(ProvingError::ConstraintDegreeTooHigh {
+                ce_blowup_factor,
+                lde_blowup_factor,
+            });
+        }
+
+        let num_trace_randomizers = self.num_trace_randomizers();
+        if num_trace_randomizers >= trace_len {
+            return Err(ProvingError::TooManyTraceRandomizers {
+                num_trace_randomizers,
+                trace_len,
+            });
+        }
+
+        Ok(())
     }
 
     fn trace_domain(&self) -> Radix2EvaluationDomain<Self::Fp> {
@@ -109,7 +208,8 @@ pub trait Air {
     // TODO: consider changing back to borrow
     fn constraints(&self) -> Vec<AlgebraicExpression<Self::Fp, Self::Fq>>;
 
-    fn get_challenges(&self, public_coin: &mut PublicCoin<impl Digest>) -> Challenges<Self::Fq> {
+    /// Number of challenges referenced by the constraints
+    fn num_challenges(&self) -> usize {
         let mut num_challenges = 0;
         for constraint in self.constraints() {
             constraint.traverse(&mut |node| {
@@ -118,28 +218,40 @@ pub trait Air {
                 }
             })
         }
+        num_challenges
+    }
 
-        if num_challenges == 0 {
-            Challenges::default()
-        } else {
-            let mut rng = public_coin.draw_rng();
-            Challenges::new(&mut rng, num_challenges)
-        }
+    fn get_challenges(&self, public_coin: &mut PublicCoin<impl HashFn>) -> Challenges<Self::Fq>
+    where
+        Self: Sized,
+    {
+        draw_challenges(public_coin, self.num_challenges())
     }
 
     fn get_hints(&self, _challenges: &Challenges<Self::Fq>) -> Hints<Self::Fq> {
         Hints::default()
     }
 
+    /// Public columns referenced by [AlgebraicExpression::Periodic]
+    fn periodic_columns(&self) -> Vec<PeriodicColumn<Self::Fp>> {
+        Vec::new()
+    }
+
+    /// Names used by [AlgebraicExpression::display_with] when displaying
+    /// constraints e.g. in errors about unsatisfied constraints
+    fn display_names(&self) -> Names {
+        Names::default()
+    }
+
     // TODO: make this generic
     fn get_constraint_composition_coeffs(
         &self,
-        public_coin: &mut PublicCoin<impl Digest>,
-    ) -> Vec<(Self::Fq, Self::Fq)> {
-        let mut rng = public_coin.draw_rng();
-        (0..self.constraints().len())
-            .map(|_| (Self::Fq::rand(&mut rng), Self::Fq::rand(&mut rng)))
-            .collect()
+        public_coin: &mut PublicCoin<impl HashFn>,
+    ) -> Vec<(Self::Fq, Self::Fq)>
+    where
+        Self: Sized,
+    {
+        draw_constraint_composition_coeffs(public_coin, self.constraints().len())
     }
 
     fn trace_arguments(&self) -> BTreeSet<(usize, isize)> {
@@ -154,85 +266,40 @@ pub trait Air {
     /// degree_adjustment_coeffs)`
     fn get_deep_composition_coeffs(
         &self,
-        public_coin: &mut PublicCoin<impl Digest>,
-    ) -> DeepCompositionCoeffs<Self::Fq> {
-        let mut rng = public_coin.draw_rng();
-
-        // execution trace coeffs
-        let mut execution_trace_coeffs = Vec::new();
-        for _ in self.trace_arguments() {
-            execution_trace_coeffs.push(Self::Fq::rand(&mut rng));
-        }
-
-        // composition trace coeffs
-        let num_composition_trace_cols = self.ce_blowup_factor();
-        let mut composition_trace_coeffs = Vec::new();
-        for _ in 0..num_composition_trace_cols {
-            composition_trace_coeffs.push(Self::Fq::rand(&mut rng));
-        }
-
-        DeepCompositionCoeffs {
-            execution_trace: execution_trace_coeffs,
-            composition_trace: composition_trace_coeffs,
-            degree: (Self::Fq::rand(&mut rng), Self::Fq::rand(&mut rng)),
-        }
+        public_coin: &mut PublicCoin<impl HashFn>,
+    ) -> DeepCompositionCoeffs<Self::Fq>
+    where
+        Self: Sized,
+    {
+        let num_trace_arguments = self.trace_arguments().len();
+        draw_deep_composition_coeffs(public_coin, num_trace_arguments, self.ce_blowup_factor())
     }
+}
 
-    #[cfg(all(feature = "std", debug_assertions))]
+/// An [Air] whose fields have GPU implementations. The prover needs these but
+/// the verifier doesn't so they aren't bounds on [Air] itself.
+#[cfg(feature = "prover")]
+pub trait GpuAir: Air<Fp: GpuFftField, Fq: GpuStarkExtensionOf<<Self as Air>::Fp>> {
+    /// Checks every constraint holds over the trace domain. It's too slow for
+    /// release builds so the prover only calls it in debug builds.
+    #[cfg(debug_assertions)]
     fn validate_constraints(
         &self,
         challenges: &Challenges<Self::Fq>,
         hints: &Hints<Self::Fq>,
         base_trace: &crate::Matrix<Self::Fp>,
         extension_trace: Option<&crate::Matrix<Self::Fq>>,
-    ) {
+        preprocessed_trace: Option<&crate::Matrix<Self::Fp>>,
+    ) -> Result<(), ProvingError> {
         use crate::constraints::FieldConstant;
         use AlgebraicExpression::*;
 
         let trace_info = self.trace_info();
-        let num_execution_trace_columns =
-            trace_info.num_base_columns + trace_info.num_extension_columns;
-
-        let mut col_indicies = vec![false; num_execution_trace_columns];
-        let mut challenge_indicies = vec![false; challenges.len()];
-        let mut hint_indicies = vec![false; hints.len()];
-
-        for constraint in self.constraints() {
-            constraint.traverse(&mut |node| {
-                use AlgebraicExpression::*;
-                match node {
-                    Challenge(i) => challenge_indicies[*i] = true,
-                    Trace(i, _) => col_indicies[*i] = true,
-                    Hint(i) => hint_indicies[*i] = true,
-                    _ => {}
-                }
-            })
-        }
-
-        for (index, exists) in col_indicies.into_iter().enumerate() {
-            if !exists {
-                // TODO: make assertion
-                println!("WARN: no constraints for execution trace column {index}");
-            }
-        }
-
-        for (index, exists) in challenge_indicies.into_iter().enumerate() {
-            if !exists {
-                // TODO: make assertion
-                println!("WARN: challenge at index {index} never used");
-            }
-        }
-
-        for (index, exists) in hint_indicies.into_iter().enumerate() {
-            if !exists {
-                // TODO: make assertion
-                println!("WARN: hint at index {index} never used");
-            }
-        }
-
+        let periodic_columns = self.periodic_columns();
         let trace_domain = self.trace_domain();
         let base_column_range = trace_info.base_columns_range();
         let extension_column_range = trace_info.extension_columns_range();
+        let preprocessed_column_range = trace_info.preprocessed_columns_range();
 
         // helper function to get a value from the execution trace
         let get_trace_value = |row: usize, col: usize, offset: isize| {
@@ -242,6 +309,9 @@ pub trait Air {
             } else if extension_column_range.contains(&col) {
                 let col = col - trace_info.num_base_columns;
                 FieldConstant::Fq(extension_trace.unwrap().0[col][pos])
+            } else if preprocessed_column_range.contains(&col) {
+                let col = col - preprocessed_column_range.start;
+                FieldConstant::Fp(preprocessed_trace.unwrap().0[col][pos])
             } else {
                 unreachable!("requested column {col} does not exist")
             }
@@ -254,6 +324,7 @@ pub trait Air {
                         &FieldConstant::Fp(x),
                         &|i| FieldConstant::Fq(hints[i]),
                         &|i| FieldConstant::Fq(challenges[i]),
+                        &|i| FieldConstant::Fp(periodic_columns[i].value_at(row)),
                         &|col, offset| get_trace_value(row, col, offset),
                     )
                     .is_some();
@@ -268,6 +339,10 @@ pub trait Air {
                         )),
                         Challenge(i) => vals.push(format!("Challenge({i}) = {}", challenges[i])),
                         Hint(i) => vals.push(format!("Hint({i}) = {}", hints[i])),
+                        Periodic(i) => vals.push(format!(
+                            "Periodic({i}) = {}",
+                            periodic_columns[i].value_at(row)
+                        )),
 
                         // skip tree nodes
                         _ => (),
@@ -276,16 +351,175 @@ pub trait Air {
                     vals.sort();
                     vals.dedup();
 
-                    // TODO: display constraint? eprintln!("Constraint is:\n{constraint}\n");
                     #[cfg(feature = "std")]
                     eprint!("Constraint {c_idx} does not evaluate to a low degree polynomial. ");
                     #[cfg(feature = "std")]
                     eprintln!("Divide by zero occurs at row {row}.\n");
                     #[cfg(feature = "std")]
+                    eprintln!(
+                        "Constraint is:\n{}\n",
+                        constraint.display_with(&self.display_names())
+                    );
+                    #[cfg(feature = "std")]
                     eprintln!("Expression values:\n{}", vals.join("\n"));
-                    panic!();
+                    return Err(ProvingError::UnsatisfiedConstraint {
+                        constraint: c_idx,
+                        row,
+                    });
                 }
             }
         }
+
+        Ok(())
+    }
+
+    /// Returns the parts of the AIR that are likely mistakes. Exact constraint
+    /// degrees are found with [degree::analyze] so the trace must satisfy the
+    /// constraints.
+    fn warnings(
+        &self,
+        challenges: &Challenges<Self::Fq>,
+        hints: &Hints<Self::Fq>,
+        base_trace: &crate::Matrix<Self::Fp>,
+        extension_trace: Option<&crate::Matrix<Self::Fq>>,
+        preprocessed_trace: Option<&crate::Matrix<Self::Fp>>,
+    ) -> Result<Vec<AirWarning>, ProvingError> {
+        let mut column_indices = vec![false; self.trace_info().num_columns()];
+        let mut challenge_indices = vec![false; challenges.len()];
+        let mut hint_indices = vec![false; hints.len()];
+        for constraint in self.constraints() {
+            constraint.traverse(&mut |node| match *node {
+                AlgebraicExpression::Challenge(i) => challenge_indices[i] = true,
+                AlgebraicExpression::Trace(i, _) => column_indices[i] = true,
+                AlgebraicExpression::Hint(i) => hint_indices[i] = true,
+                _ => {}
+            })
+        }
+
+        let unused = |indices: Vec<bool>| {
+            indices
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, used)| (!used).then_some(i))
+        };
+        let mut warnings = Vec::new();
+        warnings.extend(
+            unused(column_indices).map(|column| AirWarning::UnconstrainedColumn { column }),
+        );
+        warnings.extend(
+            unused(challenge_indices).map(|challenge| AirWarning::UnusedChallenge { challenge }),
+        );
+        warnings.extend(unused(hint_indices).map(|hint| AirWarning::UnusedHint { hint }));
+
+        let trace_len = self.trace_len();
+        let degrees = degree::analyze(
+            self,
+            challenges,
+            hints,
+            base_trace,
+            extension_trace,
+            preprocessed_trace,
+        )?;
+        let mut symbolic_blowup_factor = 1;
+        let mut exact_blowup_factor = 1;
+        for (constraint, degree) in degrees.into_iter().enumerate() {
+            let symbolic = utils::ce_blowup_factor(degree.symbolic, trace_len);
+            let exact = utils::ce_blowup_factor(degree.exact, trace_len);
+            symbolic_blowup_factor = core::cmp::max(symbolic_blowup_factor, symbolic);
+            exact_blowup_factor = core::cmp::max(exact_blowup_factor, exact);
+            if degree.wastes_blowup(trace_len) {
+                warnings.push(AirWarning::OverestimatedDegree { constraint, degree });
+            }
+        }
+        if symbolic_blowup_factor > exact_blowup_factor {
+            warnings.push(AirWarning::WastedBlowup {
+                symbolic: symbolic_blowup_factor,
+                exact: exact_blowup_factor,
+            });
+        }
+
+        Ok(warnings)
+    }
+}
+
+/// Likely mistake in an [Air] found by [GpuAir::warnings]
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AirWarning {
+    /// No constraint references the execution trace column
+    UnconstrainedColumn { column: usize },
+    /// No constraint references the challenge
+    UnusedChallenge { challenge: usize },
+    /// No constraint references the hint
+    UnusedHint { hint: usize },
+    /// The symbolic degree bound of the constraint needs a larger constraint
+    /// evaluation blowup factor than its exact degree
+    OverestimatedDegree {
+        constraint: usize,
+        degree: ConstraintDegree,
+    },
+    /// Overestimated degrees increase the AIR's constraint evaluation blowup
+    /// factor from `exact` to `symbolic`
+    WastedBlowup { symbolic: usize, exact: usize },
+}
+
+#[cfg(feature = "prover")]
+impl<A: Air + ?Sized> GpuAir for A
+where
+    A::Fp: GpuFftField,
+    A::Fq: GpuStarkExtensionOf<A::Fp>,
+{
+}
+
+pub struct DeepCompositionCoeffs<F> {
+    /// Execution trace poly coefficients
+    pub execution_trace: Vec<F>,
+    /// Composition trace poly coefficients
+    pub composition_trace: Vec<F>,
+    /// Degree adjustment coefficients
+    pub degree: (F, F),
+}
+
+/// Draws `num_challenges` verifier challenges from the public coin
+pub(crate) fn draw_challenges<F: Field>(
+    public_coin: &mut PublicCoin<impl HashFn>,
+    num_challenges: usize,
+) -> Challenges<F> {
+    let challenges = (0..num_challenges).map(|_| public_coin.draw());
+    Challenges::from(challenges.collect::<Vec<F>>())
+}
+
+/// Draws a pair of random coefficients for each constraint
+pub(crate) fn draw_constraint_composition_coeffs<F: Field>(
+    public_coin: &mut PublicCoin<impl HashFn>,
+    num_constraints: usize,
+) -> Vec<(F, F)> {
+    (0..num_constraints)
+        .map(|_| (public_coin.draw(), public_coin.draw()))
+        .collect()
+}
+
+/// Draws the coefficients of the DEEP composition polynomial
+pub(crate) fn draw_deep_composition_coeffs<F: Field>(
+    public_coin: &mut PublicCoin<impl HashFn>,
+    num_trace_arguments: usize,
+    num_composition_trace_cols: usize,
+) -> DeepCompositionCoeffs<F> {
+    // execution trace coeffs
+    let mut execution_trace_coeffs = Vec::new();
+    for _ in 0..num_trace_arguments {
+        execution_trace_coeffs.push(public_coin.draw());
+    }
+
+    // composition trace coeffs
+    let mut composition_trace_coeffs = Vec::new();
+    for _ in 0..num_composition_trace_cols {
+        composition_trace_coeffs.push(public_coin.draw());
+    }
+
+    DeepCompositionCoeffs {
+        execution_trace: execution_trace_coeffs,
+        composition_trace: composition_trace_coeffs,
+        degree: (public_coin.draw(), public_coin.draw()),
     }
 }
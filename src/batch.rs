@@ -0,0 +1,152 @@
+use crate::hash::HashFn;
+use crate::verifier::serialize;
+use crate::verifier::VerifierContext;
+use crate::Air;
+use crate::Proof;
+use crate::VerificationError;
+use crate::VerificationKey;
+use crate::VerificationPolicy;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use sha2::Sha256;
+
+/// Result of verifying a batch of proofs
+#[derive(Debug, Default)]
+pub struct BatchVerificationReport {
+    /// Number of proofs in the batch
+    pub num_proofs: usize,
+    /// Position in the batch and reason of each proof that failed verification
+    pub failures: Vec<(usize, VerificationError)>,
+}
+
+impl BatchVerificationReport {
+    /// Returns true if every proof in the batch is valid
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Verifies many proofs of the same AIR.
+///
+/// Simplified constraints, domains and FRI parameters are computed once for
+/// each trace info, set of proof options and public inputs and reused for all
+/// later proofs that share them.
+pub struct BatchVerifier<A: Air, D: HashFn = Sha256> {
+    policy: VerificationPolicy,
+    key: Option<VerificationKey>,
+    contexts: BTreeMap<Vec<u8>, VerifierContext<A>>,
+    _digest: PhantomData<D>,
+}
+
+impl<A: Air, D: HashFn> BatchVerifier<A, D> {
+    pub fn new(policy: VerificationPolicy) -> Self {
+        BatchVerifier {
+            policy,
+            key: None,
+            contexts: BTreeMap::new(),
+            _digest: PhantomData,
+        }
+    }
+
+    /// Checks openings of preprocessed columns against the commitment in `key`
+    pub fn with_key(mut self, key: VerificationKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Verifies each proof against its expected public inputs
+    #[cfg(not(feature = "parallel"))]
+    pub fn verify(&mut self, proofs: &[(Proof<A, D>, A::PublicInputs)]) -> BatchVerificationReport {
+        self.build_contexts(proofs);
+        let failures = proofs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (proof, expected_public_inputs))| {
+                self.verify_proof(proof, expected_public_inputs)
+                    .err()
+                    .map(|err| (i, err))
+            })
+            .collect();
+
+        BatchVerificationReport {
+            num_proofs: proofs.len(),
+            failures,
+        }
+    }
+
+    /// Verifies each proof against its expected public inputs. Proofs are
+    /// verified in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn verify(&mut self, proofs: &[(Proof<A, D>, A::PublicInputs)]) -> BatchVerificationReport
+    where
+        A::PublicInputs: Send + Sync,
+        Proof<A, D>: Sync,
+        Self: Sync,
+    {
+        self.build_contexts(proofs);
+        let failures = proofs
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, (proof, expected_public_inputs))| {
+                self.verify_proof(proof, expected_public_inputs)
+                    .err()
+                    .map(|err| (i, err))
+            })
+            .collect();
+
+        BatchVerificationReport {
+            num_proofs: proofs.len(),
+            failures,
+        }
+    }
+
+    /// Builds the contexts upfront so the proofs can be verified in parallel
+    fn build_contexts(&mut self, proofs: &[(Proof<A, D>, A::PublicInputs)]) {
+        for (proof, expected_public_inputs) in proofs {
+            let key = context_key(proof, expected_public_inputs);
+            if self.contexts.contains_key(&key)
+                || proof
+                    .check_policy(expected_public_inputs, &self.policy)
+                    .is_err()
+            {
+                continue;
+            }
+            let air = A::new(
+                proof.trace_info.clone(),
+                expected_public_inputs.clone(),
+                proof.options,
+            );
+            self.contexts.insert(key, VerifierContext::new(&air));
+        }
+    }
+
+    fn verify_proof(
+        &self,
+        proof: &Proof<A, D>,
+        expected_public_inputs: &A::PublicInputs,
+    ) -> Result<(), VerificationError> {
+        proof.check_policy(expected_public_inputs, &self.policy)?;
+        let context = &self.contexts[&context_key(proof, expected_public_inputs)];
+        let air = A::new(
+            proof.trace_info.clone(),
+            expected_public_inputs.clone(),
+            proof.options,
+        );
+        proof.verify_with_context(&air, context, self.key.as_ref())
+    }
+}
+
+/// Proofs with the same key share a [VerifierContext]. Public inputs are part
+/// of the key since the constraints depend on them.
+fn context_key<A: Air, D: HashFn>(
+    proof: &Proof<A, D>,
+    expected_public_inputs: &A::PublicInputs,
+) -> Vec<u8> {
+    let mut key = serialize(&proof.trace_info);
+    key.extend(serialize(&proof.options));
+    key.extend(serialize(expected_public_inputs));
+    key
+}
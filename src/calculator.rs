@@ -4,7 +4,7 @@ use crate::constraints::AlgebraicExpression;
 use crate::constraints::EvaluationLde;
 use crate::constraints::FieldConstant;
 use crate::constraints::FieldType;
-use crate::Air;
+use crate::GpuAir;
 use crate::Matrix;
 use alloc::collections::BTreeMap;
 use alloc::rc::Rc;
@@ -29,7 +29,7 @@ use gpu_poly::stage::NegInPlaceStage;
 use gpu_poly::stage::NegIntoStage;
 use gpu_poly::utils::buffer_no_copy;
 
-pub fn lde_calculator<A: Air>(
+pub fn lde_calculator<A: GpuAir + ?Sized>(
     air: &A,
     expr: AlgebraicExpression<A::Fp, A::Fq>,
     hint: &impl Fn(usize) -> FieldConstant<A::Fp, A::Fq>,
@@ -37,7 +37,7 @@ pub fn lde_calculator<A: Air>(
     trace: &mut impl FnMut(usize) -> EvaluationLde<A::Fp, A::Fq>,
 ) -> Matrix<A::Fq> {
     use AlgebraicExpression::*;
-    let mut expr = expr.reuse_shared_nodes();
+    let mut expr = expr.simplify().reuse_shared_nodes();
     let library = &PLANNER.library;
     let command_queue = &PLANNER.command_queue;
     let device = command_queue.device();
@@ -49,6 +49,9 @@ pub fn lde_calculator<A: Air>(
 
     // temporary data structure for holding trace LDEs
     let mut trace_ldes = BTreeMap::new();
+    let periodic_columns = air.periodic_columns();
+    let mut periodic_ldes = BTreeMap::new();
+    let mut row_ldes = BTreeMap::new();
 
     // substitute LDEs, constants and restructure
     // TODO: expand on this
@@ -59,6 +62,26 @@ pub fn lde_calculator<A: Air>(
                 .or_insert_with(|| lde_cache.add_buffer(trace(*i)));
             *node = Lde(Rc::clone(lde), *j * ce_lde_step as isize)
         }
+        Periodic(i) => {
+            let lde = periodic_ldes.entry(*i).or_insert_with(|| {
+                // evaluations repeat so one period is expanded to the full LDE
+                let evals = periodic_columns[*i].evaluate_over(ce_domain, air.trace_len());
+                let mut periodic_lde = Vec::with_capacity_in(ce_lde_size, PageAlignedAllocator);
+                periodic_lde.extend(evals.iter().cycle().take(ce_lde_size));
+                let gpu_buffer = buffer_mut_no_copy(device, &mut periodic_lde);
+                lde_cache.add_buffer(EvaluationLde::Fp(periodic_lde, gpu_buffer))
+            });
+            *node = Lde(Rc::clone(lde), 0)
+        }
+        OnRows(rows) => {
+            let lde = row_ldes.entry(rows.clone()).or_insert_with(|| {
+                let mut rows_lde = Vec::with_capacity_in(ce_lde_size, PageAlignedAllocator);
+                rows_lde.extend(rows.evaluate_over(ce_domain));
+                let gpu_buffer = buffer_mut_no_copy(device, &mut rows_lde);
+                lde_cache.add_buffer(EvaluationLde::Fp(rows_lde, gpu_buffer))
+            });
+            *node = Lde(Rc::clone(lde), 0)
+        }
         Hint(i) => *node = Constant(hint(*i)),
         Challenge(i) => *node = Constant(challenge(*i)),
         Neg(a) => {
@@ -641,7 +664,25 @@ pub fn lde_calculator<A: Air>(
                             Lde(dst, *buff_offset)
                         }
                     }
-                    EvaluationLde::Fq(_, _buff) => todo!(),
+                    EvaluationLde::Fq(_, buff) => {
+                        // there are no extension field exponentiation or inverse stages.
+                        // Powers (e.g. merged by simplification) are multiplied out and
+                        // constraints with inverses are evaluated on the CPU instead.
+                        assert!(
+                            *e > 0,
+                            "extension field inverses can't be evaluated on the GPU"
+                        );
+                        if *e == 1 {
+                            Lde(Rc::clone(lde), *buff_offset)
+                        } else {
+                            let dst = lde_cache.get_buffer(FieldType::Fq);
+                            mul_into_fq.encode(command_buffer, dst.get_gpu_buffer(), buff, buff, 0);
+                            for _ in 2..*e {
+                                mul_assign_fq.encode(command_buffer, dst.get_gpu_buffer(), buff, 0);
+                            }
+                            Lde(dst, *buff_offset)
+                        }
+                    }
                 },
                 _ => unreachable!(),
             };
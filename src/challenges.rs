@@ -14,6 +14,12 @@ impl<F: Field> Challenges<F> {
     }
 }
 
+impl<F: Field> From<Vec<F>> for Challenges<F> {
+    fn from(challenges: Vec<F>) -> Self {
+        Challenges(challenges)
+    }
+}
+
 impl<F: Field> Deref for Challenges<F> {
     type Target = Vec<F>;
 
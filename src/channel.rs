@@ -1,19 +1,21 @@
 use crate::fri;
 use crate::fri::FriProof;
+use crate::hash::HashFn;
+use crate::prover::ProvingError;
+use crate::random::public_coin_seed;
 use crate::random::PublicCoin;
 use crate::trace::Queries;
-use crate::Air;
+use crate::GpuAir;
 use crate::Proof;
+use alloc::string::ToString;
 use alloc::vec::Vec;
-use ark_serialize::CanonicalSerialize;
-use ark_std::rand::Rng;
+use ark_ff::Field;
+use core::marker::PhantomData;
 use core::ops::Deref;
-use digest::Digest;
 use digest::Output;
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
+use gpu_poly::GpuField;
 
-pub struct ProverChannel<'a, A: Air, D: Digest> {
+pub struct ProverChannel<'a, A: GpuAir, D: HashFn> {
     air: &'a A,
     pub public_coin: PublicCoin<D>,
     base_trace_commitment: Output<D>,
@@ -25,18 +27,9 @@ pub struct ProverChannel<'a, A: Air, D: Digest> {
     pow_nonce: u64,
 }
 
-// impl<'a, A: Air, D: Digest> ProverChannel<'a, A, D> {
-impl<'a, A: Air, D: Digest> ProverChannel<'a, A, D> {
+impl<'a, A: GpuAir, D: HashFn> ProverChannel<'a, A, D> {
     pub fn new(air: &'a A) -> Self {
-        let mut seed = Vec::new();
-        // Seed the public coin with:
-        // 1. serialized public imputs
-        air.pub_inputs().serialize_compressed(&mut seed).unwrap();
-        // 2. various metadata about the air and proof
-        // TODO: field bytes?
-        air.trace_info().serialize_compressed(&mut seed).unwrap();
-        air.options().serialize_compressed(&mut seed).unwrap();
-        let public_coin = PublicCoin::<D>::new(&seed);
+        let public_coin = PublicCoin::<D>::new(&public_coin_seed::<A, D>(air));
         ProverChannel {
             air,
             public_coin,
@@ -50,6 +43,12 @@ impl<'a, A: Air, D: Digest> ProverChannel<'a, A, D> {
         }
     }
 
+    /// Binds the commitment to the preprocessed trace to the transcript. It's
+    /// given to the verifier ahead of time so it isn't included in the proof.
+    pub fn commit_preprocessed_trace(&mut self, commitment: &Output<D>) {
+        self.public_coin.reseed(&commitment.deref());
+    }
+
     pub fn commit_base_trace(&mut self, commitment: &Output<D>) {
         self.public_coin.reseed(&commitment.deref());
         self.base_trace_commitment = commitment.clone();
@@ -79,38 +78,28 @@ impl<'a, A: Air, D: Digest> ProverChannel<'a, A, D> {
         self.composition_trace_ood_evals = evals;
     }
 
-    pub fn grind_fri_commitments(&mut self) {
+    pub fn grind_fri_commitments(&mut self) -> Result<(), ProvingError> {
         let grinding_factor = self.air.options().grinding_factor as u32;
         if grinding_factor == 0 {
             // skip if there is no grinding required
-            return;
+            return Ok(());
         }
 
-        #[cfg(not(feature = "parallel"))]
-        let nonce = (1..u64::MAX)
-            .find(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor);
-
-        #[cfg(feature = "parallel")]
-        let nonce = (1..u64::MAX)
-            .into_par_iter()
-            .find_any(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor);
-
-        self.pow_nonce = nonce.expect("nonce not found");
+        let nonce = self.public_coin.grind(grinding_factor);
+        self.pow_nonce = nonce.ok_or(ProvingError::GrindingExhausted { grinding_factor })?;
         self.public_coin.reseed(&self.pow_nonce);
+        Ok(())
     }
 
     pub fn get_fri_query_positions(&mut self) -> Vec<usize> {
-        // TODO: voulnerability if multiple positions are the same
-        let num_queries = self.air.options().num_queries;
+        let num_queries = self.air.options().num_queries as usize;
         let lde_domain_size = self.air.trace_len() * self.air.lde_blowup_factor();
-        let mut rng = self.public_coin.draw_rng();
-        (0..num_queries)
-            .map(|_| rng.gen_range(0..lde_domain_size))
-            .collect()
+        self.public_coin.draw_queries(num_queries, lde_domain_size)
     }
 
-    pub fn build_proof(self, trace_queries: Queries<A>, fri_proof: FriProof<A::Fq>) -> Proof<A> {
+    pub fn build_proof(self, trace_queries: Queries<A>, fri_proof: FriProof<A::Fq>) -> Proof<A, D> {
         Proof {
+            hash_fn: D::NAME.to_string(),
             options: *self.air.options(),
             trace_info: self.air.trace_info().clone(),
             base_trace_commitment: self.base_trace_commitment.to_vec(),
@@ -122,12 +111,13 @@ impl<'a, A: Air, D: Digest> ProverChannel<'a, A, D> {
             pow_nonce: self.pow_nonce,
             fri_proof,
             trace_queries,
+            _digest: PhantomData,
         }
     }
 }
 
 // FRI prover channel implementation
-impl<'a, A: Air, D: Digest> fri::ProverChannel<A::Fq> for ProverChannel<'a, A, D> {
+impl<'a, A: GpuAir, D: HashFn> fri::ProverChannel<A::Fq> for ProverChannel<'a, A, D> {
     type Digest = D;
 
     fn commit_fri_layer(&mut self, commitment: &Output<D>) {
@@ -139,3 +129,16 @@ impl<'a, A: Air, D: Digest> fri::ProverChannel<A::Fq> for ProverChannel<'a, A, D
         self.public_coin.draw()
     }
 }
+
+// FRI prover channel for proofs that drive the public coin directly
+impl<F: GpuField + Field, D: HashFn> fri::ProverChannel<F> for PublicCoin<D> {
+    type Digest = D;
+
+    fn commit_fri_layer(&mut self, commitment: &Output<D>) {
+        self.reseed(&commitment.deref());
+    }
+
+    fn draw_fri_alpha(&mut self) -> F {
+        self.draw()
+    }
+}
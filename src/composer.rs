@@ -1,28 +1,150 @@
 use crate::challenges::Challenges;
 use crate::constraints::AlgebraicExpression;
+#[cfg(feature = "gpu")]
+use crate::constraints::EvaluationLde;
 use crate::constraints::FieldConstant;
+use crate::constraints::RowSet;
 use crate::hints::Hints;
-use crate::merkle::MerkleTree;
+use crate::prover::ProvingError;
 use crate::utils;
 use crate::utils::divide_out_point_into;
 use crate::utils::horner_evaluate;
-use crate::Air;
+use crate::GpuAir;
 use crate::Matrix;
+#[cfg(feature = "gpu")]
+use crate::StarkExtensionOf;
+use crate::TraceInfo;
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
+#[cfg(feature = "gpu")]
+use ark_ff::FftField;
 use ark_ff::Field;
 use ark_ff::Zero;
 use ark_poly::EvaluationDomain;
+#[cfg(feature = "gpu")]
+use core::ops::Range;
 use gpu_poly::prelude::*;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use sha2::Sha256;
 
-pub struct ConstraintComposer<'a, A: Air> {
+/// A column of the execution trace. Base and preprocessed columns are over
+/// `Fp` and extension columns are over `Fq`.
+#[derive(Clone, Copy)]
+enum TraceColumn<'a, Fp, Fq> {
+    Fp(&'a [Fp]),
+    Fq(&'a [Fq]),
+}
+
+/// Lists the base, extension and preprocessed columns in the order constraints
+/// index them. Fails if a segment doesn't have the width given in `trace_info`.
+fn trace_columns<'a, Fp: Field, Fq: Field>(
+    trace_info: &TraceInfo,
+    base_trace: &'a Matrix<Fp>,
+    extension_trace: Option<&'a Matrix<Fq>>,
+    preprocessed_trace: Option<&'a Matrix<Fp>>,
+) -> Result<Vec<TraceColumn<'a, Fp, Fq>>, ProvingError> {
+    let num_base_columns = base_trace.num_cols();
+    if num_base_columns != trace_info.num_base_columns {
+        return Err(ProvingError::BaseTraceWidth {
+            expected: trace_info.num_base_columns,
+            actual: num_base_columns,
+        });
+    }
+
+    let num_extension_columns = extension_trace.map_or(0, Matrix::num_cols);
+    if num_extension_columns != trace_info.num_extension_columns {
+        return Err(ProvingError::ExtensionTraceWidth {
+            expected: trace_info.num_extension_columns,
+            actual: num_extension_columns,
+        });
+    }
+
+    let num_preprocessed_columns = preprocessed_trace.map_or(0, Matrix::num_cols);
+    if num_preprocessed_columns != trace_info.num_preprocessed_columns {
+        return Err(ProvingError::PreprocessedTraceWidth {
+            expected: trace_info.num_preprocessed_columns,
+            actual: num_preprocessed_columns,
+        });
+    }
+
+    let base_columns = base_trace
+        .iter()
+        .map(|column| TraceColumn::Fp(column.as_slice()));
+    let extension_columns = extension_trace.into_iter().flat_map(|trace| {
+        trace
+            .iter()
+            .map(|column| TraceColumn::Fq(column.as_slice()))
+    });
+    let preprocessed_columns = preprocessed_trace.into_iter().flat_map(|trace| {
+        trace
+            .iter()
+            .map(|column| TraceColumn::Fp(column.as_slice()))
+    });
+    Ok(base_columns
+        .chain(extension_columns)
+        .chain(preprocessed_columns)
+        .collect())
+}
+
+/// Returns true if an expression inverts a non-constant expression over the
+/// extension field. The GPU has no extension field inverse stage so these
+/// expressions are evaluated on the CPU.
+#[cfg(feature = "gpu")]
+fn has_extension_field_inverse<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    expr: &AlgebraicExpression<Fp, Fq>,
+    trace_info: &TraceInfo,
+) -> bool {
+    /// Returns whether an expression is constant and whether it's over the
+    /// extension field
+    fn visit<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        expr: &AlgebraicExpression<Fp, Fq>,
+        extension_columns: &Range<usize>,
+        found: &mut bool,
+    ) -> (bool, bool) {
+        use AlgebraicExpression::*;
+        match expr {
+            X | Periodic(_) | OnRows(_) => (false, false),
+            Constant(constant) => (true, matches!(constant, FieldConstant::Fq(_))),
+            Challenge(_) | Hint(_) => (true, true),
+            Trace(column, _) => (false, extension_columns.contains(column)),
+            Lde(lde, _) => (false, matches!(**lde, EvaluationLde::Fq(..))),
+            Neg(a) => visit(&a.borrow(), extension_columns, found),
+            Exp(a, e) => {
+                let (is_constant, is_fq) = visit(&a.borrow(), extension_columns, found);
+                *found |= *e < 0 && !is_constant && is_fq;
+                (is_constant, is_fq)
+            }
+            Add(a, b) | Mul(a, b) => {
+                let (a_is_constant, a_is_fq) = visit(&a.borrow(), extension_columns, found);
+                let (b_is_constant, b_is_fq) = visit(&b.borrow(), extension_columns, found);
+                (a_is_constant && b_is_constant, a_is_fq || b_is_fq)
+            }
+        }
+    }
+
+    let mut found = false;
+    visit(expr, &trace_info.extension_columns_range(), &mut found);
+    found
+}
+
+/// Copies every `step`th row of an LDE
+fn reduced_lde_copy<F: Field>(lde: &Matrix<F>, step: usize) -> Matrix<F> {
+    let columns = ark_std::cfg_iter!(lde)
+        .map(|column| {
+            let mut reduced = Vec::with_capacity_in(column.len() / step, PageAlignedAllocator);
+            reduced.extend(column.iter().step_by(step).copied());
+            reduced
+        })
+        .collect();
+    Matrix::new(columns)
+}
+
+pub struct ConstraintComposer<'a, A: GpuAir + ?Sized> {
     air: &'a A,
     composition_coeffs: Vec<(A::Fq, A::Fq)>,
 }
 
-impl<'a, A: Air> ConstraintComposer<'a, A> {
+impl<'a, A: GpuAir + ?Sized> ConstraintComposer<'a, A> {
     pub fn new(air: &'a A, composition_coeffs: Vec<(A::Fq, A::Fq)>) -> Self {
         ConstraintComposer {
             air,
@@ -38,7 +160,8 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         hints: &Hints<A::Fq>,
         base_trace_lde: Matrix<A::Fp>,
         extension_trace_lde: Option<Matrix<A::Fq>>,
-    ) -> Matrix<A::Fq> {
+        preprocessed_trace_lde: Option<Matrix<A::Fp>>,
+    ) -> Result<Matrix<A::Fq>, ProvingError> {
         use crate::calculator::lde_calculator;
         use crate::constraints::EvaluationLde;
         let command_queue = &PLANNER.command_queue;
@@ -51,7 +174,15 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
             hints,
             &base_trace_lde,
             extension_trace_lde.as_ref(),
-        );
+            preprocessed_trace_lde.as_ref(),
+        )?;
+        #[cfg(not(debug_assertions))]
+        trace_columns(
+            self.air.trace_info(),
+            &base_trace_lde,
+            extension_trace_lde.as_ref(),
+            preprocessed_trace_lde.as_ref(),
+        )?;
 
         let mut trace_ldes = Vec::new();
 
@@ -65,12 +196,17 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
             trace_ldes.push(Some(EvaluationLde::Fq(lde, gpu_buffer)));
         }
 
+        for lde in preprocessed_trace_lde.into_iter().flatten() {
+            let gpu_buffer = buffer_no_copy(device, &lde);
+            trace_ldes.push(Some(EvaluationLde::Fp(lde, gpu_buffer)));
+        }
+
         let result = lde_calculator(
             self.air,
             composition_constraint,
             &|i| FieldConstant::Fq(hints[i]),
             &|i| FieldConstant::Fq(challenges[i]),
-            &mut |i| trace_ldes[i].take().unwrap(),
+            &mut |i| trace_ldes[i].take().expect("each column is only read once"),
         );
 
         #[cfg(debug_assertions)]
@@ -82,10 +218,9 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
                 assert_eq!(expected, actual, "mismatch at {i}");
             });
 
-        result
+        Ok(result)
     }
 
-    #[cfg(any(not(feature = "gpu"), debug_assertions))]
     pub fn evaluate_constraint_cpu(
         &self,
         composition_constraint: &AlgebraicExpression<A::Fp, A::Fq>,
@@ -93,7 +228,14 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         hints: &Hints<A::Fq>,
         base_trace_lde: &Matrix<A::Fp>,
         extension_trace_lde: Option<&Matrix<A::Fq>>,
-    ) -> Matrix<A::Fq> {
+        preprocessed_trace_lde: Option<&Matrix<A::Fp>>,
+    ) -> Result<Matrix<A::Fq>, ProvingError> {
+        let columns = trace_columns(
+            self.air.trace_info(),
+            base_trace_lde,
+            extension_trace_lde,
+            preprocessed_trace_lde,
+        )?;
         let ce_domain = self.air.ce_domain();
         let step = self.air.ce_blowup_factor() as isize;
         let xs = ce_domain.elements();
@@ -101,26 +243,42 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         let mut result = Vec::with_capacity_in(n, PageAlignedAllocator);
         result.resize(n, A::Fq::zero());
 
-        let trace_info = self.air.trace_info();
-        let base_columns_range = trace_info.base_columns_range();
-        let extension_columns_range = trace_info.extension_columns_range();
+        let mut periodic_evals = self
+            .air
+            .periodic_columns()
+            .iter()
+            .map(|column| column.evaluate_over(ce_domain, self.air.trace_len()))
+            .collect::<Vec<Vec<A::Fp>>>();
+
+        // the row vanishing polynomials are evaluated over the whole domain upfront
+        // and are then treated like extra periodic columns
+        let mut row_sets = BTreeMap::new();
+        let composition_constraint = composition_constraint.simplify();
+        let composition_constraint = composition_constraint.map(&mut |node| match node {
+            AlgebraicExpression::OnRows(rows) => {
+                let i = *row_sets.entry(rows).or_insert_with_key(|rows: &RowSet| {
+                    periodic_evals.push(rows.evaluate_over(ce_domain));
+                    periodic_evals.len() - 1
+                });
+                AlgebraicExpression::Periodic(i)
+            }
+            node => node,
+        });
 
         for (i, (v, x)) in result.iter_mut().zip(xs).enumerate() {
             let eval_result = composition_constraint.eval(
                 &FieldConstant::Fp(x),
                 &|h| FieldConstant::Fq(hints[h]),
                 &|c| FieldConstant::Fq(challenges[c]),
+                &|p| {
+                    let evals = &periodic_evals[p];
+                    FieldConstant::Fp(evals[i % evals.len()])
+                },
                 &|col_idx, offset| {
                     let position = (i as isize + step * offset).rem_euclid(n as isize) as usize;
-                    if base_columns_range.contains(&col_idx) {
-                        let column = &base_trace_lde[col_idx];
-                        FieldConstant::Fp(column[position])
-                    } else if extension_columns_range.contains(&col_idx) {
-                        let extension_column_offset = col_idx - trace_info.num_base_columns;
-                        let column = &extension_trace_lde.unwrap()[extension_column_offset];
-                        FieldConstant::Fq(column[position])
-                    } else {
-                        panic!("invalid column {col_idx}")
+                    match columns[col_idx] {
+                        TraceColumn::Fp(column) => FieldConstant::Fp(column[position]),
+                        TraceColumn::Fq(column) => FieldConstant::Fq(column[position]),
                     }
                 },
             );
@@ -131,7 +289,7 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
             };
         }
 
-        Matrix::new(vec![result])
+        Ok(Matrix::new(vec![result]))
     }
 
     pub fn evaluate(
@@ -140,9 +298,10 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         hints: &Hints<A::Fq>,
         mut base_trace_lde: Matrix<A::Fp>,
         mut extension_trace_lde: Option<Matrix<A::Fq>>,
-    ) -> Matrix<A::Fq> {
+        preprocessed_trace_lde: Option<&Matrix<A::Fp>>,
+    ) -> Result<Matrix<A::Fq>, ProvingError> {
         use AlgebraicExpression::*;
-        let trace_degree = self.air.trace_len() - 1;
+        let trace_degree = self.air.trace_degree();
         let composition_degree = self.air.composition_degree();
 
         // only a subset of the execution trace LDE evaluations need to be used for
@@ -151,7 +310,9 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         // TODO: reduce_lde_blowup_factor should take in an lde and output the reduced
         // LDE along with the now unused LDEs space. This will prevent having to
         // allocate memory buffers during constraint evaluation.
-        let lde_blowup_factor = self.air.lde_blowup_factor();
+        // the LDE can be larger than the AIR's own LDE domain when it's shared with
+        // longer traces e.g. in multi-table proofs
+        let lde_blowup_factor = base_trace_lde.num_rows() / self.air.trace_len();
         let ce_blowup_factor = self.air.ce_blowup_factor();
         ark_std::cfg_iter_mut!(base_trace_lde).for_each(|column| {
             utils::reduce_lde_blowup_factor(column, lde_blowup_factor, ce_blowup_factor)
@@ -163,54 +324,73 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
             });
         }
 
+        // the preprocessed trace is shared between proofs so it's copied rather
+        // than reduced in place
+        let preprocessed_trace_lde = preprocessed_trace_lde
+            .map(|lde| reduced_lde_copy(lde, lde_blowup_factor / ce_blowup_factor));
+
         // Constraint composition as in:
         // https://medium.com/starkware/starkdex-deep-dive-the-stark-core-engine-497942d0f0ab
+        // degree adjustments are for the simplified constraints to match the verifier
         let composition_constraint = self
             .air
             .constraints()
             .iter()
+            .map(AlgebraicExpression::simplify)
             .enumerate()
             .map(|(i, constraint)| {
                 let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
                 let evaluation_degree = numerator_degree - denominator_degree;
-                assert!(evaluation_degree <= composition_degree);
+                if evaluation_degree > composition_degree {
+                    return Err(ProvingError::ConstraintDegree {
+                        constraint: i,
+                        degree: evaluation_degree,
+                        max: composition_degree,
+                    });
+                }
                 let degree_adjustment = composition_degree - evaluation_degree;
                 let (alpha, beta) = self.composition_coeffs[i];
                 // TODO: would be nice to use Fp is Fq and Fp are the same
-                constraint
+                Ok(constraint
                     * (X.pow(degree_adjustment) * FieldConstant::Fq(alpha)
-                        + FieldConstant::Fq(beta))
+                        + FieldConstant::Fq(beta)))
             })
-            .sum::<AlgebraicExpression<A::Fp, A::Fq>>();
+            .sum::<Result<AlgebraicExpression<A::Fp, A::Fq>, ProvingError>>()?;
 
         #[cfg(feature = "gpu")]
-        return self.evaluate_constraint_gpu(
-            composition_constraint,
-            challenges,
-            hints,
-            base_trace_lde,
-            extension_trace_lde,
-        );
-        #[cfg(not(feature = "gpu"))]
-        return self.evaluate_constraint_cpu(
+        if !has_extension_field_inverse(&composition_constraint, self.air.trace_info()) {
+            return self.evaluate_constraint_gpu(
+                composition_constraint,
+                challenges,
+                hints,
+                base_trace_lde,
+                extension_trace_lde,
+                preprocessed_trace_lde,
+            );
+        }
+
+        self.evaluate_constraint_cpu(
             &composition_constraint,
             challenges,
             hints,
             &base_trace_lde,
             extension_trace_lde.as_ref(),
-        );
+            preprocessed_trace_lde.as_ref(),
+        )
     }
 
     fn trace_polys(&self, composed_evaluations: Matrix<A::Fq>) -> Matrix<A::Fq> {
-        assert_eq!(composed_evaluations.num_cols(), 1);
+        // invariant: constraints are composed into a single column of evaluations
+        debug_assert_eq!(composed_evaluations.num_cols(), 1);
         let composition_poly = composed_evaluations.into_polynomials(self.air.ce_domain());
         let num_composition_trace_cols = self.air.ce_blowup_factor();
         if num_composition_trace_cols == 1 {
             composition_poly
         } else {
+            let Matrix(mut columns) = composition_poly;
             Matrix::from_rows(
-                GpuVec::try_from(composition_poly)
-                    .unwrap()
+                columns
+                    .swap_remove(0)
                     .chunks(num_composition_trace_cols)
                     .map(|chunk| chunk.to_vec())
                     .collect(),
@@ -218,47 +398,54 @@ impl<'a, A: Air> ConstraintComposer<'a, A> {
         }
     }
 
-    /// builds a commitment to the composed trace polynomial.
-    /// Output is of the form `(lde, poly, lde_merkle_tree)`
-    pub fn build_commitment(
+    /// builds the composition trace polynomials
+    pub fn build_trace_polys(
         mut self,
         challenges: &Challenges<A::Fq>,
         hints: &Hints<A::Fq>,
         base_trace_lde: Matrix<A::Fp>,
         extension_trace_lde: Option<Matrix<A::Fq>>,
-    ) -> (Matrix<A::Fq>, Matrix<A::Fq>, MerkleTree<Sha256>) {
-        let composed_evaluations =
-            self.evaluate(challenges, hints, base_trace_lde, extension_trace_lde);
-        let composition_trace_polys = self.trace_polys(composed_evaluations);
-        let composition_trace_lde = composition_trace_polys.evaluate(self.air.lde_domain());
-        let merkle_tree = composition_trace_lde.commit_to_rows();
-        (composition_trace_lde, composition_trace_polys, merkle_tree)
+        preprocessed_trace_lde: Option<&Matrix<A::Fp>>,
+    ) -> Result<Matrix<A::Fq>, ProvingError> {
+        let composed_evaluations = self.evaluate(
+            challenges,
+            hints,
+            base_trace_lde,
+            extension_trace_lde,
+            preprocessed_trace_lde,
+        )?;
+        Ok(self.trace_polys(composed_evaluations))
     }
 }
 
-pub struct DeepPolyComposer<'a, A: Air> {
+pub struct DeepPolyComposer<'a, A: GpuAir + ?Sized> {
     z: A::Fq,
     air: &'a A,
-    base_trace_polys: &'a Matrix<A::Fp>,
-    extension_trace_polys: Option<&'a Matrix<A::Fq>>,
+    execution_trace_polys: Vec<TraceColumn<'a, A::Fp, A::Fq>>,
     composition_trace_polys: Matrix<A::Fq>,
 }
 
-impl<'a, A: Air> DeepPolyComposer<'a, A> {
+impl<'a, A: GpuAir + ?Sized> DeepPolyComposer<'a, A> {
     pub fn new(
         air: &'a A,
         z: A::Fq,
         base_trace_polys: &'a Matrix<A::Fp>,
         extension_trace_polys: Option<&'a Matrix<A::Fq>>,
+        preprocessed_trace_polys: Option<&'a Matrix<A::Fp>>,
         composition_trace_polys: Matrix<A::Fq>,
-    ) -> Self {
-        DeepPolyComposer {
-            z,
-            air,
+    ) -> Result<Self, ProvingError> {
+        let execution_trace_polys = trace_columns(
+            air.trace_info(),
             base_trace_polys,
             extension_trace_polys,
+            preprocessed_trace_polys,
+        )?;
+        Ok(DeepPolyComposer {
+            z,
+            air,
+            execution_trace_polys,
             composition_trace_polys,
-        }
+        })
     }
 
     /// Output is of the form `(execution_trace_evals, composition_trace_evals)`
@@ -266,8 +453,7 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
         let Self {
             z,
             air,
-            base_trace_polys,
-            extension_trace_polys,
+            execution_trace_polys,
             composition_trace_polys,
             ..
         } = self;
@@ -277,24 +463,12 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
         let g_inv = trace_domain.group_gen_inv();
 
         // generate ood evaluations for the execution trace polynomials
-        let trace_info = air.trace_info();
-        let base_columns_range = trace_info.base_columns_range();
-        let extension_columns_range = trace_info.extension_columns_range();
         let execution_trace_evals = ark_std::cfg_into_iter!(air.trace_arguments())
             .map(|(column, offset)| {
                 let x = *z * if offset >= 0 { g } else { g_inv }.pow([offset.abs() as u64]);
-                if base_columns_range.contains(&column) {
-                    let coeffs = &base_trace_polys[column];
-                    horner_evaluate(coeffs, &x)
-                } else if extension_columns_range.contains(&column) {
-                    let coeffs =
-                        &extension_trace_polys.unwrap()[column - trace_info.num_base_columns];
-                    horner_evaluate(coeffs, &x)
-                } else {
-                    panic!(
-                        "column is {column} but there are only {} columns",
-                        trace_info.num_base_columns + trace_info.num_extension_columns
-                    )
+                match execution_trace_polys[column] {
+                    TraceColumn::Fp(coeffs) => horner_evaluate(coeffs, &x),
+                    TraceColumn::Fq(coeffs) => horner_evaluate(coeffs, &x),
                 }
             })
             .collect();
@@ -308,25 +482,27 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
         (execution_trace_evals, composition_trace_evals)
     }
 
-    pub fn into_deep_poly(self, composition_coeffs: DeepCompositionCoeffs<A::Fq>) -> Matrix<A::Fq> {
+    /// Returns the random linear combination of the execution and composition
+    /// trace polynomials with their OOD evaluations divided out. The highest
+    /// coefficient is always zero.
+    pub fn into_quotients_sum(
+        self,
+        execution_trace_alphas: Vec<A::Fq>,
+        composition_trace_alphas: Vec<A::Fq>,
+    ) -> GpuVec<A::Fq> {
         let Self {
             z,
             air,
-            base_trace_polys,
-            extension_trace_polys,
+            execution_trace_polys,
             composition_trace_polys,
             ..
         } = self;
 
-        let DeepCompositionCoeffs {
-            execution_trace: execution_trace_alphas,
-            composition_trace: composition_trace_alphas,
-            degree: (degree_alpha, degree_beta),
-        } = composition_coeffs;
-
         let trace_domain = air.trace_domain();
         let g = trace_domain.group_gen();
         let g_inv = trace_domain.group_gen_inv();
+        // randomized trace polynomials have more coefficients than the trace length
+        let num_coeffs = utils::ceil_power_of_two(air.trace_degree() + 1);
 
         // divide out OOD point from composition trace polys
         let z_n = self.z.pow([composition_trace_polys.num_cols() as u64]);
@@ -335,7 +511,7 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
                 .zip(composition_trace_alphas)
                 .map(|(coeffs, alpha)| {
                     let mut res = Vec::new_in(PageAlignedAllocator);
-                    res.resize(trace_domain.size(), A::Fq::zero());
+                    res.resize(num_coeffs, A::Fq::zero());
                     divide_out_point_into(&mut res, &coeffs, &z_n, &alpha);
                     res
                 })
@@ -343,9 +519,6 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
         );
 
         // divide out OOD points from execution trace polys
-        let trace_info = air.trace_info();
-        let base_columns_range = trace_info.base_columns_range();
-        let extension_columns_range = trace_info.extension_columns_range();
         // NOTE: ark_std::cfg_into_iter! doesn't work with
         // .zip() on BTreeSet but works with Vec.
         #[allow(clippy::needless_collect)]
@@ -355,20 +528,15 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
                 .zip(execution_trace_alphas)
                 .map(|((col, offset), alpha)| {
                     let mut res = Vec::new_in(PageAlignedAllocator);
-                    res.resize(trace_domain.size(), A::Fq::zero());
+                    res.resize(num_coeffs, A::Fq::zero());
                     let x = z * if offset >= 0 { g } else { g_inv }.pow([offset.abs() as u64]);
-                    if base_columns_range.contains(&col) {
-                        let coeffs = &base_trace_polys[col];
-                        divide_out_point_into(&mut res, coeffs, &x, &alpha);
-                    } else if extension_columns_range.contains(&col) {
-                        let coeffs =
-                            &extension_trace_polys.unwrap()[col - trace_info.num_base_columns];
-                        divide_out_point_into(&mut res, coeffs, &x, &alpha);
-                    } else {
-                        panic!(
-                            "column is {col} but there are only {} columns",
-                            trace_info.num_base_columns + trace_info.num_extension_columns
-                        )
+                    match execution_trace_polys[col] {
+                        TraceColumn::Fp(coeffs) => {
+                            divide_out_point_into(&mut res, coeffs, &x, &alpha)
+                        }
+                        TraceColumn::Fq(coeffs) => {
+                            divide_out_point_into(&mut res, coeffs, &x, &alpha)
+                        }
                     }
                     res
                 })
@@ -376,27 +544,9 @@ impl<'a, A: Air> DeepPolyComposer<'a, A> {
         );
 
         let quotients = Matrix::join(vec![execution_trace_quotients, composition_trace_quotients]);
-        let mut combined_coeffs = GpuVec::try_from(quotients.sum_columns()).unwrap();
-
-        // Adjust the degree
-        // P(x) * (alpha + x * beta)
-        let mut last = A::Fq::zero();
-        for coeff in &mut combined_coeffs {
-            let tmp = *coeff;
-            *coeff *= degree_alpha;
-            *coeff += last * degree_beta;
-            last = tmp;
-        }
-
-        Matrix::new(vec![combined_coeffs])
+        // invariant: summing columns always gives a single column
+        let Matrix(mut sum) = quotients.sum_columns();
+        debug_assert_eq!(sum.len(), 1);
+        sum.swap_remove(0)
     }
 }
-
-pub struct DeepCompositionCoeffs<F> {
-    /// Execution trace poly coefficients
-    pub execution_trace: Vec<F>,
-    /// Composition trace poly coefficients
-    pub composition_trace: Vec<F>,
-    /// Degree adjustment coefficients
-    pub degree: (F, F),
-}
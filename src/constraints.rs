@@ -1,12 +1,19 @@
 // Implementation is adapted from RationalExpression in https://github.com/0xProject/OpenZKP
 
+use crate::display::ExpressionDisplay;
+use crate::display::Format;
+use crate::display::Names;
 use crate::StarkExtensionOf;
 use alloc::collections::BTreeMap;
 use alloc::collections::BTreeSet;
 use alloc::rc::Rc;
 use alloc::vec::Vec;
+use ark_ff::batch_inversion;
 use ark_ff::FftField;
 use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use ark_std::One;
 use ark_std::Zero;
 use core::cell::RefCell;
 use core::fmt::Display;
@@ -24,7 +31,6 @@ use core::ops::Neg;
 use core::ops::Sub;
 use core::ops::SubAssign;
 use digest::Digest;
-use gpu_poly::GpuFftField;
 use sha2::Sha256;
 
 fn from_bytes<F: Field>(bytes: &[u8]) -> F {
@@ -39,9 +45,7 @@ fn from_bytes<F: Field>(bytes: &[u8]) -> F {
 pub trait Hint {
     fn index(&self) -> usize;
 
-    fn hint<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
-        &self,
-    ) -> AlgebraicExpression<Fp, Fq> {
+    fn hint<Fp: FftField, Fq: StarkExtensionOf<Fp>>(&self) -> AlgebraicExpression<Fp, Fq> {
         AlgebraicExpression::Hint(self.index())
     }
 }
@@ -52,15 +56,29 @@ impl Hint for usize {
     }
 }
 
+/// An interface for types that can symbolically represent a periodic column
+pub trait Periodic {
+    /// Returns the index of the periodic column in [crate::Air::periodic_columns]
+    fn index(&self) -> usize;
+
+    fn periodic<Fp: FftField, Fq: StarkExtensionOf<Fp>>(&self) -> AlgebraicExpression<Fp, Fq> {
+        AlgebraicExpression::Periodic(self.index())
+    }
+}
+
+impl Periodic for usize {
+    fn index(&self) -> usize {
+        *self
+    }
+}
+
 pub trait VerifierChallenge {
     /// Get the challenge index
     fn index(&self) -> usize;
 
     /// Symbolic representation of a challenge
     // TODO: terrible name. Needs refactoring
-    fn challenge<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
-        &self,
-    ) -> AlgebraicExpression<Fp, Fq> {
+    fn challenge<Fp: FftField, Fq: StarkExtensionOf<Fp>>(&self) -> AlgebraicExpression<Fp, Fq> {
         AlgebraicExpression::Challenge(self.index())
     }
 }
@@ -78,20 +96,16 @@ pub trait ExecutionTraceColumn {
     fn index(&self) -> usize;
 
     // Create a constraint element for the current cycle
-    fn curr<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
-        &self,
-    ) -> AlgebraicExpression<Fp, Fq> {
+    fn curr<Fp: FftField, Fq: StarkExtensionOf<Fp>>(&self) -> AlgebraicExpression<Fp, Fq> {
         self.offset(0)
     }
 
     // Create a constraint element for the next cycle
-    fn next<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
-        &self,
-    ) -> AlgebraicExpression<Fp, Fq> {
+    fn next<Fp: FftField, Fq: StarkExtensionOf<Fp>>(&self) -> AlgebraicExpression<Fp, Fq> {
         self.offset(1)
     }
 
-    fn offset<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+    fn offset<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
         &self,
         offset: isize,
     ) -> AlgebraicExpression<Fp, Fq> {
@@ -114,13 +128,13 @@ macro_rules! map {
     }
 }
 
-#[derive(Clone, Copy, Debug, Hash)]
-pub enum FieldConstant<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldConstant<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
     Fp(Fp),
     Fq(Fq),
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> FieldConstant<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> FieldConstant<Fp, Fq> {
     /// Computes the multiplicative inverse of `self` if `self` is nonzero.
     pub fn inverse(&self) -> Option<Self> {
         match self {
@@ -143,7 +157,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> FieldConstant<Fp, Fq>
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Display for FieldConstant<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Display for FieldConstant<Fp, Fq> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             FieldConstant::Fp(v) => Display::fmt(v, f),
@@ -152,7 +166,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Display for FieldCons
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Zero for FieldConstant<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Zero for FieldConstant<Fp, Fq> {
     fn zero() -> Self {
         FieldConstant::Fp(Fp::zero())
     }
@@ -165,9 +179,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Zero for FieldConstan
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp, Fq>>
-    for FieldConstant<Fp, Fq>
-{
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp, Fq>> for FieldConstant<Fp, Fq> {
     type Output = FieldConstant<Fp, Fq>;
 
     fn add(self, rhs: FieldConstant<Fp, Fq>) -> Self::Output {
@@ -180,9 +192,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp, Fq>>
-    for FieldConstant<Fp, Fq>
-{
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp, Fq>> for FieldConstant<Fp, Fq> {
     type Output = FieldConstant<Fp, Fq>;
 
     fn mul(self, rhs: FieldConstant<Fp, Fq>) -> Self::Output {
@@ -195,7 +205,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Neg for FieldConstant<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Neg for FieldConstant<Fp, Fq> {
     type Output = FieldConstant<Fp, Fq>;
 
     fn neg(self) -> Self::Output {
@@ -226,12 +236,169 @@ impl<Fp: gpu_poly::GpuField, Fq: gpu_poly::GpuField> EvaluationLde<Fp, Fq> {
     }
 }
 
+/// A set of rows of an execution trace with `trace_len` rows
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RowSet {
+    /// Every row except the last
+    Transition { trace_len: usize },
+    /// Rows `offset`, `offset + step`, `offset + 2 * step`, ...
+    EveryNth {
+        trace_len: usize,
+        step: usize,
+        offset: usize,
+    },
+    /// An arbitrary set of rows
+    Rows { trace_len: usize, rows: Vec<usize> },
+}
+
+impl RowSet {
+    pub fn trace_len(&self) -> usize {
+        match self {
+            RowSet::Transition { trace_len }
+            | RowSet::EveryNth { trace_len, .. }
+            | RowSet::Rows { trace_len, .. } => *trace_len,
+        }
+    }
+
+    /// Degree in X of `1/Z(x)` where `Z` is the polynomial that vanishes on the
+    /// rows. Output is of the form `(numerator_degree, denominator_degree)`
+    pub fn degree(&self) -> (usize, usize) {
+        match self {
+            // (x - g^(n-1)) / (x^n - 1)
+            RowSet::Transition { trace_len } => (1, *trace_len),
+            // 1 / (x^(n/step) - g^(offset*n/step))
+            RowSet::EveryNth {
+                trace_len, step, ..
+            } => (0, trace_len / step),
+            // 1 / ((x - g^row_0)(x - g^row_1)...)
+            RowSet::Rows { rows, .. } => (0, rows.len()),
+        }
+    }
+
+    /// Evaluates `1/Z(x)` where `Z` is the polynomial that vanishes on the rows.
+    /// Returns None if `x` corresponds to one of the rows.
+    pub fn evaluate<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        x: FieldConstant<Fp, Fq>,
+    ) -> Option<FieldConstant<Fp, Fq>> {
+        let trace_domain = Radix2EvaluationDomain::<Fp>::new(self.trace_len()).unwrap();
+        let minus = |v: Fp| FieldConstant::Fp(-v);
+        match self {
+            &RowSet::Transition { trace_len } => {
+                let last_x = trace_domain.element(trace_len - 1);
+                let numerator = x + minus(last_x);
+                if numerator.is_zero() {
+                    // `(x^n - 1) / (x - g^(n-1))` is `n * x^(n-1)` on the last row
+                    let n = FieldConstant::Fp(Fp::from(trace_len as u64));
+                    return (n * x.pow([trace_len as u64 - 1])).inverse();
+                }
+                let denominator = x.pow([trace_len as u64]) + minus(Fp::one());
+                denominator.inverse().map(|v| numerator * v)
+            }
+            &RowSet::EveryNth {
+                trace_len,
+                step,
+                offset,
+            } => {
+                let cycle_len = (trace_len / step) as u64;
+                let shift = trace_domain.element(offset).pow([cycle_len]);
+                (x.pow([cycle_len]) + minus(shift)).inverse()
+            }
+            RowSet::Rows { rows, .. } => rows
+                .iter()
+                .map(|&row| x + minus(trace_domain.element(row)))
+                .fold(FieldConstant::Fp(Fp::one()), |acc, v| acc * v)
+                .inverse(),
+        }
+    }
+
+    /// Evaluates `1/Z(x)` over a domain that's disjoint from the trace domain
+    /// e.g. the constraint evaluation domain. Uses a single batch inversion.
+    pub fn evaluate_over<F: FftField>(&self, domain: Radix2EvaluationDomain<F>) -> Vec<F> {
+        let trace_domain = Radix2EvaluationDomain::<F>::new(self.trace_len()).unwrap();
+        let n = domain.size();
+        match self {
+            &RowSet::Transition { trace_len } => {
+                let last_x = trace_domain.element(trace_len - 1);
+                let mut evals = power_evals(domain, trace_len, F::one());
+                batch_inversion(&mut evals);
+                let period = evals.len();
+                domain
+                    .elements()
+                    .enumerate()
+                    .map(|(i, x)| (x - last_x) * evals[i % period])
+                    .collect()
+            }
+            &RowSet::EveryNth {
+                trace_len,
+                step,
+                offset,
+            } => {
+                let cycle_len = trace_len / step;
+                let shift = trace_domain.element(offset).pow([cycle_len as u64]);
+                let mut evals = power_evals(domain, cycle_len, shift);
+                batch_inversion(&mut evals);
+                evals.iter().cycle().take(n).copied().collect()
+            }
+            RowSet::Rows { rows, .. } => {
+                let row_xs = rows
+                    .iter()
+                    .map(|&row| trace_domain.element(row))
+                    .collect::<Vec<F>>();
+                let mut evals = domain
+                    .elements()
+                    .map(|x| row_xs.iter().map(|row_x| x - row_x).product())
+                    .collect::<Vec<F>>();
+                batch_inversion(&mut evals);
+                evals
+            }
+        }
+    }
+}
+
+/// Returns one period of `x^exponent - shift` over the domain. The values
+/// repeat every `domain.size() / exponent` elements.
+fn power_evals<F: FftField>(
+    domain: Radix2EvaluationDomain<F>,
+    exponent: usize,
+    shift: F,
+) -> Vec<F> {
+    let period = domain.size() / exponent;
+    let generator = domain.group_gen().pow([exponent as u64]);
+    let mut acc = domain.coset_offset().pow([exponent as u64]);
+    let mut evals = Vec::with_capacity(period);
+    for _ in 0..period {
+        evals.push(acc - shift);
+        acc *= generator;
+    }
+    evals
+}
+
+impl Display for RowSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RowSet::Transition { trace_len } => write!(f, "rows 0..{}", trace_len - 1),
+            RowSet::EveryNth {
+                trace_len,
+                step,
+                offset,
+            } => write!(f, "rows {offset}..{trace_len} step {step}"),
+            RowSet::Rows { rows, .. } => write!(f, "rows {rows:?}"),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
-pub enum AlgebraicExpression<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+pub enum AlgebraicExpression<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
     X,
     Constant(FieldConstant<Fp, Fq>),
     Challenge(usize),
     Hint(usize),
+    /// Periodic column defined by [crate::Air::periodic_columns]
+    Periodic(usize),
+    /// Inverse of the polynomial that vanishes on a set of rows. Multiplying a
+    /// constraint by it enforces the constraint on just those rows.
+    OnRows(RowSet),
     Trace(/* =column */ usize, /* =offset */ isize),
     #[cfg(feature = "gpu")]
     Lde(Rc<EvaluationLde<Fp, Fq>>, /* =offset */ isize),
@@ -247,11 +414,60 @@ pub enum AlgebraicExpression<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp
     Exp(Rc<RefCell<AlgebraicExpression<Fp, Fq>>>, isize),
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<Fp, Fq> {
     pub fn pow(&self, exp: usize) -> Self {
         Self::Exp(Rc::new(RefCell::new(self.clone())), exp.try_into().unwrap())
     }
 
+    /// Displays the expression in infix form using the names of an AIR
+    pub fn display_with<'a>(&'a self, names: &'a Names) -> ExpressionDisplay<'a, Fp, Fq> {
+        ExpressionDisplay::new(self, names, Format::Text)
+    }
+
+    /// Like [Self::display_with] but renders LaTeX
+    pub fn latex_with<'a>(&'a self, names: &'a Names) -> ExpressionDisplay<'a, Fp, Fq> {
+        ExpressionDisplay::new(self, names, Format::Latex)
+    }
+
+    /// Enforces the constraint on the first row of a trace
+    pub fn on_first_row(&self, trace_len: usize) -> Self {
+        self.on_rows(trace_len, &[0])
+    }
+
+    /// Enforces the constraint on the last row of a trace
+    pub fn on_last_row(&self, trace_len: usize) -> Self {
+        self.on_rows(trace_len, &[trace_len - 1])
+    }
+
+    /// Enforces the constraint on every row of a trace except the last
+    pub fn on_transition(&self, trace_len: usize) -> Self {
+        assert!(trace_len.is_power_of_two());
+        self * Self::OnRows(RowSet::Transition { trace_len })
+    }
+
+    /// Enforces the constraint on the given rows of a trace
+    pub fn on_rows(&self, trace_len: usize, rows: &[usize]) -> Self {
+        assert!(trace_len.is_power_of_two());
+        let rows = rows.iter().copied().collect::<BTreeSet<usize>>();
+        assert!(!rows.is_empty(), "no rows given");
+        assert!(rows.iter().all(|&row| row < trace_len), "row out of bounds");
+        let rows = rows.into_iter().collect();
+        self * Self::OnRows(RowSet::Rows { trace_len, rows })
+    }
+
+    /// Enforces the constraint on rows `offset`, `offset + step`, `offset + 2 *
+    /// step`, ... of a trace. The step must be a power of two.
+    pub fn every_nth_row(&self, trace_len: usize, step: usize, offset: usize) -> Self {
+        assert!(trace_len.is_power_of_two());
+        assert!(step.is_power_of_two() && step <= trace_len);
+        assert!(offset < step, "offset must be less than the step");
+        self * Self::OnRows(RowSet::EveryNth {
+            trace_len,
+            step,
+            offset,
+        })
+    }
+
     /// Calculates an upper bound on the degree in X.
     /// Output is of the form `(numerator_degree, denominator_degree)`
     pub fn degree(&self, trace_degree: usize) -> (usize, usize) {
@@ -264,7 +480,13 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
         match self {
             X => (x_degree, 0),
             Hint(_) | Challenge(_) | Constant(_) => (0, 0),
-            Trace(..) => (trace_degree, 0),
+            // periodic columns are interpolated over a subgroup of the trace
+            // domain so their degree is at most that of a trace column
+            Trace(..) | Periodic(_) => (trace_degree, 0),
+            OnRows(rows) => {
+                let (numerator, denominator) = rows.degree();
+                (x_degree * numerator, x_degree * denominator)
+            }
             Add(a, b) => {
                 let (a_numerator, a_denominator) = a.borrow().degree_impl(x_degree, trace_degree);
                 let (b_numerator, b_denominator) = b.borrow().degree_impl(x_degree, trace_degree);
@@ -378,6 +600,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
         x: &FieldConstant<Fp, Fq>,
         hint: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
         challenge: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
+        periodic: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
         trace: &impl Fn(usize, isize) -> FieldConstant<Fp, Fq>,
     ) -> FieldConstant<Fp, Fq> {
         use AlgebraicExpression::*;
@@ -386,20 +609,22 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
             &Constant(c) => c,
             &Challenge(i) => challenge(i),
             &Hint(i) => hint(i),
+            &Periodic(i) => periodic(i),
+            OnRows(rows) => rows.evaluate(*x).unwrap(),
             &Trace(i, j) => trace(i, j),
             Add(a, b) => {
-                a.borrow().eval(x, hint, challenge, trace)
-                    + b.borrow().eval(x, hint, challenge, trace)
+                a.borrow().eval(x, hint, challenge, periodic, trace)
+                    + b.borrow().eval(x, hint, challenge, periodic, trace)
             }
-            Neg(a) => -a.borrow().eval(x, hint, challenge, trace),
+            Neg(a) => -a.borrow().eval(x, hint, challenge, periodic, trace),
             Mul(a, b) => {
-                a.borrow().eval(x, hint, challenge, trace)
-                    * b.borrow().eval(x, hint, challenge, trace)
+                a.borrow().eval(x, hint, challenge, periodic, trace)
+                    * b.borrow().eval(x, hint, challenge, periodic, trace)
             }
             Exp(a, e) => {
                 let eval = a
                     .borrow()
-                    .eval(x, hint, challenge, trace)
+                    .eval(x, hint, challenge, periodic, trace)
                     .pow([e.unsigned_abs() as u64]);
                 if *e >= 0 {
                     eval
@@ -437,6 +662,15 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
             FieldConstant::Fq(from_bytes::<Fq>(&hasher.finalize()))
         };
 
+        let periodic = |i: usize| {
+            let mut hasher = Sha256::new();
+            hasher.update(&x_bytes);
+            hasher.update("periodic");
+            hasher.update(i.to_ne_bytes());
+            // TODO: use Fq::from_random_bytes. Deserialization failing for large fields
+            FieldConstant::Fq(from_bytes::<Fq>(&hasher.finalize()))
+        };
+
         let trace = |column: usize, offset: isize| {
             let mut hasher = Sha256::new();
             hasher.update(&x_bytes);
@@ -447,7 +681,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
             FieldConstant::Fq(from_bytes::<Fq>(&hasher.finalize()))
         };
 
-        self.eval(&FieldConstant::Fq(x), &hint, &challenge, &trace)
+        self.eval(&FieldConstant::Fq(x), &hint, &challenge, &periodic, &trace)
             .as_fq()
     }
 
@@ -459,6 +693,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
         x: &FieldConstant<Fp, Fq>,
         hint: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
         challenge: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
+        periodic: &impl Fn(usize) -> FieldConstant<Fp, Fq>,
         trace: &impl Fn(usize, isize) -> FieldConstant<Fp, Fq>,
     ) -> Option<FieldConstant<Fp, Fq>> {
         use AlgebraicExpression::*;
@@ -467,20 +702,25 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
             &Constant(c) => Some(c),
             &Challenge(i) => Some(challenge(i)),
             &Hint(i) => Some(hint(i)),
+            &Periodic(i) => Some(periodic(i)),
+            OnRows(rows) => rows.evaluate(*x),
             &Trace(i, j) => Some(trace(i, j)),
             Add(a, b) => {
-                let a = a.borrow().check(x, hint, challenge, trace);
-                let b = b.borrow().check(x, hint, challenge, trace);
+                let a = a.borrow().check(x, hint, challenge, periodic, trace);
+                let b = b.borrow().check(x, hint, challenge, periodic, trace);
                 if let Some(a) = a && let Some(b) = b {
                     Some(a + b)
                 } else {
                     None
                 }
             }
-            Neg(a) => a.borrow().check(x, hint, challenge, trace).map(|a| -a),
+            Neg(a) => a
+                .borrow()
+                .check(x, hint, challenge, periodic, trace)
+                .map(|a| -a),
             Mul(a, b) => {
-                let a = a.borrow().check(x, hint, challenge, trace);
-                let b = b.borrow().check(x, hint, challenge, trace);
+                let a = a.borrow().check(x, hint, challenge, periodic, trace);
+                let b = b.borrow().check(x, hint, challenge, periodic, trace);
                 match (a, b) {
                     (Some(a), Some(b)) => Some(a * b),
                     (Some(x), None) | (None, Some(x)) => x.is_zero().then_some(x),
@@ -488,7 +728,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
                 }
             }
             Exp(a, e) => {
-                let a = a.borrow().check(x, hint, challenge, trace);
+                let a = a.borrow().check(x, hint, challenge, periodic, trace);
                 a.and_then(|a| {
                     let res = a.pow([e.abs() as u64]);
                     if *e < 0 {
@@ -504,10 +744,63 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
         }
     }
 
+    /// Returns an equivalent expression without redundant structure. Constants
+    /// are folded, sums and products are flattened, zero terms and one factors
+    /// are dropped and the exponents of identical factors are merged so
+    /// factors in the numerator and denominator cancel. The result is the
+    /// same rational function so [Self::degree] remains an upper bound.
+    pub fn simplify(&self) -> Self {
+        Term::sum(self.terms())
+    }
+
+    /// Splits an expression into the terms of a sum
+    fn terms(&self) -> Vec<Term<Fp, Fq>> {
+        use AlgebraicExpression::*;
+        match self {
+            Add(a, b) => {
+                let mut terms = a.borrow().terms();
+                terms.extend(b.borrow().terms());
+                terms
+            }
+            Neg(a) => {
+                let mut terms = a.borrow().terms();
+                terms.iter_mut().for_each(|term| term.coeff = -term.coeff);
+                terms
+            }
+            other => vec![other.term()],
+        }
+    }
+
+    /// Splits an expression into the factors of a product
+    fn term(&self) -> Term<Fp, Fq> {
+        use AlgebraicExpression::*;
+        match self {
+            &Constant(c) => Term::constant(c),
+            Neg(a) => {
+                let mut term = a.borrow().term();
+                term.coeff = -term.coeff;
+                term
+            }
+            Mul(a, b) => a.borrow().term().mul(b.borrow().term()),
+            Exp(a, e) => a.borrow().term().pow(*e),
+            Add(..) => {
+                // sums are kept as a single factor unless they reduce to one term
+                let mut terms = Term::combine(self.terms());
+                match terms.len() {
+                    0 => Term::constant(FieldConstant::Fp(Fp::zero())),
+                    1 => terms.pop().unwrap(),
+                    _ => Term::factor(Term::sum(terms)),
+                }
+            }
+            leaf => Term::factor(leaf.clone()),
+        }
+    }
+
     /// TODO: improve the explanation: reuses shared nodes. determines node
     /// equality probabilistically using a kind of evaluation hash
     /// Inspired by Thorkil Værge's "Reusing Shared Nodes" article:
     /// https://neptune.cash/learn/speed-up-stark-provers-with-multicircuits/
+    #[cfg(feature = "prover")]
     pub fn reuse_shared_nodes(&self) -> Self {
         use AlgebraicExpression::*;
         let mut rng = rand::thread_rng();
@@ -577,7 +870,149 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AlgebraicExpression<F
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Display for AlgebraicExpression<Fp, Fq> {
+/// Product `coeff * base_0^exp_0 * base_1^exp_1 * ...` used to simplify
+/// expressions. Bases are distinct and aren't products themselves.
+struct Term<Fp: FftField, Fq: StarkExtensionOf<Fp>> {
+    coeff: FieldConstant<Fp, Fq>,
+    factors: Vec<(AlgebraicExpression<Fp, Fq>, isize)>,
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Term<Fp, Fq> {
+    fn constant(coeff: FieldConstant<Fp, Fq>) -> Self {
+        Term {
+            coeff,
+            factors: Vec::new(),
+        }
+    }
+
+    fn factor(base: AlgebraicExpression<Fp, Fq>) -> Self {
+        Term {
+            coeff: FieldConstant::Fp(Fp::one()),
+            factors: vec![(base, 1)],
+        }
+    }
+
+    fn mul(mut self, other: Self) -> Self {
+        self.coeff = self.coeff * other.coeff;
+        for (base, exp) in other.factors {
+            let position = self.factors.iter().position(|(other, _)| *other == base);
+            match position {
+                Some(i) => self.factors[i].1 += exp,
+                None => self.factors.push((base, exp)),
+            }
+        }
+        // identical factors in the numerator and denominator cancel
+        self.factors.retain(|(_, exp)| *exp != 0);
+        self
+    }
+
+    fn pow(self, exp: isize) -> Self {
+        if exp == 0 {
+            return Term::constant(FieldConstant::Fp(Fp::one()));
+        }
+        let coeff = if exp > 0 {
+            self.coeff
+        } else {
+            self.coeff.inverse().expect("division by zero")
+        };
+        Term {
+            coeff: coeff.pow([exp.unsigned_abs() as u64]),
+            factors: self
+                .factors
+                .into_iter()
+                .map(|(base, base_exp)| (base, base_exp * exp))
+                .collect(),
+        }
+    }
+
+    fn has_same_factors(&self, other: &Self) -> bool {
+        self.factors.len() == other.factors.len()
+            && self.factors.iter().all(|factor| other.factors.contains(factor))
+    }
+
+    /// Adds the coefficients of like terms and removes terms that are zero
+    fn combine(terms: Vec<Self>) -> Vec<Self> {
+        let mut combined: Vec<Self> = Vec::new();
+        for term in terms {
+            let position = combined.iter().position(|other| other.has_same_factors(&term));
+            match position {
+                Some(i) => combined[i].coeff = combined[i].coeff + term.coeff,
+                None => combined.push(term),
+            }
+        }
+        combined.retain(|term| !term.coeff.is_zero());
+        combined
+    }
+
+    /// Builds the sum of terms with the constant term last
+    fn sum(terms: Vec<Self>) -> AlgebraicExpression<Fp, Fq> {
+        let (constants, mut terms): (Vec<Self>, Vec<Self>) = Self::combine(terms)
+            .into_iter()
+            .partition(|term| term.factors.is_empty());
+        terms.extend(constants);
+        let mut terms = terms.into_iter();
+        let first = match terms.next() {
+            Some(term) => term.into_expr(),
+            None => return AlgebraicExpression::Constant(FieldConstant::Fp(Fp::zero())),
+        };
+        terms.fold(first, |acc, mut term| {
+            if (-term.coeff).as_fq().is_one() {
+                term.coeff = -term.coeff;
+                acc - term.into_expr()
+            } else {
+                acc + term.into_expr()
+            }
+        })
+    }
+
+    fn into_expr(mut self) -> AlgebraicExpression<Fp, Fq> {
+        use AlgebraicExpression::*;
+        if self.coeff.is_zero() {
+            return Constant(self.coeff);
+        }
+        // numerator factors come before denominator factors
+        self.factors.sort_by_key(|(_, exp)| *exp < 0);
+        let product = self
+            .factors
+            .into_iter()
+            .map(|(base, exp)| match exp {
+                1 => base,
+                exp => Exp(Rc::new(RefCell::new(base)), exp),
+            })
+            .reduce(|acc, factor| acc * factor);
+        match product {
+            None => Constant(self.coeff),
+            Some(product) if self.coeff.as_fq().is_one() => product,
+            Some(product) if (-self.coeff).as_fq().is_one() => -product,
+            Some(product) => product * self.coeff,
+        }
+    }
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> PartialEq for AlgebraicExpression<Fp, Fq> {
+    fn eq(&self, other: &Self) -> bool {
+        use AlgebraicExpression::*;
+        match (self, other) {
+            (X, X) => true,
+            (Constant(a), Constant(b)) => a == b,
+            (Challenge(a), Challenge(b)) | (Hint(a), Hint(b)) | (Periodic(a), Periodic(b)) => {
+                a == b
+            }
+            (OnRows(a), OnRows(b)) => a == b,
+            (Trace(a_col, a_offset), Trace(b_col, b_offset)) => {
+                a_col == b_col && a_offset == b_offset
+            }
+            #[cfg(feature = "gpu")]
+            (Lde(a, a_offset), Lde(b, b_offset)) => Rc::ptr_eq(a, b) && a_offset == b_offset,
+            (Add(a0, a1), Add(b0, b1)) | (Mul(a0, a1), Mul(b0, b1)) => a0 == b0 && a1 == b1,
+            (Neg(a), Neg(b)) => a == b,
+            (Exp(a, a_exp), Exp(b, b_exp)) => a_exp == b_exp && a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Display for AlgebraicExpression<Fp, Fq> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use AlgebraicExpression::*;
         match self {
@@ -585,6 +1020,8 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Display for Algebraic
             Constant(c) => write!(f, "{c}"),
             Challenge(i) => write!(f, "challenge[{i}]"),
             Hint(i) => write!(f, "hint[{i}]"),
+            Periodic(i) => write!(f, "periodic[{i}]"),
+            OnRows(rows) => write!(f, "1/Z({rows})"),
             Trace(i, j) => write!(f, "Trace({i}, {j})"),
             Add(a, b) => match &*b.borrow() {
                 Neg(b) => write!(f, "({} - {})", a.borrow(), b.borrow()),
@@ -601,7 +1038,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Display for Algebraic
 }
 
 #[allow(clippy::derive_hash_xor_eq)]
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Hash for AlgebraicExpression<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Hash for AlgebraicExpression<Fp, Fq> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         use AlgebraicExpression::*;
         match self {
@@ -619,6 +1056,14 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Hash for AlgebraicExp
                 "challenge".hash(state);
                 i.hash(state);
             }
+            Periodic(i) => {
+                "periodic".hash(state);
+                i.hash(state);
+            }
+            OnRows(rows) => {
+                "rows".hash(state);
+                rows.hash(state);
+            }
             Trace(i, j) => {
                 "trace".hash(state);
                 i.hash(state);
@@ -651,7 +1096,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Hash for AlgebraicExp
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sum<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Sum<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn sum<I: Iterator<Item = AlgebraicExpression<Fp, Fq>>>(mut iter: I) -> Self {
@@ -663,7 +1108,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sum<AlgebraicExpressi
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Product<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Product<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn product<I: Iterator<Item = AlgebraicExpression<Fp, Fq>>>(mut iter: I) -> Self {
@@ -676,7 +1121,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Product<AlgebraicExpr
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<&AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Mul<&AlgebraicExpression<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -686,7 +1131,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<&AlgebraicExpress
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Mul<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -696,7 +1141,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<AlgebraicExpressi
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<&AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Div<&AlgebraicExpression<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -706,7 +1151,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<&AlgebraicExpress
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Div<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -718,7 +1163,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<AlgebraicExpressi
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<&AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Add<&AlgebraicExpression<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -728,7 +1173,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<&AlgebraicExpress
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Add<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -738,7 +1183,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<AlgebraicExpressi
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<&AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Sub<&AlgebraicExpression<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -748,7 +1193,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<&AlgebraicExpress
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Sub<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -759,7 +1204,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<AlgebraicExpressi
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Neg for AlgebraicExpression<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Neg for AlgebraicExpression<Fp, Fq> {
     type Output = AlgebraicExpression<Fp, Fq>;
 
     fn neg(self) -> Self::Output {
@@ -767,7 +1212,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Neg for AlgebraicExpr
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Neg for &AlgebraicExpression<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Neg for &AlgebraicExpression<Fp, Fq> {
     type Output = AlgebraicExpression<Fp, Fq>;
 
     #[inline]
@@ -776,7 +1221,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Neg for &AlgebraicExp
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -786,7 +1231,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<&FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Mul<&FieldConstant<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -796,7 +1241,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Mul<&FieldConstant<Fp
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Div<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -807,7 +1252,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<&FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Div<&FieldConstant<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -818,7 +1263,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Div<&FieldConstant<Fp
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -828,7 +1273,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<&FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Add<&FieldConstant<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -838,7 +1283,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Add<&FieldConstant<Fp
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Sub<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -848,7 +1293,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<FieldConstant<Fp,
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<&FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Sub<&FieldConstant<Fp, Fq>>
     for &AlgebraicExpression<Fp, Fq>
 {
     type Output = AlgebraicExpression<Fp, Fq>;
@@ -858,16 +1303,16 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Sub<&FieldConstant<Fp
     }
 }
 
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Mul, mul for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Div, div for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Add, add for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Sub, sub for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Mul, mul for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Div, div for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Add, add for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_binop!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > Sub, sub for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Mul, mul for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Div, div for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Add, add for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Sub, sub for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Mul, mul for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Div, div for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Add, add for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_binop!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > Sub, sub for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> MulAssign<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> MulAssign<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn mul_assign(&mut self, other: AlgebraicExpression<Fp, Fq>) {
@@ -875,7 +1320,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> MulAssign<AlgebraicEx
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> MulAssign<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> MulAssign<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn mul_assign(&mut self, rhs: FieldConstant<Fp, Fq>) {
@@ -883,7 +1328,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> MulAssign<FieldConsta
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> DivAssign<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> DivAssign<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn div_assign(&mut self, other: AlgebraicExpression<Fp, Fq>) {
@@ -891,7 +1336,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> DivAssign<AlgebraicEx
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> DivAssign<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> DivAssign<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn div_assign(&mut self, rhs: FieldConstant<Fp, Fq>) {
@@ -899,7 +1344,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> DivAssign<FieldConsta
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AddAssign<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> AddAssign<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn add_assign(&mut self, other: AlgebraicExpression<Fp, Fq>) {
@@ -907,7 +1352,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AddAssign<AlgebraicEx
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AddAssign<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> AddAssign<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn add_assign(&mut self, rhs: FieldConstant<Fp, Fq>) {
@@ -915,7 +1360,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> AddAssign<FieldConsta
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> SubAssign<AlgebraicExpression<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> SubAssign<AlgebraicExpression<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn sub_assign(&mut self, other: AlgebraicExpression<Fp, Fq>) {
@@ -923,7 +1368,7 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> SubAssign<AlgebraicEx
     }
 }
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> SubAssign<FieldConstant<Fp, Fq>>
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> SubAssign<FieldConstant<Fp, Fq>>
     for AlgebraicExpression<Fp, Fq>
 {
     fn sub_assign(&mut self, rhs: FieldConstant<Fp, Fq>) {
@@ -931,11 +1376,11 @@ impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> SubAssign<FieldConsta
     }
 }
 
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > MulAssign, mul_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > DivAssign, div_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > AddAssign, add_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > SubAssign, sub_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > MulAssign, mul_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > DivAssign, div_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > AddAssign, add_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
-forward_ref_op_assign!(impl< Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp> > SubAssign, sub_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > MulAssign, mul_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > DivAssign, div_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > AddAssign, add_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > SubAssign, sub_assign for AlgebraicExpression<Fp, Fq>, AlgebraicExpression<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > MulAssign, mul_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > DivAssign, div_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > AddAssign, add_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
+forward_ref_op_assign!(impl< Fp: FftField, Fq: StarkExtensionOf<Fp> > SubAssign, sub_assign for AlgebraicExpression<Fp, Fq>, FieldConstant<Fp, Fq>);
@@ -0,0 +1,89 @@
+//! Exact degree analysis of constraints.
+//!
+//! [AlgebraicExpression::degree](crate::constraints::AlgebraicExpression::degree)
+//! only gives an upper bound on the degree of a constraint and can't see
+//! cancellation e.g. between the numerator and denominator of a rational
+//! constraint. Overestimated degrees can increase the constraint evaluation
+//! blowup factor which makes proving slower.
+//!
+//! The exact degree of a constraint is found by evaluating it over the
+//! constraint evaluation domain for a trace that satisfies the constraints and
+//! interpolating the evaluations. The degree found is the degree for that
+//! specific trace. It matches the degree of the constraint with high
+//! probability if the trace values are random looking e.g. after verifier
+//! challenges have been mixed in.
+use crate::challenges::Challenges;
+use crate::composer::ConstraintComposer;
+use crate::hints::Hints;
+use crate::prover::ProvingError;
+use crate::utils::ce_blowup_factor;
+use crate::GpuAir;
+use crate::Matrix;
+use alloc::vec::Vec;
+
+/// Degree of a constraint's quotient i.e. the degree of the constraint after
+/// the rows it's enforced on have been divided out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstraintDegree {
+    /// Upper bound given by
+    /// [AlgebraicExpression::degree](crate::constraints::AlgebraicExpression::degree)
+    pub symbolic: usize,
+    /// Degree of the interpolated evaluations of the constraint
+    pub exact: usize,
+}
+
+impl ConstraintDegree {
+    pub fn is_overestimated(&self) -> bool {
+        self.exact < self.symbolic
+    }
+
+    /// Returns true if the constraint evaluation blowup factor required by the
+    /// symbolic bound is larger than the one required by the exact degree
+    pub fn wastes_blowup(&self, trace_len: usize) -> bool {
+        ce_blowup_factor(self.symbolic, trace_len) > ce_blowup_factor(self.exact, trace_len)
+    }
+}
+
+/// Finds the exact degree of each constraint of an AIR. The trace must
+/// satisfy the constraints otherwise the evaluations of rational constraints
+/// aren't polynomial. Trace randomizers are ignored so symbolic bounds are for
+/// trace polynomials of degree `trace_len - 1`. Fails if the trace doesn't
+/// have the columns given by the AIR's trace info.
+pub fn analyze<A: GpuAir + ?Sized>(
+    air: &A,
+    challenges: &Challenges<A::Fq>,
+    hints: &Hints<A::Fq>,
+    base_trace: &Matrix<A::Fp>,
+    extension_trace: Option<&Matrix<A::Fq>>,
+    preprocessed_trace: Option<&Matrix<A::Fp>>,
+) -> Result<Vec<ConstraintDegree>, ProvingError> {
+    let trace_domain = air.trace_domain();
+    let ce_domain = air.ce_domain();
+    let trace_degree = air.trace_len() - 1;
+    let base_trace_lde = base_trace.interpolate(trace_domain).evaluate(ce_domain);
+    let extension_trace_lde =
+        extension_trace.map(|trace| trace.interpolate(trace_domain).evaluate(ce_domain));
+    let preprocessed_trace_lde =
+        preprocessed_trace.map(|trace| trace.interpolate(trace_domain).evaluate(ce_domain));
+
+    let composer = ConstraintComposer::new(air, Vec::new());
+    air.constraints()
+        .iter()
+        .map(|constraint| {
+            let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
+            let evaluations = composer.evaluate_constraint_cpu(
+                constraint,
+                challenges,
+                hints,
+                &base_trace_lde,
+                extension_trace_lde.as_ref(),
+                preprocessed_trace_lde.as_ref(),
+            )?;
+            let poly = evaluations.into_polynomials(ce_domain);
+            Ok(ConstraintDegree {
+                symbolic: numerator_degree - denominator_degree,
+                exact: poly.column_degrees()[0],
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,382 @@
+//! Readable rendering of constraints.
+//!
+//! [AlgebraicExpression::display_with](crate::constraints::AlgebraicExpression::display_with)
+//! renders an expression in infix form with the column, challenge and hint
+//! names of an AIR e.g. `ProcessorBaseColumn::Ip' - ProcessorBaseColumn::Ip - 1`.
+//! Parentheses are only added where precedence requires them and inverted
+//! factors are written as fractions.
+use crate::constraints::AlgebraicExpression;
+use crate::constraints::ExecutionTraceColumn;
+use crate::constraints::FieldConstant;
+use crate::constraints::Hint;
+use crate::constraints::Periodic;
+use crate::constraints::RowSet;
+use crate::constraints::VerifierChallenge;
+use crate::StarkExtensionOf;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use ark_ff::FftField;
+use core::fmt::Display;
+
+/// Names of the columns, challenges, hints and periodic columns of an AIR.
+/// Anything without a name is displayed by its index.
+#[derive(Clone, Debug, Default)]
+pub struct Names {
+    columns: BTreeMap<usize, String>,
+    challenges: BTreeMap<usize, String>,
+    hints: BTreeMap<usize, String>,
+    periodic_columns: BTreeMap<usize, String>,
+}
+
+impl Names {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names execution trace columns by their [Display] implementation
+    pub fn columns<C: ExecutionTraceColumn + Display>(
+        mut self,
+        columns: impl IntoIterator<Item = C>,
+    ) -> Self {
+        for column in columns {
+            self.columns.insert(column.index(), column.to_string());
+        }
+        self
+    }
+
+    /// Names verifier challenges by their [Display] implementation
+    pub fn challenges<C: VerifierChallenge + Display>(
+        mut self,
+        challenges: impl IntoIterator<Item = C>,
+    ) -> Self {
+        for challenge in challenges {
+            self.challenges
+                .insert(challenge.index(), challenge.to_string());
+        }
+        self
+    }
+
+    /// Names hints by their [Display] implementation
+    pub fn hints<H: Hint + Display>(mut self, hints: impl IntoIterator<Item = H>) -> Self {
+        for hint in hints {
+            self.hints.insert(hint.index(), hint.to_string());
+        }
+        self
+    }
+
+    /// Names periodic columns by their [Display] implementation
+    pub fn periodic_columns<P: Periodic + Display>(
+        mut self,
+        periodic_columns: impl IntoIterator<Item = P>,
+    ) -> Self {
+        for column in periodic_columns {
+            self.periodic_columns
+                .insert(column.index(), column.to_string());
+        }
+        self
+    }
+}
+
+/// Output format of an [ExpressionDisplay]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Latex,
+}
+
+/// Displays an [AlgebraicExpression] with the names of an AIR
+pub struct ExpressionDisplay<'a, Fp: FftField, Fq: StarkExtensionOf<Fp>> {
+    expr: &'a AlgebraicExpression<Fp, Fq>,
+    names: &'a Names,
+    format: Format,
+}
+
+impl<'a, Fp: FftField, Fq: StarkExtensionOf<Fp>> ExpressionDisplay<'a, Fp, Fq> {
+    pub fn new(expr: &'a AlgebraicExpression<Fp, Fq>, names: &'a Names, format: Format) -> Self {
+        ExpressionDisplay {
+            expr,
+            names,
+            format,
+        }
+    }
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Display for ExpressionDisplay<'_, Fp, Fq> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let renderer = Renderer {
+            names: self.names,
+            format: self.format,
+        };
+        f.write_str(&renderer.render(self.expr).0)
+    }
+}
+
+/// Binding strength of a rendered expression. An operand is grouped in
+/// parentheses if it binds weaker than its operator requires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Sum,
+    Neg,
+    Product,
+    Power,
+    Atom,
+}
+
+struct Renderer<'a> {
+    names: &'a Names,
+    format: Format,
+}
+
+impl Renderer<'_> {
+    fn render<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        expr: &AlgebraicExpression<Fp, Fq>,
+    ) -> (String, Precedence) {
+        use AlgebraicExpression::*;
+        match expr {
+            X => ("x".into(), Precedence::Atom),
+            Constant(c) if negated(c).is_some() => self.sum(expr),
+            Constant(c) => (c.to_string(), Precedence::Atom),
+            Challenge(i) => (
+                self.name(&self.names.challenges, "challenge", *i),
+                Precedence::Atom,
+            ),
+            Hint(i) => (self.name(&self.names.hints, "hint", *i), Precedence::Atom),
+            Periodic(i) => (
+                self.name(&self.names.periodic_columns, "periodic", *i),
+                Precedence::Atom,
+            ),
+            Trace(col, offset) => (self.trace(*col, *offset), Precedence::Atom),
+            Add(..) | Neg(_) => self.sum(expr),
+            Mul(..) | OnRows(_) => self.product(expr),
+            Exp(_, e) if *e < 0 => self.product(expr),
+            Exp(a, e) => self.power(&*a.borrow(), *e),
+            // don't display lde
+            #[cfg(feature = "gpu")]
+            Lde(..) => unreachable!(),
+        }
+    }
+
+    /// Renders a sum as a sequence of added and subtracted terms
+    fn sum<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        expr: &AlgebraicExpression<Fp, Fq>,
+    ) -> (String, Precedence) {
+        let mut terms = Vec::new();
+        self.terms(expr, false, &mut terms);
+        let mut res = String::new();
+        for (i, (is_negative, term, precedence)) in terms.iter().enumerate() {
+            res += match (i, is_negative) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+            if *is_negative && *precedence < Precedence::Product {
+                res += &self.group(term);
+            } else {
+                res += term;
+            }
+        }
+        let precedence = match terms.as_slice() {
+            [(false, _, precedence)] => *precedence,
+            [(true, ..)] => Precedence::Neg,
+            _ => Precedence::Sum,
+        };
+        (res, precedence)
+    }
+
+    fn terms<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        expr: &AlgebraicExpression<Fp, Fq>,
+        is_negative: bool,
+        terms: &mut Vec<(bool, String, Precedence)>,
+    ) {
+        use AlgebraicExpression::*;
+        match expr {
+            Add(a, b) => {
+                self.terms(&*a.borrow(), is_negative, terms);
+                self.terms(&*b.borrow(), is_negative, terms);
+            }
+            Neg(a) => self.terms(&*a.borrow(), !is_negative, terms),
+            Constant(c) => match negated(c) {
+                Some(c) => terms.push((!is_negative, c.to_string(), Precedence::Atom)),
+                None => terms.push((is_negative, c.to_string(), Precedence::Atom)),
+            },
+            _ => {
+                let (term, precedence) = self.render(expr);
+                terms.push((is_negative, term, precedence));
+            }
+        }
+    }
+
+    /// Renders a product as a fraction if any of its factors are inverted
+    fn product<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        expr: &AlgebraicExpression<Fp, Fq>,
+    ) -> (String, Precedence) {
+        let mut numerator = Vec::new();
+        let mut denominator = Vec::new();
+        self.factors(expr, &mut numerator, &mut denominator);
+        if denominator.is_empty() && numerator.len() == 1 {
+            return numerator.pop().unwrap();
+        }
+
+        if denominator.is_empty() {
+            return (self.join_factors(&numerator), Precedence::Product);
+        }
+        let res = match self.format {
+            Format::Text => {
+                let numerator = if numerator.is_empty() {
+                    "1".into()
+                } else {
+                    self.join_factors(&numerator)
+                };
+                let denominator = match denominator.as_slice() {
+                    [(factor, precedence)] if *precedence >= Precedence::Power => factor.clone(),
+                    [(factor, _)] => self.group(factor),
+                    _ => self.group(&self.join_factors(&denominator)),
+                };
+                format!("{numerator} / {denominator}")
+            }
+            // the fraction bar already groups the numerator and denominator
+            Format::Latex => format!(
+                "\\frac{{{}}}{{{}}}",
+                self.fraction_part(&numerator),
+                self.fraction_part(&denominator)
+            ),
+        };
+        (res, Precedence::Product)
+    }
+
+    fn factors<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        expr: &AlgebraicExpression<Fp, Fq>,
+        numerator: &mut Vec<(String, Precedence)>,
+        denominator: &mut Vec<(String, Precedence)>,
+    ) {
+        use AlgebraicExpression::*;
+        match expr {
+            Mul(a, b) => {
+                self.factors(&*a.borrow(), numerator, denominator);
+                self.factors(&*b.borrow(), numerator, denominator);
+            }
+            OnRows(rows) => denominator.push((self.vanishing(rows), Precedence::Atom)),
+            Exp(a, e) if *e < 0 => denominator.push(self.power(&*a.borrow(), -*e)),
+            _ => numerator.push(self.render(expr)),
+        }
+    }
+
+    fn fraction_part(&self, factors: &[(String, Precedence)]) -> String {
+        match factors {
+            [] => "1".into(),
+            [(factor, _)] => factor.clone(),
+            _ => self.join_factors(factors),
+        }
+    }
+
+    fn join_factors(&self, factors: &[(String, Precedence)]) -> String {
+        let separator = match self.format {
+            Format::Text => " * ",
+            Format::Latex => " \\cdot ",
+        };
+        factors
+            .iter()
+            .map(|(factor, precedence)| {
+                if *precedence < Precedence::Product {
+                    self.group(factor)
+                } else {
+                    factor.clone()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    fn power<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+        &self,
+        base: &AlgebraicExpression<Fp, Fq>,
+        exp: isize,
+    ) -> (String, Precedence) {
+        if exp == 1 {
+            return self.render(base);
+        }
+        let (base, precedence) = self.render(base);
+        let base = if precedence < Precedence::Atom {
+            self.group(&base)
+        } else {
+            base
+        };
+        let res = match self.format {
+            Format::Text => format!("{base}^{exp}"),
+            Format::Latex => format!("{base}^{{{exp}}}"),
+        };
+        (res, Precedence::Power)
+    }
+
+    /// Renders a trace element. The next row is marked with a prime and
+    /// other offsets are written out.
+    fn trace(&self, col: usize, offset: isize) -> String {
+        let name = self.name(&self.names.columns, "trace", col);
+        match (self.format, offset) {
+            (_, 0) => name,
+            (_, 1) => format!("{name}'"),
+            (Format::Text, _) => format!("{name}[{offset:+}]"),
+            (Format::Latex, _) => format!("{name}_{{{offset:+}}}"),
+        }
+    }
+
+    /// Renders the polynomial that vanishes on a set of rows
+    fn vanishing(&self, rows: &RowSet) -> String {
+        match self.format {
+            Format::Text => format!("Z({rows})"),
+            Format::Latex => format!("Z_{{\\text{{{}}}}}", latex_escape(&rows.to_string())),
+        }
+    }
+
+    fn name(&self, names: &BTreeMap<usize, String>, kind: &str, index: usize) -> String {
+        let name = match names.get(&index) {
+            Some(name) => name.clone(),
+            None => format!("{kind}[{index}]"),
+        };
+        match self.format {
+            Format::Text => name,
+            Format::Latex => format!("\\texttt{{{}}}", latex_escape(&name)),
+        }
+    }
+
+    fn group(&self, s: &str) -> String {
+        match self.format {
+            Format::Text => format!("({s})"),
+            Format::Latex => format!("\\left({s}\\right)"),
+        }
+    }
+}
+
+/// Returns the negation of a constant if it's shorter to write e.g. `p - 1`
+/// is written as `-1`
+fn negated<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    c: &FieldConstant<Fp, Fq>,
+) -> Option<FieldConstant<Fp, Fq>> {
+    let neg = -*c;
+    (neg.to_string().len() < c.to_string().len()).then_some(neg)
+}
+
+fn latex_escape(s: &str) -> String {
+    let mut res = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => res += "\\textbackslash{}",
+            '~' => res += "\\textasciitilde{}",
+            '^' => res += "\\textasciicircum{}",
+            '{' | '}' | '_' | '#' | '$' | '%' | '&' => {
+                res.push('\\');
+                res.push(c);
+            }
+            _ => res.push(c),
+        }
+    }
+    res
+}
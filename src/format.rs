@@ -0,0 +1,292 @@
+//! Versioned binary container for proofs.
+//!
+//! ```text
+//! ┌───────┬─────────┬────────┬─────────┬─────┬─────────┐
+//! │ magic │ version │ header │ options │ ... │ queries │
+//! └───────┴─────────┴────────┴─────────┴─────┴─────────┘
+//! ```
+//!
+//! The magic is the four bytes `MSTK` and the version is a little-endian u16.
+//! They are followed by sections in a fixed order. Each section is prefixed by
+//! its length in bytes as a little-endian u64 and contains one or more items
+//! serialized with `CanonicalSerialize` in compressed form.
+use crate::hash::HashFn;
+use crate::Air;
+use crate::Proof;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use ark_ff::BigInteger;
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use ark_serialize::SerializationError;
+use core::marker::PhantomData;
+use snafu::Snafu;
+
+/// Magic bytes at the start of every serialized proof
+pub const MAGIC: [u8; 4] = *b"MSTK";
+
+/// Current version of the proof format
+pub const VERSION: u16 = 2;
+
+/// Number of length prefixed sections in a serialized proof
+const NUM_SECTIONS: usize = 8;
+
+/// Number of bytes taken by the magic, version and section length prefixes
+pub(crate) const FRAMING_SIZE: usize =
+    MAGIC.len() + core::mem::size_of::<u16>() + NUM_SECTIONS * core::mem::size_of::<u64>();
+
+/// Errors that can occur when decoding a proof
+#[derive(Debug, Snafu)]
+pub enum ProofFormatError {
+    #[snafu(display("bytes do not start with the proof magic"))]
+    InvalidMagic,
+    #[snafu(display("proof format version {version} is not supported (expected {VERSION})"))]
+    UnsupportedVersion { version: u16 },
+    #[snafu(display("proof is truncated in the {section} section"))]
+    Truncated { section: &'static str },
+    #[snafu(display("the {section} section is malformed"))]
+    MalformedSection { section: &'static str },
+    #[snafu(display("proof has {num_bytes} unexpected trailing bytes"))]
+    TrailingBytes { num_bytes: usize },
+    #[snafu(display("proof is over a different field to the verifier"))]
+    FieldMismatch,
+    #[snafu(display("proof has extension degree {actual} but expected {expected}"))]
+    ExtensionDegreeMismatch { expected: u64, actual: u64 },
+    #[snafu(display("proof was generated with {actual} but expected {expected}"))]
+    HashFnMismatch {
+        expected: &'static str,
+        actual: String,
+    },
+    #[snafu(display("proof is for the {actual} AIR but expected {expected}"))]
+    AirMismatch {
+        expected: &'static str,
+        actual: String,
+    },
+}
+
+/// Describes what a serialized proof is for
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofHeader {
+    /// Little-endian bytes of the base field modulus
+    pub field_modulus: Vec<u8>,
+    /// Degree of the extension field over the base field
+    pub extension_degree: u64,
+    /// Name of the hash function
+    pub hash_fn: String,
+    /// Identifier of the AIR
+    pub air: String,
+}
+
+impl ProofHeader {
+    pub fn new<A: Air, D: HashFn>() -> Self {
+        let modulus = <<A::Fp as Field>::BasePrimeField as PrimeField>::MODULUS;
+        ProofHeader {
+            field_modulus: modulus.to_bytes_le(),
+            extension_degree: A::Fq::extension_degree(),
+            hash_fn: D::NAME.to_string(),
+            air: A::id().to_string(),
+        }
+    }
+
+    /// Reads the header of a serialized proof without decoding the proof
+    pub fn read(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        let mut reader = SectionReader::new(bytes)?;
+        reader.read_header()
+    }
+
+    /// Checks the header matches the header of proofs for `A` and `D`
+    pub fn check<A: Air, D: HashFn>(&self) -> Result<(), ProofFormatError> {
+        let expected = Self::new::<A, D>();
+        if self.field_modulus != expected.field_modulus {
+            Err(ProofFormatError::FieldMismatch)
+        } else if self.extension_degree != expected.extension_degree {
+            Err(ProofFormatError::ExtensionDegreeMismatch {
+                expected: expected.extension_degree,
+                actual: self.extension_degree,
+            })
+        } else if self.hash_fn != D::NAME {
+            Err(ProofFormatError::HashFnMismatch {
+                expected: D::NAME,
+                actual: self.hash_fn.clone(),
+            })
+        } else if self.air != A::id() {
+            Err(ProofFormatError::AirMismatch {
+                expected: A::id(),
+                actual: self.air.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<A: Air, D: HashFn> Proof<A, D> {
+    /// Serializes the proof into the versioned proof format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        write_section(&mut bytes, |s| {
+            ProofHeader::new::<A, D>().serialize_compressed(s)
+        });
+        write_section(&mut bytes, |s| self.options.serialize_compressed(s));
+        write_section(&mut bytes, |s| self.trace_info.serialize_compressed(s));
+        write_section(&mut bytes, |s| self.public_inputs.serialize_compressed(s));
+        write_section(&mut bytes, |s| {
+            self.base_trace_commitment.serialize_compressed(&mut *s)?;
+            self.extension_trace_commitment
+                .serialize_compressed(&mut *s)?;
+            self.composition_trace_commitment.serialize_compressed(s)
+        });
+        write_section(&mut bytes, |s| {
+            self.execution_trace_ood_evals
+                .serialize_compressed(&mut *s)?;
+            self.composition_trace_ood_evals.serialize_compressed(s)
+        });
+        write_section(&mut bytes, |s| {
+            self.fri_proof.serialize_compressed(&mut *s)?;
+            self.pow_nonce.serialize_compressed(s)
+        });
+        write_section(&mut bytes, |s| self.trace_queries.serialize_compressed(s));
+        bytes
+    }
+
+    /// Deserializes a proof in the versioned proof format. Fails if the proof
+    /// was generated for a different field, hash function or AIR.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        use ProofFormatError::*;
+        let mut reader = SectionReader::new(bytes)?;
+        let header = reader.read_header()?;
+        header.check::<A, D>()?;
+
+        let options = reader.read_section("options", |s| {
+            CanonicalDeserialize::deserialize_compressed(s)
+        })?;
+        let trace_info = reader.read_section("trace info", |s| {
+            CanonicalDeserialize::deserialize_compressed(s)
+        })?;
+        let public_inputs = reader.read_section("public inputs", |s| {
+            CanonicalDeserialize::deserialize_compressed(s)
+        })?;
+        let (base_trace_commitment, extension_trace_commitment, composition_trace_commitment) =
+            reader.read_section("commitments", |s| {
+                Ok((
+                    CanonicalDeserialize::deserialize_compressed(&mut *s)?,
+                    CanonicalDeserialize::deserialize_compressed(&mut *s)?,
+                    CanonicalDeserialize::deserialize_compressed(s)?,
+                ))
+            })?;
+        let (execution_trace_ood_evals, composition_trace_ood_evals) =
+            reader.read_section("out-of-domain evaluations", |s| {
+                Ok((
+                    CanonicalDeserialize::deserialize_compressed(&mut *s)?,
+                    CanonicalDeserialize::deserialize_compressed(s)?,
+                ))
+            })?;
+        let (fri_proof, pow_nonce) = reader.read_section("fri proof", |s| {
+            Ok((
+                CanonicalDeserialize::deserialize_compressed(&mut *s)?,
+                CanonicalDeserialize::deserialize_compressed(s)?,
+            ))
+        })?;
+        let trace_queries = reader.read_section("queries", |s| {
+            CanonicalDeserialize::deserialize_compressed(s)
+        })?;
+
+        if !reader.bytes.is_empty() {
+            return Err(TrailingBytes {
+                num_bytes: reader.bytes.len(),
+            });
+        }
+
+        Ok(Proof {
+            hash_fn: header.hash_fn,
+            options,
+            trace_info,
+            base_trace_commitment,
+            extension_trace_commitment,
+            composition_trace_commitment,
+            fri_proof,
+            pow_nonce,
+            trace_queries,
+            public_inputs,
+            execution_trace_ood_evals,
+            composition_trace_ood_evals,
+            _digest: PhantomData,
+        })
+    }
+}
+
+fn write_section(
+    dst: &mut Vec<u8>,
+    write: impl FnOnce(&mut Vec<u8>) -> Result<(), SerializationError>,
+) {
+    let mut section = Vec::new();
+    write(&mut section).expect("failed to serialize section");
+    dst.extend_from_slice(&(section.len() as u64).to_le_bytes());
+    dst.extend_from_slice(&section);
+}
+
+struct SectionReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SectionReader<'a> {
+    /// Checks the magic and version and returns a reader for the sections
+    fn new(bytes: &'a [u8]) -> Result<Self, ProofFormatError> {
+        let (magic, bytes) = split(bytes, MAGIC.len(), "magic")?;
+        if magic != MAGIC {
+            return Err(ProofFormatError::InvalidMagic);
+        }
+
+        let (version, bytes) = split(bytes, 2, "version")?;
+        let version = u16::from_le_bytes(version.try_into().unwrap());
+        if version != VERSION {
+            return Err(ProofFormatError::UnsupportedVersion { version });
+        }
+
+        Ok(SectionReader { bytes })
+    }
+
+    fn read_header(&mut self) -> Result<ProofHeader, ProofFormatError> {
+        self.read_section("header", |s| {
+            CanonicalDeserialize::deserialize_compressed(s)
+        })
+    }
+
+    /// Reads the next section. The section must be consumed entirely by `read`.
+    fn read_section<T>(
+        &mut self,
+        section: &'static str,
+        read: impl FnOnce(&mut &'a [u8]) -> Result<T, SerializationError>,
+    ) -> Result<T, ProofFormatError> {
+        let (len, bytes) = split(self.bytes, 8, section)?;
+        let len = u64::from_le_bytes(len.try_into().unwrap());
+        let len = usize::try_from(len).map_err(|_| ProofFormatError::Truncated { section })?;
+        let (mut contents, bytes) = split(bytes, len, section)?;
+        self.bytes = bytes;
+
+        let item =
+            read(&mut contents).map_err(|_| ProofFormatError::MalformedSection { section })?;
+        if contents.is_empty() {
+            Ok(item)
+        } else {
+            Err(ProofFormatError::MalformedSection { section })
+        }
+    }
+}
+
+fn split<'a>(
+    bytes: &'a [u8],
+    mid: usize,
+    section: &'static str,
+) -> Result<(&'a [u8], &'a [u8]), ProofFormatError> {
+    if bytes.len() < mid {
+        Err(ProofFormatError::Truncated { section })
+    } else {
+        Ok(bytes.split_at(mid))
+    }
+}
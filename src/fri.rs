@@ -1,6 +1,19 @@
+use crate::hash::HashFn;
+#[cfg(feature = "json")]
+use crate::json;
+#[cfg(feature = "json")]
+use crate::json::JsonError;
+#[cfg(feature = "json")]
+use crate::json::Value;
 use crate::merkle::MerkleProof;
 use crate::merkle::MerkleTree;
 use crate::merkle::MerkleTreeError;
+#[cfg(feature = "prover")]
+use crate::observer::observe;
+#[cfg(feature = "prover")]
+use crate::observer::ProverObserver;
+#[cfg(feature = "prover")]
+use crate::observer::ProvingStage;
 use crate::random::PublicCoin;
 use crate::utils::interleave;
 use alloc::vec::Vec;
@@ -17,9 +30,12 @@ use ark_serialize::CanonicalSerialize;
 use core::ops::Deref;
 use digest::Digest;
 use digest::Output;
+#[cfg(feature = "prover")]
 use gpu_poly::prelude::*;
-#[cfg(feature = "parallel")]
+#[cfg(all(feature = "prover", feature = "parallel"))]
 use rayon::prelude::*;
+#[cfg(feature = "json")]
+use serde_json::json;
 use snafu::Snafu;
 
 #[derive(Clone, Copy)]
@@ -54,12 +70,20 @@ impl FriOptions {
         domain_size
     }
 
+    #[cfg(feature = "prover")]
     pub fn domain_offset<F: GpuField>(&self) -> F::FftField
     where
         F::FftField: FftField,
     {
         F::FftField::GENERATOR
     }
+
+    /// Domain of the first FRI layer for polynomials of degree at most
+    /// `max_poly_degree`
+    pub fn domain<F: FftField>(&self, max_poly_degree: usize) -> Radix2EvaluationDomain<F> {
+        let domain_size = max_poly_degree.next_power_of_two() * self.blowup_factor;
+        Radix2EvaluationDomain::new_coset(domain_size, F::GENERATOR).unwrap()
+    }
 }
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
@@ -69,10 +93,7 @@ pub struct FriProof<F: Field> {
     remainder_commitment: Vec<u8>,
 }
 
-impl<F: GpuField + Field> FriProof<F>
-where
-    F::FftField: FftField,
-{
+impl<F: Field> FriProof<F> {
     pub fn new(
         layers: Vec<FriProofLayer<F>>,
         remainder_commitment: Vec<u8>,
@@ -84,13 +105,54 @@ where
             remainder,
         }
     }
+
+    pub fn layers(&self) -> &[FriProofLayer<F>] {
+        &self.layers
+    }
+
+    pub fn remainder(&self) -> &[F] {
+        &self.remainder
+    }
+
+    pub fn remainder_commitment(&self) -> &[u8] {
+        &self.remainder_commitment
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "layers": self.layers.iter().map(FriProofLayer::to_json).collect::<Vec<Value>>(),
+            "remainder": json::fields_to_json(&self.remainder),
+            "remainder_commitment": json::hex_to_json(&self.remainder_commitment),
+        })
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let layers = json::get(value, "layers")?
+            .as_array()
+            .ok_or(JsonError::InvalidValue { field: "layers" })?;
+        Ok(FriProof {
+            layers: layers
+                .iter()
+                .map(FriProofLayer::from_json)
+                .collect::<Result<Vec<FriProofLayer<F>>, JsonError>>()?,
+            remainder: json::fields_from_json(json::get(value, "remainder")?, "remainder")?,
+            remainder_commitment: json::hex_from_json(
+                json::get(value, "remainder_commitment")?,
+                "remainder_commitment",
+            )?,
+        })
+    }
 }
 
+#[cfg(feature = "prover")]
 pub struct FriProver<F: GpuField, D: Digest> {
     options: FriOptions,
     layers: Vec<FriLayer<F, D>>,
 }
 
+#[cfg(feature = "prover")]
 struct FriLayer<F: GpuField, D: Digest> {
     tree: MerkleTree<D>,
     evaluations: Vec<F>,
@@ -99,53 +161,87 @@ struct FriLayer<F: GpuField, D: Digest> {
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
 pub struct FriProofLayer<F: Field> {
     values: Vec<F>,
-    proofs: Vec<MerkleProof>,
+    proof: MerkleProof,
     commitment: Vec<u8>,
 }
 
-impl<F: GpuField + Field> FriProofLayer<F>
-where
-    F::FftField: FftField,
-{
+impl<F: Field> FriProofLayer<F> {
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    pub fn proof(&self) -> &MerkleProof {
+        &self.proof
+    }
+
+    pub fn commitment(&self) -> &[u8] {
+        &self.commitment
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "commitment": json::hex_to_json(&self.commitment),
+            "values": json::fields_to_json(&self.values),
+            "proof": self.proof.to_json(),
+        })
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(FriProofLayer {
+            values: json::fields_from_json(json::get(value, "values")?, "values")?,
+            proof: MerkleProof::from_json(json::get(value, "proof")?)?,
+            commitment: json::hex_from_json(json::get(value, "commitment")?, "commitment")?,
+        })
+    }
+}
+
+impl<F: Field> FriProofLayer<F> {
     pub fn new<const N: usize>(
         values: Vec<[F; N]>,
-        proofs: Vec<MerkleProof>,
+        proof: MerkleProof,
         commitment: Vec<u8>,
     ) -> Self {
         let values = values.into_iter().flatten().collect();
         FriProofLayer {
             values,
-            proofs,
+            proof,
             commitment,
         }
     }
 
+    /// Verifies the layer values against the layer commitment. `positions` are
+    /// the folded positions of a layer with `num_chunks` chunks of `N` values.
     pub fn verify<D: Digest, const N: usize>(
         &self,
         positions: &[usize],
+        num_chunks: usize,
     ) -> Result<(), MerkleTreeError> {
+        if self.commitment.len() != D::output_size() {
+            return Err(MerkleTreeError::InvalidProof);
+        }
         let commitment = Output::<D>::from_slice(&self.commitment);
-        // TODO: could check raminder is empty but not critical
-        // TODO: could check positions has the same len as other vecs but not critical
-        let (chunks, _remainder) = &self.values.as_chunks::<N>();
-        // zip chains could be dangerous
-        for (i, position) in positions.iter().enumerate() {
-            let proof = self.proofs[i].parse::<D>();
-            let expected_leaf = &proof[0];
-            let mut chunk_bytes = Vec::with_capacity(chunks.compressed_size());
-            chunks.serialize_compressed(&mut chunk_bytes).unwrap();
-            let actual_leaf = D::new_with_prefix(chunk_bytes).finalize();
-
-            if *expected_leaf != actual_leaf {
-                return Err(MerkleTreeError::InvalidProof);
-            }
-
-            MerkleTree::<D>::verify(commitment, &proof, *position / 4)?;
+        let (chunks, remainder) = &self.values.as_chunks::<N>();
+        if !remainder.is_empty() {
+            return Err(MerkleTreeError::InvalidProof);
         }
-        Ok(())
+
+        let leaves = chunks
+            .iter()
+            .map(|chunk| {
+                let mut chunk_bytes = Vec::with_capacity(chunk.compressed_size());
+                chunk.serialize_compressed(&mut chunk_bytes).unwrap();
+                D::new_with_prefix(chunk_bytes).finalize()
+            })
+            .collect::<Vec<Output<D>>>();
+
+        let proof = self.proof.parse::<D>();
+        MerkleTree::<D>::verify_batch(commitment, &proof, positions, &leaves, num_chunks)
     }
 }
 
+#[cfg(feature = "prover")]
 impl<F: GpuField + Field, D: Digest> FriProver<F, D>
 where
     F: DomainCoeff<F::FftField>,
@@ -197,18 +293,23 @@ where
         &mut self,
         channel: &mut impl ProverChannel<F, Digest = D>,
         mut evaluations: GpuVec<F>,
+        observer: &mut impl ProverObserver,
     ) {
         assert!(self.layers.is_empty());
         // let codeword = evaluations.0[0];
 
-        for _ in 0..self.options.num_layers(evaluations.len()) + 1 {
-            evaluations = match self.options.folding_factor {
+        for layer in 0..self.options.num_layers(evaluations.len()) + 1 {
+            let stage = ProvingStage::FriLayer {
+                layer,
+                domain_size: evaluations.len(),
+            };
+            evaluations = observe(observer, stage, || match self.options.folding_factor {
                 2 => self.build_layer::<2>(channel, evaluations),
                 4 => self.build_layer::<4>(channel, evaluations),
                 8 => self.build_layer::<8>(channel, evaluations),
                 16 => self.build_layer::<16>(channel, evaluations),
                 folding_factor => unreachable!("folding factor {folding_factor} not supported"),
-            }
+            })
         }
     }
 
@@ -276,41 +377,56 @@ pub enum VerificationError {
         folding_factor: usize,
         layer: usize,
     },
+    #[snafu(display("commitment of layer {layer} is {actual} bytes but expected {expected}"))]
+    LayerCommitmentLength {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[snafu(display("remainder commitment is {actual} bytes but expected {expected}"))]
+    RemainderCommitmentLength { expected: usize, actual: usize },
 }
 
-pub struct FriVerifier<F: GpuField + Field, D: Digest>
-where
-    F::FftField: FftField,
-{
+pub struct FriVerifier<'a, F: Field, D: Digest> {
     options: FriOptions,
     layer_commitments: Vec<Output<D>>,
     layer_alphas: Vec<F>,
-    proof: FriProof<F>,
-    domain: Radix2EvaluationDomain<F::FftField>,
+    proof: &'a FriProof<F>,
+    domain: Radix2EvaluationDomain<F::BasePrimeField>,
 }
 
-impl<F: GpuField + Field, D: Digest> FriVerifier<F, D>
-where
-    F: DomainCoeff<F::FftField>,
-    F::FftField: FftField,
-{
+impl<'a, F: Field + DomainCoeff<F::BasePrimeField>, D: Digest> FriVerifier<'a, F, D> {
     pub fn new(
-        public_coin: &mut PublicCoin<impl Digest>,
+        public_coin: &mut PublicCoin<impl HashFn>,
         options: FriOptions,
-        proof: FriProof<F>,
+        proof: &'a FriProof<F>,
         max_poly_degree: usize,
+    ) -> Result<Self, VerificationError> {
+        let domain = options.domain(max_poly_degree);
+        Self::with_domain(public_coin, options, proof, domain)
+    }
+
+    /// Like [FriVerifier::new] but with a precomputed domain for the first layer
+    pub fn with_domain(
+        public_coin: &mut PublicCoin<impl HashFn>,
+        options: FriOptions,
+        proof: &'a FriProof<F>,
+        domain: Radix2EvaluationDomain<F::BasePrimeField>,
     ) -> Result<Self, VerificationError> {
         let folding_factor = options.folding_factor;
-        let domain_offset = options.domain_offset::<F>();
-        let domain_size = max_poly_degree.next_power_of_two() * options.blowup_factor;
-        let domain = Radix2EvaluationDomain::new_coset(domain_size, domain_offset).unwrap();
+        let domain_size = domain.size();
 
         let mut layer_alphas = Vec::new();
         let mut layer_commitments = Vec::new();
         let mut layer_codeword_len = domain_size;
         for (i, layer) in proof.layers.iter().enumerate() {
-            // TODO: batch merkle tree proofs
-            // get the merkle root from the first merkle path
+            if layer.commitment.len() != D::output_size() {
+                return Err(VerificationError::LayerCommitmentLength {
+                    layer: i,
+                    expected: D::output_size(),
+                    actual: layer.commitment.len(),
+                });
+            }
             let layer_commitment = Output::<D>::from_slice(&layer.commitment).clone();
             public_coin.reseed(&layer_commitment.deref());
             let alpha = public_coin.draw();
@@ -328,6 +444,12 @@ where
             layer_codeword_len /= folding_factor;
         }
 
+        if proof.remainder_commitment.len() != D::output_size() {
+            return Err(VerificationError::RemainderCommitmentLength {
+                expected: D::output_size(),
+                actual: proof.remainder_commitment.len(),
+            });
+        }
         let remainder_root = Output::<D>::from_slice(&proof.remainder_commitment).clone();
         public_coin.reseed(&remainder_root.deref());
         let remainder_alpha = public_coin.draw();
@@ -351,9 +473,8 @@ where
         let domain_offset = self.domain.coset_offset();
         let folding_domain = Radix2EvaluationDomain::new(N).unwrap();
 
-        let mut layers = self.proof.layers.into_iter();
+        let mut layers = self.proof.layers.iter();
         let mut layer_alphas = self.layer_alphas.into_iter();
-        let mut layer_commitments = self.layer_commitments.into_iter();
         let mut positions = positions.to_vec();
         let mut evaluations = evaluations.to_vec();
         let mut domain_size = self.domain.size();
@@ -363,29 +484,16 @@ where
         for i in 0..self.options.num_layers(domain_size) {
             let folded_positions = fold_positions(&positions, domain_size / N);
             let layer_alpha = layer_alphas.next().unwrap();
-            let layer_commitment = layer_commitments.next().unwrap();
 
-            // TODO: change assert to error. Check remainder
+            // TODO: change assert to error
             let layer = layers.next().unwrap();
             let (chunks, _) = &layer.values.as_chunks::<N>();
             assert_eq!(chunks.len(), folded_positions.len());
 
             // verify the layer values against the layer's commitment
-            for (j, position) in folded_positions.iter().enumerate() {
-                let proof = layer.proofs[j].parse::<D>();
-                let expected_leaf = &proof[0];
-                let chunk = chunks[j];
-                let mut chunk_bytes = Vec::with_capacity(chunk.compressed_size());
-                chunk.serialize_compressed(&mut chunk_bytes).unwrap();
-                let actual_leaf = D::new_with_prefix(&chunk_bytes).finalize();
-
-                if *expected_leaf != actual_leaf {
-                    return Err(VerificationError::LayerCommitmentInvalid { layer: i });
-                }
-
-                MerkleTree::<D>::verify(&layer_commitment, &proof, *position)
-                    .map_err(|_| VerificationError::LayerCommitmentInvalid { layer: i })?
-            }
+            layer
+                .verify::<D, N>(&folded_positions, domain_size / N)
+                .map_err(|_| VerificationError::LayerCommitmentInvalid { layer: i })?;
 
             let query_values = get_query_values(chunks, &positions, &folded_positions, domain_size);
             if evaluations != query_values {
@@ -415,8 +523,8 @@ where
         }
 
         verify_remainder::<F, D, N>(
-            layer_commitments.next().unwrap(),
-            self.proof.remainder,
+            self.layer_commitments.into_iter().last().unwrap(),
+            &self.proof.remainder,
             domain_size - 1,
         )
     }
@@ -437,20 +545,16 @@ where
     }
 }
 
-fn verify_remainder<F: GpuField + Field, D: Digest, const N: usize>(
+fn verify_remainder<F: Field + DomainCoeff<F::BasePrimeField>, D: Digest, const N: usize>(
     commitment: Output<D>,
-    mut remainder_evals: Vec<F>,
+    remainder_evals: &[F],
     max_degree: usize,
-) -> Result<(), VerificationError>
-where
-    F: DomainCoeff<F::FftField>,
-    F::FftField: FftField,
-{
+) -> Result<(), VerificationError> {
     if max_degree >= remainder_evals.len() {
         return Err(VerificationError::RemainderTooSmall);
     }
 
-    let interleaved_evals: Vec<[F; N]> = interleave(&remainder_evals);
+    let interleaved_evals: Vec<[F; N]> = interleave(remainder_evals);
     let hashed_evals = interleaved_evals
         .into_iter()
         .map(|chunk| {
@@ -473,8 +577,7 @@ where
         }
     } else {
         let domain = Radix2EvaluationDomain::new(remainder_evals.len()).unwrap();
-        domain.ifft_in_place(&mut remainder_evals);
-        let poly = DensePolynomial::from_coefficients_vec(remainder_evals);
+        let poly = DensePolynomial::from_coefficients_vec(domain.ifft(remainder_evals));
 
         if poly.degree() > max_degree {
             Err(VerificationError::RemainderDegreeMismatch { degree: max_degree })
@@ -484,6 +587,7 @@ where
     }
 }
 
+#[cfg(feature = "prover")]
 pub trait ProverChannel<F: GpuField> {
     type Digest: Digest;
 
@@ -526,6 +630,7 @@ pub trait ProverChannel<F: GpuField> {
 //    ├────────┼────┼────┼────┼────┤
 //    │ drp[i] │ 82 │ 12 │ 57 │ 34 │
 //    └────────┴────┴────┴────┴────┘
+#[cfg(feature = "prover")]
 pub fn apply_drp<F: GpuField + Field>(
     evals: GpuVec<F>,
     domain_offset: F::FftField,
@@ -562,6 +667,7 @@ where
     fft(drp_coeffs, drp_domain)
 }
 
+#[cfg(feature = "prover")]
 fn ifft<F: GpuField + Field>(
     evals: GpuVec<F>,
     domain: Radix2EvaluationDomain<F::FftField>,
@@ -583,6 +689,7 @@ where
     coeffs.to_vec_in(PageAlignedAllocator)
 }
 
+#[cfg(feature = "prover")]
 fn fft<F: GpuField + Field>(
     coeffs: GpuVec<F>,
     domain: Radix2EvaluationDomain<F::FftField>,
@@ -634,6 +741,7 @@ fn get_query_values<F: Field, const N: usize>(
         .collect()
 }
 
+#[cfg(feature = "prover")]
 fn query_layer<F: GpuField + Field, D: Digest, const N: usize>(
     layer: &FriLayer<F, D>,
     positions: &[usize],
@@ -641,20 +749,15 @@ fn query_layer<F: GpuField + Field, D: Digest, const N: usize>(
 where
     F::FftField: FftField,
 {
-    let proofs = positions
-        .iter()
-        .map(|pos| {
-            layer
-                .tree
-                .prove(*pos)
-                .expect("failed to generate Merkle proof")
-        })
-        .collect::<Vec<MerkleProof>>();
+    let proof = layer
+        .tree
+        .prove_batch(positions)
+        .expect("failed to generate Merkle proof");
     let mut values: Vec<[F; N]> = Vec::new();
     for &position in positions {
         let i = position * N;
         let chunk = &layer.evaluations[i..i + N];
         values.push(chunk.try_into().unwrap());
     }
-    FriProofLayer::new(values, proofs, layer.tree.root().to_vec())
+    FriProofLayer::new(values, proof, layer.tree.root().to_vec())
 }
@@ -0,0 +1,53 @@
+use ark_ff::Field;
+use ark_ff::UniformRand;
+use digest::Digest;
+use digest::Output;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// A hash function that can be used for Merkle commitments and the public coin
+/// of a proof.
+pub trait HashFn: Digest + Send + Sync {
+    /// Unique name of the hash function. This is recorded in each proof so a
+    /// verifier can't be tricked into using a different hash function to the
+    /// one the prover used.
+    const NAME: &'static str;
+
+    /// Conjectured collision resistance of the hash function in bits
+    const COLLISION_RESISTANCE: usize;
+
+    /// Maps a digest drawn by the public coin to a field element. Returning
+    /// `None` rejects the digest and the public coin draws another one.
+    ///
+    /// By default the digest seeds a ChaCha20 stream that the element is
+    /// sampled from. Hash functions over a field override this to use the
+    /// elements of the digest directly.
+    fn digest_to_field<F: Field>(digest: &Output<Self>) -> Option<F> {
+        let mut seed = [0; 32];
+        seed.copy_from_slice(&digest[..32]);
+        Some(F::rand(&mut ChaCha20Rng::from_seed(seed)))
+    }
+}
+
+impl HashFn for sha2::Sha256 {
+    const NAME: &'static str = "sha256";
+    const COLLISION_RESISTANCE: usize = 128;
+}
+
+#[cfg(feature = "sha3")]
+impl HashFn for sha3::Sha3_256 {
+    const NAME: &'static str = "sha3-256";
+    const COLLISION_RESISTANCE: usize = 128;
+}
+
+#[cfg(feature = "sha3")]
+impl HashFn for sha3::Keccak256 {
+    const NAME: &'static str = "keccak256";
+    const COLLISION_RESISTANCE: usize = 128;
+}
+
+#[cfg(feature = "blake3")]
+impl HashFn for blake3::Hasher {
+    const NAME: &'static str = "blake3";
+    const COLLISION_RESISTANCE: usize = 128;
+}
@@ -0,0 +1,339 @@
+//! JSON encoding of proofs for debugging and for cross-checking against other
+//! verifiers. Commitments, Merkle paths and salts are hex strings. Field
+//! elements are decimal strings and elements of extension fields are arrays
+//! of their base prime field coefficients. Converting a proof to JSON and back
+//! gives exactly the same proof.
+use crate::fri::FriProof;
+use crate::hash::HashFn;
+use crate::merkle::MerkleProof;
+use crate::merkle::Salt;
+use crate::trace::Queries;
+use crate::Air;
+use crate::Proof;
+use crate::ProofOptions;
+use crate::TraceInfo;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_ff::Zero;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use core::marker::PhantomData;
+use serde_json::json;
+pub use serde_json::Value;
+use snafu::Snafu;
+
+/// Errors that can occur when reading a proof from JSON
+#[derive(Debug, Snafu)]
+pub enum JsonError {
+    #[snafu(display("missing field `{field}`"))]
+    MissingField { field: &'static str },
+    #[snafu(display("field `{field}` has an invalid value"))]
+    InvalidValue { field: &'static str },
+}
+
+/// Returns the value of a field in a JSON object
+pub fn get<'a>(value: &'a Value, field: &'static str) -> Result<&'a Value, JsonError> {
+    value.get(field).ok_or(JsonError::MissingField { field })
+}
+
+/// Encodes bytes as a lowercase hex string
+pub fn hex_to_json(bytes: &[u8]) -> Value {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let hex = bytes
+        .iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize], DIGITS[(b & 0xF) as usize]])
+        .map(char::from)
+        .collect::<String>();
+    Value::String(hex)
+}
+
+/// Decodes bytes from a hex string
+pub fn hex_from_json(value: &Value, field: &'static str) -> Result<Vec<u8>, JsonError> {
+    let hex = value.as_str().ok_or(JsonError::InvalidValue { field })?;
+    if hex.len() % 2 != 0 {
+        return Err(JsonError::InvalidValue { field });
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let hi = (pair[0] as char).to_digit(16);
+            let lo = (pair[1] as char).to_digit(16);
+            match (hi, lo) {
+                (Some(hi), Some(lo)) => Ok((hi << 4 | lo) as u8),
+                _ => Err(JsonError::InvalidValue { field }),
+            }
+        })
+        .collect()
+}
+
+/// Encodes a field element as a decimal string or, for extension fields, an
+/// array of decimal strings
+pub fn field_to_json<F: Field>(element: &F) -> Value {
+    let mut coeffs = element
+        .to_base_prime_field_elements()
+        .map(|coeff| Value::String(coeff.into_bigint().to_string()))
+        .collect::<Vec<Value>>();
+    if F::extension_degree() == 1 {
+        coeffs.pop().unwrap()
+    } else {
+        Value::Array(coeffs)
+    }
+}
+
+/// Decodes a field element encoded with [field_to_json]
+pub fn field_from_json<F: Field>(value: &Value, field: &'static str) -> Result<F, JsonError> {
+    let coeffs = if F::extension_degree() == 1 {
+        vec![prime_field_from_json(value, field)?]
+    } else {
+        let coeffs = value.as_array().ok_or(JsonError::InvalidValue { field })?;
+        coeffs
+            .iter()
+            .map(|coeff| prime_field_from_json(coeff, field))
+            .collect::<Result<Vec<F::BasePrimeField>, JsonError>>()?
+    };
+    F::from_base_prime_field_elems(&coeffs).ok_or(JsonError::InvalidValue { field })
+}
+
+/// Decodes a prime field element from its canonical decimal representation
+fn prime_field_from_json<F: PrimeField>(
+    value: &Value,
+    field: &'static str,
+) -> Result<F, JsonError> {
+    let decimal = value.as_str().ok_or(JsonError::InvalidValue { field })?;
+    let ten = F::from(10u8);
+    let mut element = F::zero();
+    for c in decimal.chars() {
+        let digit = c.to_digit(10).ok_or(JsonError::InvalidValue { field })?;
+        element = element * ten + F::from(digit as u8);
+    }
+    // rejects values that aren't canonical e.g. leading zeros or overflowing
+    if element.into_bigint().to_string() != decimal {
+        return Err(JsonError::InvalidValue { field });
+    }
+    Ok(element)
+}
+
+pub fn fields_to_json<F: Field>(elements: &[F]) -> Value {
+    Value::Array(elements.iter().map(field_to_json).collect())
+}
+
+pub fn fields_from_json<F: Field>(value: &Value, field: &'static str) -> Result<Vec<F>, JsonError> {
+    let elements = value.as_array().ok_or(JsonError::InvalidValue { field })?;
+    elements
+        .iter()
+        .map(|element| field_from_json(element, field))
+        .collect()
+}
+
+/// Reads an integer field of a JSON object
+fn get_int<T: TryFrom<u64>>(object: &Value, field: &'static str) -> Result<T, JsonError> {
+    get(object, field)?
+        .as_u64()
+        .and_then(|v| T::try_from(v).ok())
+        .ok_or(JsonError::InvalidValue { field })
+}
+
+fn salts_to_json(salts: &[Salt]) -> Value {
+    Value::Array(salts.iter().map(|salt| hex_to_json(salt)).collect())
+}
+
+fn salts_from_json(value: &Value, field: &'static str) -> Result<Vec<Salt>, JsonError> {
+    let salts = value.as_array().ok_or(JsonError::InvalidValue { field })?;
+    salts
+        .iter()
+        .map(|salt| {
+            hex_from_json(salt, field)?
+                .try_into()
+                .map_err(|_| JsonError::InvalidValue { field })
+        })
+        .collect()
+}
+
+/// Encodes an item as the hex of its compressed serialization
+fn serialized_to_json(item: &impl CanonicalSerialize) -> Value {
+    let mut bytes = Vec::new();
+    item.serialize_compressed(&mut bytes).unwrap();
+    hex_to_json(&bytes)
+}
+
+fn serialized_from_json<T: CanonicalDeserialize>(
+    value: &Value,
+    field: &'static str,
+) -> Result<T, JsonError> {
+    let bytes = hex_from_json(value, field)?;
+    T::deserialize_compressed(bytes.as_slice()).map_err(|_| JsonError::InvalidValue { field })
+}
+
+impl ProofOptions {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "num_queries": self.num_queries,
+            "lde_blowup_factor": self.lde_blowup_factor,
+            "grinding_factor": self.grinding_factor,
+            "fri_folding_factor": self.fri_folding_factor,
+            "fri_max_remainder_size": self.fri_max_remainder_size,
+            "zero_knowledge": self.zero_knowledge,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let zero_knowledge = get(value, "zero_knowledge")?;
+        Ok(ProofOptions {
+            num_queries: get_int(value, "num_queries")?,
+            lde_blowup_factor: get_int(value, "lde_blowup_factor")?,
+            grinding_factor: get_int(value, "grinding_factor")?,
+            fri_folding_factor: get_int(value, "fri_folding_factor")?,
+            fri_max_remainder_size: get_int(value, "fri_max_remainder_size")?,
+            zero_knowledge: zero_knowledge.as_bool().ok_or(JsonError::InvalidValue {
+                field: "zero_knowledge",
+            })?,
+        })
+    }
+}
+
+impl TraceInfo {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "num_base_columns": self.num_base_columns,
+            "num_extension_columns": self.num_extension_columns,
+            "num_preprocessed_columns": self.num_preprocessed_columns,
+            "trace_len": self.trace_len,
+            "meta": hex_to_json(&self.meta),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(TraceInfo {
+            num_base_columns: get_int(value, "num_base_columns")?,
+            num_extension_columns: get_int(value, "num_extension_columns")?,
+            num_preprocessed_columns: get_int(value, "num_preprocessed_columns")?,
+            trace_len: get_int(value, "trace_len")?,
+            meta: hex_from_json(get(value, "meta")?, "meta")?,
+        })
+    }
+}
+
+impl<A: Air> Queries<A> {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "base_trace": {
+                "values": fields_to_json(&self.base_trace_values),
+                "proof": self.base_trace_proof.to_json(),
+                "salts": salts_to_json(&self.base_trace_salts),
+            },
+            "extension_trace": {
+                "values": fields_to_json(&self.extension_trace_values),
+                "proof": self.extension_trace_proof.as_ref().map(MerkleProof::to_json),
+                "salts": salts_to_json(&self.extension_trace_salts),
+            },
+            "composition_trace": {
+                "values": fields_to_json(&self.composition_trace_values),
+                "proof": self.composition_trace_proof.to_json(),
+                "salts": salts_to_json(&self.composition_trace_salts),
+            },
+            "preprocessed_trace": {
+                "values": fields_to_json(&self.preprocessed_trace_values),
+                "proof": self.preprocessed_trace_proof.as_ref().map(MerkleProof::to_json),
+            },
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let base_trace = get(value, "base_trace")?;
+        let extension_trace = get(value, "extension_trace")?;
+        let composition_trace = get(value, "composition_trace")?;
+        let preprocessed_trace = get(value, "preprocessed_trace")?;
+        let extension_trace_proof = match get(extension_trace, "proof")? {
+            Value::Null => None,
+            proof => Some(MerkleProof::from_json(proof)?),
+        };
+        let preprocessed_trace_proof = match get(preprocessed_trace, "proof")? {
+            Value::Null => None,
+            proof => Some(MerkleProof::from_json(proof)?),
+        };
+        Ok(Queries {
+            base_trace_values: fields_from_json(get(base_trace, "values")?, "values")?,
+            extension_trace_values: fields_from_json(get(extension_trace, "values")?, "values")?,
+            composition_trace_values: fields_from_json(
+                get(composition_trace, "values")?,
+                "values",
+            )?,
+            preprocessed_trace_values: fields_from_json(
+                get(preprocessed_trace, "values")?,
+                "values",
+            )?,
+            base_trace_proof: MerkleProof::from_json(get(base_trace, "proof")?)?,
+            extension_trace_proof,
+            composition_trace_proof: MerkleProof::from_json(get(composition_trace, "proof")?)?,
+            preprocessed_trace_proof,
+            base_trace_salts: salts_from_json(get(base_trace, "salts")?, "salts")?,
+            extension_trace_salts: salts_from_json(get(extension_trace, "salts")?, "salts")?,
+            composition_trace_salts: salts_from_json(get(composition_trace, "salts")?, "salts")?,
+        })
+    }
+}
+
+impl<A: Air, D: HashFn> Proof<A, D> {
+    /// Encodes the proof as a JSON document
+    pub fn to_json(&self) -> Value {
+        json!({
+            "hash_fn": self.hash_fn,
+            "options": self.options.to_json(),
+            "trace_info": self.trace_info.to_json(),
+            "public_inputs": serialized_to_json(&self.public_inputs),
+            "commitments": {
+                "base_trace": hex_to_json(&self.base_trace_commitment),
+                "extension_trace": self.extension_trace_commitment.as_deref().map(hex_to_json),
+                "composition_trace": hex_to_json(&self.composition_trace_commitment),
+            },
+            "ood_evaluations": {
+                "execution_trace": fields_to_json(&self.execution_trace_ood_evals),
+                "composition_trace": fields_to_json(&self.composition_trace_ood_evals),
+            },
+            "fri_proof": self.fri_proof.to_json(),
+            "pow_nonce": self.pow_nonce,
+            "queries": self.trace_queries.to_json(),
+        })
+    }
+
+    /// Decodes a proof from a JSON document created with [Proof::to_json]
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let hash_fn = get(value, "hash_fn")?;
+        let commitments = get(value, "commitments")?;
+        let ood_evals = get(value, "ood_evaluations")?;
+        let extension_trace_commitment = match get(commitments, "extension_trace")? {
+            Value::Null => None,
+            commitment => Some(hex_from_json(commitment, "extension_trace")?),
+        };
+        Ok(Proof {
+            hash_fn: hash_fn
+                .as_str()
+                .ok_or(JsonError::InvalidValue { field: "hash_fn" })?
+                .to_string(),
+            options: ProofOptions::from_json(get(value, "options")?)?,
+            trace_info: TraceInfo::from_json(get(value, "trace_info")?)?,
+            base_trace_commitment: hex_from_json(get(commitments, "base_trace")?, "base_trace")?,
+            extension_trace_commitment,
+            composition_trace_commitment: hex_from_json(
+                get(commitments, "composition_trace")?,
+                "composition_trace",
+            )?,
+            fri_proof: FriProof::from_json(get(value, "fri_proof")?)?,
+            pow_nonce: get_int(value, "pow_nonce")?,
+            trace_queries: Queries::from_json(get(value, "queries")?)?,
+            public_inputs: serialized_from_json(get(value, "public_inputs")?, "public_inputs")?,
+            execution_trace_ood_evals: fields_from_json(
+                get(ood_evals, "execution_trace")?,
+                "execution_trace",
+            )?,
+            composition_trace_ood_evals: fields_from_json(
+                get(ood_evals, "composition_trace")?,
+                "composition_trace",
+            )?,
+            _digest: PhantomData,
+        })
+    }
+}
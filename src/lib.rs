@@ -2,6 +2,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![feature(
     allocator_api,
+    associated_type_bounds,
     let_chains,
     array_windows,
     array_chunks,
@@ -14,17 +15,39 @@
 #[macro_use]
 mod macros;
 mod air;
+mod batch;
+#[cfg(feature = "prover")]
 pub mod calculator;
 pub mod challenges;
+#[cfg(feature = "prover")]
 pub mod channel;
+#[cfg(feature = "prover")]
 mod composer;
 pub mod constraints;
+#[cfg(feature = "prover")]
+pub mod degree;
+pub mod display;
+pub mod format;
 pub mod fri;
+pub mod hash;
 pub mod hints;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lookup;
+#[cfg(feature = "prover")]
 pub mod matrix;
 pub mod merkle;
+pub mod merkle_path;
+pub mod multi;
+pub mod observer;
+pub mod periodic;
+pub mod permutation;
+#[cfg(feature = "prover")]
 pub mod prover;
 pub mod random;
+pub mod range_check;
+pub mod report;
+pub mod rescue_prime;
 pub mod trace;
 pub mod utils;
 mod verifier;
@@ -32,6 +55,11 @@ mod verifier;
 #[macro_use]
 extern crate alloc;
 pub use air::Air;
+#[cfg(feature = "prover")]
+pub use air::AirWarning;
+#[cfg(feature = "prover")]
+pub use air::GpuAir;
+use alloc::string::String;
 use alloc::vec::Vec;
 use ark_ff::BigInteger;
 use ark_ff::FftField;
@@ -40,6 +68,9 @@ use ark_ff::PrimeField;
 use ark_poly::domain::DomainCoeff;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
+pub use batch::BatchVerificationReport;
+pub use batch::BatchVerifier;
+use core::marker::PhantomData;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Mul;
@@ -48,20 +79,39 @@ use core::ops::Sub;
 use core::ops::SubAssign;
 use fri::FriOptions;
 use fri::FriProof;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuAdd;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuFftField;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuField;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuMul;
+use hash::HashFn;
+#[cfg(feature = "prover")]
 pub use matrix::Matrix;
+#[cfg(feature = "derive")]
+pub use ministark_derive::Challenge;
+#[cfg(feature = "derive")]
+pub use ministark_derive::Column;
+#[cfg(feature = "derive")]
+pub use ministark_derive::Hint;
+#[cfg(feature = "prover")]
 pub use prover::Prover;
+#[cfg(feature = "prover")]
+use prover::ProvingError;
+use sha2::Sha256;
 use trace::Queries;
+#[cfg(feature = "prover")]
 pub use trace::Trace;
 pub use trace::TraceInfo;
+pub use verifier::VerificationError;
+pub use verifier::VerificationKey;
+pub use verifier::VerificationPolicy;
 
 // TODO: include ability to specify:
 // - base field
 // - extension field
-// - hashing function
 #[derive(Debug, Clone, Copy, CanonicalSerialize, CanonicalDeserialize, PartialEq, Eq)]
 pub struct ProofOptions {
     pub num_queries: u8,
@@ -69,14 +119,17 @@ pub struct ProofOptions {
     pub grinding_factor: u8,
     pub fri_folding_factor: u8,
     pub fri_max_remainder_size: u8,
+    /// Hides the execution trace from the verifier when enabled
+    pub zero_knowledge: bool,
 }
 
 impl ProofOptions {
     pub const MIN_NUM_QUERIES: u8 = 1;
     pub const MAX_NUM_QUERIES: u8 = 128;
-    pub const MIN_BLOWUP_FACTOR: u8 = 1;
+    pub const MIN_BLOWUP_FACTOR: u8 = 2;
     pub const MAX_BLOWUP_FACTOR: u8 = 64;
     pub const MAX_GRINDING_FACTOR: u8 = 32;
+    pub const FRI_FOLDING_FACTORS: [u8; 4] = [2, 4, 8, 16];
 
     pub fn new(
         num_queries: u8,
@@ -85,25 +138,78 @@ impl ProofOptions {
         fri_folding_factor: u8,
         fri_max_remainder_size: u8,
     ) -> Self {
-        assert!(num_queries >= Self::MIN_NUM_QUERIES);
-        assert!(num_queries <= Self::MAX_NUM_QUERIES);
-        assert!(lde_blowup_factor.is_power_of_two());
-        assert!(lde_blowup_factor >= Self::MIN_BLOWUP_FACTOR);
-        assert!(lde_blowup_factor <= Self::MAX_BLOWUP_FACTOR);
-        assert!(grinding_factor <= Self::MAX_GRINDING_FACTOR);
         ProofOptions {
             num_queries,
             lde_blowup_factor,
             grinding_factor,
             fri_folding_factor,
             fri_max_remainder_size,
+            zero_knowledge: false,
+        }
+    }
+
+    /// Checks the options are within the ranges supported by the prover
+    #[cfg(feature = "prover")]
+    pub fn validate(&self) -> Result<(), ProvingError> {
+        if !(Self::MIN_NUM_QUERIES..=Self::MAX_NUM_QUERIES).contains(&self.num_queries) {
+            return Err(ProvingError::InvalidNumQueries {
+                num_queries: self.num_queries,
+            });
+        }
+
+        if !self.lde_blowup_factor.is_power_of_two()
+            || !(Self::MIN_BLOWUP_FACTOR..=Self::MAX_BLOWUP_FACTOR)
+                .contains(&self.lde_blowup_factor)
+        {
+            return Err(ProvingError::InvalidBlowupFactor {
+                lde_blowup_factor: self.lde_blowup_factor,
+            });
+        }
+
+        if self.grinding_factor > Self::MAX_GRINDING_FACTOR {
+            return Err(ProvingError::GrindingFactorTooLarge {
+                grinding_factor: self.grinding_factor,
+            });
+        }
+
+        if !Self::FRI_FOLDING_FACTORS.contains(&self.fri_folding_factor) {
+            return Err(ProvingError::UnsupportedFriFoldingFactor {
+                fri_folding_factor: self.fri_folding_factor,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Enables zero-knowledge. The trace polynomials are randomized which
+    /// doubles the degree bound checked by FRI so the LDE blowup factor must
+    /// be at least four to leave FRI a blowup factor of at least two.
+    #[cfg(feature = "prover")]
+    pub fn with_zero_knowledge(mut self) -> Result<Self, ProvingError> {
+        if self.lde_blowup_factor < 4 {
+            return Err(ProvingError::ZeroKnowledgeBlowupFactor {
+                lde_blowup_factor: self.lde_blowup_factor,
+            });
+        }
+        self.zero_knowledge = true;
+        Ok(self)
+    }
+
+    /// Blowup factor of the polynomial checked with FRI. This is half the LDE
+    /// blowup factor in zero-knowledge mode since the degree of the randomized
+    /// trace polynomials exceeds the trace length.
+    pub fn fri_blowup_factor(&self) -> u8 {
+        if self.zero_knowledge {
+            self.lde_blowup_factor / 2
+        } else {
+            self.lde_blowup_factor
         }
     }
 
     pub fn into_fri_options(self) -> FriOptions {
         // TODO: move fri params into struct
         FriOptions::new(
-            self.lde_blowup_factor.into(),
+            self.fri_blowup_factor().into(),
             self.fri_folding_factor.into(),
             self.fri_max_remainder_size.into(),
         )
@@ -112,7 +218,9 @@ impl ProofOptions {
 
 /// A proof generated by a mini-stark prover
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct Proof<A: Air> {
+pub struct Proof<A: Air, D: HashFn = Sha256> {
+    /// Name of the hash function used to generate the proof
+    pub hash_fn: String,
     pub options: ProofOptions,
     pub trace_info: TraceInfo,
     pub base_trace_commitment: Vec<u8>,
@@ -124,30 +232,33 @@ pub struct Proof<A: Air> {
     pub public_inputs: A::PublicInputs,
     pub execution_trace_ood_evals: Vec<A::Fq>,
     pub composition_trace_ood_evals: Vec<A::Fq>,
+    _digest: PhantomData<D>,
 }
 
-impl<A: Air> Proof<A> {
+impl<A: Air, D: HashFn> Proof<A, D> {
     pub fn conjectured_security_level(&self) -> usize {
         let prime_field_bits = <<A::Fp as Field>::BasePrimeField as PrimeField>::MODULUS.num_bits();
         let fq_bits = prime_field_bits as usize * A::Fq::extension_degree() as usize;
-        let sha256_collision_resistance_security = 128;
+        let lde_blowup_factor = self.options.lde_blowup_factor as usize;
+        let fri_blowup_factor = self.options.fri_blowup_factor() as usize;
+        // the size of the LDE domain is the same with or without zero-knowledge
+        let fri_degree_bound = (self.trace_info.trace_len * lde_blowup_factor)
+            .checked_div(fri_blowup_factor)
+            .unwrap_or(0);
         utils::conjectured_security_level(
             fq_bits,
-            sha256_collision_resistance_security,
-            self.options.lde_blowup_factor.into(),
-            self.trace_info.trace_len,
+            D::COLLISION_RESISTANCE,
+            fri_blowup_factor,
+            fri_degree_bound,
             self.options.num_queries.into(),
             self.options.grinding_factor.into(),
         )
     }
 }
 
-pub trait StarkExtensionOf<Fp: GpuFftField + FftField>:
-    GpuField<FftField = Fp>
-    + Field<BasePrimeField = Fp>
+pub trait StarkExtensionOf<Fp: FftField>:
+    Field<BasePrimeField = Fp>
     + DomainCoeff<Fp>
-    + GpuMul<Fp>
-    + GpuAdd<Fp>
     + From<Fp>
     + MulAssign<Fp>
     + AddAssign<Fp>
@@ -166,12 +277,9 @@ pub trait StarkExtensionOf<Fp: GpuFftField + FftField>:
 
 impl<T, F> StarkExtensionOf<F> for T
 where
-    F: GpuFftField + FftField,
-    T: GpuField<FftField = F>
-        + Field<BasePrimeField = F>
+    F: FftField,
+    T: Field<BasePrimeField = F>
         + DomainCoeff<F>
-        + GpuMul<F>
-        + GpuAdd<F>
         + MulAssign<F>
         + AddAssign<F>
         + SubAssign<F>
@@ -187,3 +295,19 @@ where
         + From<F>,
 {
 }
+
+/// A [StarkExtensionOf] `Fp` whose arithmetic with `Fp` is also implemented on
+/// the GPU. Only the prover needs this.
+#[cfg(feature = "prover")]
+pub trait GpuStarkExtensionOf<Fp: GpuFftField + FftField>:
+    StarkExtensionOf<Fp> + GpuField<FftField = Fp> + GpuMul<Fp> + GpuAdd<Fp>
+{
+}
+
+#[cfg(feature = "prover")]
+impl<T, F> GpuStarkExtensionOf<F> for T
+where
+    F: GpuFftField + FftField,
+    T: StarkExtensionOf<F> + GpuField<FftField = F> + GpuMul<F> + GpuAdd<F>,
+{
+}
@@ -0,0 +1,177 @@
+//! LogUp lookup argument from <https://eprint.iacr.org/2022/1530>.
+//!
+//! Proves the tuple of `inputs` in each row of the trace is one of the tuples
+//! of `table`. Tuples are compressed to a single value `t_0 + α*t_1 + ...`
+//! with a random challenge `α` and the argument checks the sum
+//! `Σ 1/(z - input_i) - m_i/(z - table_i)` over all rows is zero for a random
+//! challenge `z`. `m_i` is the number of times the `i`th row of the table is
+//! looked up. The sum of all rows before the current row is accumulated in a
+//! "running sum" extension column and its final value is given by a hint.
+#[cfg(feature = "prover")]
+use crate::challenges::Challenges;
+use crate::constraints::AlgebraicExpression;
+use crate::constraints::ExecutionTraceColumn;
+#[cfg(feature = "prover")]
+use crate::constraints::FieldConstant;
+use crate::constraints::Hint;
+use crate::constraints::VerifierChallenge;
+#[cfg(feature = "prover")]
+use crate::Matrix;
+use crate::StarkExtensionOf;
+use alloc::vec::Vec;
+#[cfg(feature = "prover")]
+use ark_ff::batch_inversion;
+use ark_ff::FftField;
+use ark_ff::Zero;
+#[cfg(feature = "prover")]
+use ark_poly::EvaluationDomain;
+#[cfg(feature = "prover")]
+use ark_poly::Radix2EvaluationDomain;
+#[cfg(feature = "prover")]
+use gpu_poly::prelude::PageAlignedAllocator;
+#[cfg(feature = "prover")]
+use gpu_poly::GpuVec;
+
+/// Lookup of a tuple of expressions into a table of tuples
+#[derive(Clone, Debug)]
+pub struct LogUp<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+    inputs: Vec<AlgebraicExpression<Fp, Fq>>,
+    table: Vec<AlgebraicExpression<Fp, Fq>>,
+    multiplicities: AlgebraicExpression<Fp, Fq>,
+    running_sum: usize,
+    alpha: usize,
+    z: usize,
+    sum: usize,
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> LogUp<Fp, Fq> {
+    /// Creates a lookup of `inputs` into `table` where `multiplicities` is the
+    /// number of times each row of the table is looked up. Expressions can
+    /// only reference base columns and challenges. The challenge `alpha` is
+    /// only used if the tuples have more than one element.
+    pub fn new(
+        inputs: Vec<AlgebraicExpression<Fp, Fq>>,
+        table: Vec<AlgebraicExpression<Fp, Fq>>,
+        multiplicities: AlgebraicExpression<Fp, Fq>,
+        running_sum: impl ExecutionTraceColumn,
+        alpha: impl VerifierChallenge,
+        z: impl VerifierChallenge,
+        sum: impl Hint,
+    ) -> Self {
+        assert!(!inputs.is_empty(), "tuples can't be empty");
+        assert_eq!(inputs.len(), table.len(), "tuples must have the same size");
+        LogUp {
+            inputs,
+            table,
+            multiplicities,
+            running_sum: running_sum.index(),
+            alpha: alpha.index(),
+            z: z.index(),
+            sum: sum.index(),
+        }
+    }
+
+    /// Index of the running sum column
+    pub fn running_sum_column(&self) -> usize {
+        self.running_sum
+    }
+
+    /// Constraints for a trace with `trace_len` rows
+    pub fn constraints(&self, trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        self.constraints_with_sum(trace_len, self.sum.hint())
+    }
+
+    /// Constraints where the running sum ends on `sum` rather than the hint
+    pub(crate) fn constraints_with_sum(
+        &self,
+        trace_len: usize,
+        sum: AlgebraicExpression<Fp, Fq>,
+    ) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        let z = self.z.challenge();
+        let input = &z - compress(&self.inputs, self.alpha);
+        let table = &z - compress(&self.table, self.alpha);
+        // `(next - curr) = 1/input - m/table` without the division
+        let increment = |difference: AlgebraicExpression<Fp, Fq>| {
+            &input * &table * difference - (&table - &self.multiplicities * &input)
+        };
+        let running_sum = self.running_sum;
+        vec![
+            running_sum.curr().on_first_row(trace_len),
+            increment(running_sum.next() - running_sum.curr()).on_transition(trace_len),
+            increment(sum - running_sum.curr()).on_last_row(trace_len),
+        ]
+    }
+
+    /// Hints for a lookup that's fully contained in the trace
+    pub fn hints(&self) -> Vec<(usize, Fq)> {
+        vec![(self.sum, Fq::zero())]
+    }
+
+    /// Builds the running sum column from the base columns of a trace
+    #[cfg(feature = "prover")]
+    pub fn build_running_sum(
+        &self,
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fq>,
+    ) -> GpuVec<Fq> {
+        let z = self.z.challenge();
+        let input = &z - compress(&self.inputs, self.alpha);
+        let table = &z - compress(&self.table, self.alpha);
+        let mut input_evals = eval_rows(&input, base_columns, challenges);
+        let mut table_evals = eval_rows(&table, base_columns, challenges);
+        let multiplicities = eval_rows(&self.multiplicities, base_columns, challenges);
+        batch_inversion(&mut input_evals);
+        batch_inversion(&mut table_evals);
+
+        let mut running_sum = Vec::with_capacity_in(base_columns.num_rows(), PageAlignedAllocator);
+        let mut acc = Fq::zero();
+        for ((input_inv, table_inv), multiplicity) in
+            input_evals.into_iter().zip(table_evals).zip(multiplicities)
+        {
+            running_sum.push(acc);
+            acc += input_inv - multiplicity * table_inv;
+        }
+        running_sum
+    }
+}
+
+/// Compresses a tuple to `t_0 + alpha * t_1 + alpha^2 * t_2 + ...`
+pub(crate) fn compress<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    tuple: &[AlgebraicExpression<Fp, Fq>],
+    alpha: usize,
+) -> AlgebraicExpression<Fp, Fq> {
+    let alpha = alpha.challenge();
+    let (last, rest) = tuple.split_last().unwrap();
+    rest.iter()
+        .rev()
+        .fold(last.clone(), |acc, element| acc * &alpha + element)
+}
+
+/// Evaluates an expression of base columns and challenges on each row of a
+/// trace
+#[cfg(feature = "prover")]
+pub(crate) fn eval_rows<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    expr: &AlgebraicExpression<Fp, Fq>,
+    base_columns: &Matrix<Fp>,
+    challenges: &Challenges<Fq>,
+) -> Vec<Fq> {
+    let trace_len = base_columns.num_rows();
+    let trace_domain = Radix2EvaluationDomain::<Fp>::new(trace_len).unwrap();
+    trace_domain
+        .elements()
+        .enumerate()
+        .map(|(row, x)| {
+            expr.eval(
+                &FieldConstant::Fp(x),
+                &|_| panic!("can't reference hints"),
+                &|i| FieldConstant::Fq(challenges[i]),
+                &|_| panic!("can't reference periodic columns"),
+                &|col, offset| {
+                    let pos = (row as isize + offset).rem_euclid(trace_len as isize) as usize;
+                    FieldConstant::Fp(base_columns[col][pos])
+                },
+            )
+            .as_fq()
+        })
+        .collect()
+}
@@ -1,5 +1,6 @@
 use crate::constraints::ExecutionTraceColumn;
 use crate::merkle::MerkleTree;
+use crate::merkle::Salt;
 use crate::utils::horner_evaluate;
 use alloc::string::String;
 use alloc::string::ToString;
@@ -187,6 +188,17 @@ impl<F: Field> Matrix<F> {
     }
 
     pub fn commit_to_rows<D: Digest>(&self) -> MerkleTree<D> {
+        self.commit_to_rows_impl(None)
+    }
+
+    /// Commits to the rows of the matrix. Each row is hashed with its salt.
+    pub fn commit_to_salted_rows<D: Digest>(&self, salts: &[Salt]) -> MerkleTree<D> {
+        // invariant: the prover draws a salt for every row it commits to
+        debug_assert_eq!(self.num_rows(), salts.len());
+        self.commit_to_rows_impl(Some(salts))
+    }
+
+    fn commit_to_rows_impl<D: Digest>(&self, salts: Option<&[Salt]>) -> MerkleTree<D> {
         let num_rows = self.num_rows();
 
         let mut row_hashes = vec![Default::default(); num_rows];
@@ -211,7 +223,11 @@ impl<F: Field> Matrix<F> {
                     row_bytes.clear();
                     self.read_row(offset + i, &mut row_buffer);
                     row_buffer.serialize_compressed(&mut row_bytes).unwrap();
-                    *row_hash = D::new_with_prefix(&row_bytes).finalize();
+                    let mut hasher = D::new_with_prefix(&row_bytes);
+                    if let Some(salts) = salts {
+                        hasher.update(salts[offset + i]);
+                    }
+                    *row_hash = hasher.finalize();
                 }
             });
 
@@ -343,7 +359,7 @@ impl<F: Field> Clone for Matrix<F> {
     }
 }
 
-impl<F: GpuField> IntoIterator for Matrix<F> {
+impl<F: Field> IntoIterator for Matrix<F> {
     type Item = GpuVec<F>;
     type IntoIter = <Vec<GpuVec<F>> as IntoIterator>::IntoIter;
 
@@ -366,7 +382,7 @@ impl<F: Field> Deref for Matrix<F> {
     }
 }
 
-impl<F: GpuField, C: ExecutionTraceColumn> Index<C> for Matrix<F> {
+impl<F: Field, C: ExecutionTraceColumn> Index<C> for Matrix<F> {
     type Output = GpuVec<F>;
 
     fn index(&self, col: C) -> &Self::Output {
@@ -374,7 +390,7 @@ impl<F: GpuField, C: ExecutionTraceColumn> Index<C> for Matrix<F> {
     }
 }
 
-impl<F: GpuField, C: ExecutionTraceColumn> IndexMut<C> for Matrix<F> {
+impl<F: Field, C: ExecutionTraceColumn> IndexMut<C> for Matrix<F> {
     fn index_mut(&mut self, col: C) -> &mut Self::Output {
         &mut self.0[col.index()]
     }
@@ -1,4 +1,10 @@
 //! Use arkwork_rs or re make this. Just used for personal education.
+#[cfg(feature = "json")]
+use crate::json;
+#[cfg(feature = "json")]
+use crate::json::JsonError;
+#[cfg(feature = "json")]
+use crate::json::Value;
 use alloc::vec::Vec;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
@@ -19,6 +25,10 @@ pub enum MerkleTreeError {
     InvalidProof,
 }
 
+/// Random bytes hashed with a leaf's value to hide it. Without a salt the hash
+/// of an unopened sibling leaf reveals information about its value.
+pub type Salt = [u8; 32];
+
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
 pub struct MerkleProof(Vec<u8>);
 
@@ -35,6 +45,16 @@ impl MerkleProof {
             .map(|chunk| Output::<D>::from_slice(chunk).clone())
             .collect()
     }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Value {
+        json::hex_to_json(&self.0)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(MerkleProof(json::hex_from_json(value, "proof")?))
+    }
 }
 
 /// Merkle tree implemented as a full power-of-two arity tree.
@@ -82,7 +102,6 @@ impl<D: Digest> MerkleTree<D> {
             });
         }
 
-        // TODO: batch proofs
         // TODO: could omit leaf_nodes[index]
         let mut path = vec![
             self.leaf_nodes[index].clone(),
@@ -98,6 +117,46 @@ impl<D: Digest> MerkleTree<D> {
         Ok(MerkleProof::new::<D>(path))
     }
 
+    /// Generates a single proof for multiple leaves. Each node needed to
+    /// recompute the root is included once. Nodes that can be computed from
+    /// the leaves themselves are omitted.
+    pub fn prove_batch(&self, indices: &[usize]) -> Result<MerkleProof, MerkleTreeError> {
+        let n = self.leaf_nodes.len();
+        let mut nodes = indices
+            .iter()
+            .map(|&i| {
+                if i < n {
+                    Ok(i + n)
+                } else {
+                    Err(MerkleTreeError::LeafIndexOutOfBounds { n, i })
+                }
+            })
+            .collect::<Result<Vec<usize>, MerkleTreeError>>()?;
+        nodes.sort();
+        nodes.dedup();
+
+        // walk up the tree one layer at a time. A sibling is only added to the
+        // path if it can't be computed from the nodes in the current layer.
+        let mut path = Vec::new();
+        while !nodes.is_empty() && nodes[0] != 1 {
+            let mut parents = Vec::with_capacity(nodes.len());
+            let mut i = 0;
+            while i < nodes.len() {
+                let node = nodes[i];
+                if nodes.get(i + 1) == Some(&(node ^ 1)) {
+                    i += 2;
+                } else {
+                    path.push(self.node(node ^ 1).clone());
+                    i += 1;
+                }
+                parents.push(node >> 1);
+            }
+            nodes = parents;
+        }
+
+        Ok(MerkleProof::new::<D>(path))
+    }
+
     pub fn verify(
         root: &Output<D>,
         proof: &[Output<D>],
@@ -124,6 +183,76 @@ impl<D: Digest> MerkleTree<D> {
             Err(MerkleTreeError::InvalidProof)
         }
     }
+    /// Verifies a proof generated by [MerkleTree::prove_batch]. `leaves` are the
+    /// leaf nodes at each of the `positions` in a tree with `num_leaves` leaves.
+    pub fn verify_batch(
+        root: &Output<D>,
+        proof: &[Output<D>],
+        positions: &[usize],
+        leaves: &[Output<D>],
+        num_leaves: usize,
+    ) -> Result<(), MerkleTreeError> {
+        if !num_leaves.is_power_of_two() {
+            return Err(MerkleTreeError::NumberOfLeavesNotPowerOfTwo { n: num_leaves });
+        } else if positions.is_empty() || positions.len() != leaves.len() {
+            return Err(MerkleTreeError::InvalidProof);
+        }
+
+        let mut nodes = Vec::with_capacity(positions.len());
+        for (&i, leaf) in positions.iter().zip(leaves) {
+            if i >= num_leaves {
+                return Err(MerkleTreeError::LeafIndexOutOfBounds { n: num_leaves, i });
+            }
+            nodes.push((i + num_leaves, leaf.clone()));
+        }
+        nodes.sort_by_key(|(i, _)| *i);
+        // the same position can be queried more than once but must have one leaf
+        if nodes
+            .array_windows()
+            .any(|[(i, a), (j, b)]| i == j && a != b)
+        {
+            return Err(MerkleTreeError::InvalidProof);
+        }
+        nodes.dedup_by_key(|(i, _)| *i);
+
+        let mut proof = proof.iter();
+        while nodes[0].0 != 1 {
+            let mut parents = Vec::with_capacity(nodes.len());
+            let mut nodes_iter = nodes.into_iter().peekable();
+            while let Some((i, node)) = nodes_iter.next() {
+                let sibling = match nodes_iter.next_if(|(j, _)| *j == i ^ 1) {
+                    Some((_, sibling)) => sibling,
+                    None => proof.next().ok_or(MerkleTreeError::InvalidProof)?.clone(),
+                };
+                let mut hasher = D::new();
+                if i % 2 == 0 {
+                    hasher.update(node);
+                    hasher.update(sibling);
+                } else {
+                    hasher.update(sibling);
+                    hasher.update(node);
+                }
+                parents.push((i >> 1, hasher.finalize()));
+            }
+            nodes = parents;
+        }
+
+        if proof.next().is_none() && *root == nodes[0].1 {
+            Ok(())
+        } else {
+            Err(MerkleTreeError::InvalidProof)
+        }
+    }
+
+    /// Returns the node at index `i` where leaf nodes start at index `n`
+    fn node(&self, i: usize) -> &Output<D> {
+        let n = self.leaf_nodes.len();
+        if i < n {
+            &self.nodes[i]
+        } else {
+            &self.leaf_nodes[i - n]
+        }
+    }
 }
 
 #[cfg(feature = "parallel")]
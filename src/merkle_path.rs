@@ -0,0 +1,395 @@
+//! AIR for Merkle authentication paths.
+//!
+//! [MerklePathAir] proves a leaf belongs to a
+//! [MerkleTree](crate::merkle::MerkleTree) that uses
+//! [RescuePrime](crate::rescue_prime::RescuePrime) as its hash function.
+//!
+//! This is the only part of recursive verification, i.e. proving
+//! [Proof::verify](crate::Proof::verify) with miniSTARK, that exists so far.
+//! A proof can't be verified recursively until the rest is arithmetized:
+//! - TODO: the public coin transcript
+//! - TODO: the out-of-domain constraint evaluation
+//! - TODO: the FRI folding done by `FriVerifier::verify_generic`
+//! - TODO: an end-to-end test that recursively proves a Fibonacci proof over
+//!   Goldilocks with `Fq3`
+//!
+//! Each hash takes up eight rows of the trace. The first row of a cycle holds
+//! the input to the permutation and each following row holds the state after
+//! a round. Round constants are periodic columns.
+use crate::constraints::AlgebraicExpression;
+use crate::constraints::ExecutionTraceColumn;
+use crate::constraints::FieldConstant;
+use crate::constraints::Periodic;
+use crate::periodic::PeriodicColumn;
+use crate::rescue_prime;
+use crate::rescue_prime::ALPHA;
+use crate::rescue_prime::CAPACITY;
+use crate::rescue_prime::DIGEST_SIZE;
+use crate::rescue_prime::DOMAIN_INDEX;
+use crate::rescue_prime::ELEMENT_DOMAIN;
+use crate::rescue_prime::LENGTH_INDEX;
+use crate::rescue_prime::NUM_ROUNDS;
+use crate::rescue_prime::STATE_WIDTH;
+use crate::Air;
+#[cfg(feature = "prover")]
+use crate::Matrix;
+use crate::ProofOptions;
+#[cfg(feature = "prover")]
+use crate::Trace;
+use crate::TraceInfo;
+use alloc::vec::Vec;
+use ark_ff::Field;
+use ark_ff::One;
+use ark_ff::Zero;
+use ark_ff_optimized::fp64::Fp;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "prover")]
+use gpu_poly::prelude::PageAlignedAllocator;
+
+/// Number of rows used to compute a single hash
+pub const HASH_CYCLE_LEN: usize = NUM_ROUNDS + 1;
+
+// Trace layout
+const STATE: usize = 0;
+const SIBLING: usize = STATE + STATE_WIDTH;
+const BIT: usize = SIBLING + DIGEST_SIZE;
+const INDEX: usize = BIT + 1;
+const POWER: usize = INDEX + 1;
+const NUM_COLUMNS: usize = POWER + 1;
+
+// Position of the digest and the inputs to the 2-to-1 hash in the state
+const DIGEST: usize = STATE + CAPACITY;
+const LEFT: usize = STATE + CAPACITY;
+const RIGHT: usize = LEFT + DIGEST_SIZE;
+
+/// Public inputs of [MerklePathAir]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MerklePathInputs {
+    pub leaf: [Fp; DIGEST_SIZE],
+    pub root: [Fp; DIGEST_SIZE],
+    /// Position of the leaf in the tree
+    pub index: u64,
+    /// Number of hashes between the leaf and the root
+    pub depth: usize,
+}
+
+/// Returns the length of the trace needed for a path of the given depth
+pub fn trace_len(depth: usize) -> usize {
+    let num_rows = depth * HASH_CYCLE_LEN;
+    num_rows
+        .next_power_of_two()
+        .max(TraceInfo::MIN_TRACE_LENGTH)
+}
+
+/// AIR for the authentication path of a leaf in a Rescue-Prime Merkle tree.
+/// The LDE blowup factor must be at least 8 to fit the degree of the
+/// permutation constraints.
+pub struct MerklePathAir {
+    options: ProofOptions,
+    trace_info: TraceInfo,
+    inputs: MerklePathInputs,
+    constraints: Vec<AlgebraicExpression<Fp>>,
+}
+
+impl Air for MerklePathAir {
+    type Fp = Fp;
+    type Fq = Fp;
+    type PublicInputs = MerklePathInputs;
+
+    fn id() -> &'static str {
+        "rescue-prime-merkle-path"
+    }
+
+    fn new(trace_info: TraceInfo, inputs: MerklePathInputs, options: ProofOptions) -> Self {
+        assert!(inputs.depth > 0, "path must contain at least one hash");
+        assert!(
+            inputs.depth * HASH_CYCLE_LEN <= trace_info.trace_len,
+            "trace is too short for a path of depth {}",
+            inputs.depth
+        );
+        let constraints = merkle_path_constraints(trace_info.trace_len, &inputs);
+        MerklePathAir {
+            options,
+            trace_info,
+            inputs,
+            constraints,
+        }
+    }
+
+    fn pub_inputs(&self) -> &MerklePathInputs {
+        &self.inputs
+    }
+
+    fn trace_info(&self) -> &TraceInfo {
+        &self.trace_info
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn constraints(&self) -> Vec<AlgebraicExpression<Fp>> {
+        self.constraints.clone()
+    }
+
+    fn periodic_columns(&self) -> Vec<PeriodicColumn<Fp>> {
+        // constants added after the S-box followed by those added after the
+        // inverse S-box
+        (0..2)
+            .flat_map(|half| (0..STATE_WIDTH).map(move |i| round_constants(half, i)))
+            .map(PeriodicColumn::new)
+            .collect()
+    }
+}
+
+fn merkle_path_constraints(
+    trace_len: usize,
+    inputs: &MerklePathInputs,
+) -> Vec<AlgebraicExpression<Fp>> {
+    use AlgebraicExpression::*;
+    let one = FieldConstant::Fp(Fp::one());
+    let trace_domain = Radix2EvaluationDomain::<Fp>::new(trace_len).unwrap();
+    let cycle_domain = Radix2EvaluationDomain::<Fp>::new(HASH_CYCLE_LEN).unwrap();
+
+    // `x^(n/8)` takes the same value on rows that are the same step of a cycle
+    let cycle_x: AlgebraicExpression<Fp> = X.pow(trace_len / HASH_CYCLE_LEN);
+    // vanishes on the rows at the given step of every cycle
+    let cycle_zerofier = |step: usize| &cycle_x - FieldConstant::Fp(cycle_domain.element(step));
+    // vanishes on a single row
+    let row_zerofier = |row: usize| X - FieldConstant::Fp(trace_domain.element(row));
+    // vanishes on every row
+    let trace_zerofier: AlgebraicExpression<Fp> = X.pow(trace_len) - one;
+
+    let mut constraints = Vec::new();
+
+    // the permutation rounds are applied on all rows except the last of a cycle
+    let mds = rescue_prime::MDS.map(|row| row.map(Fp::from));
+    let mds_inv = invert(mds);
+    let add_constants = (0..STATE_WIDTH)
+        .map(|i| i.periodic())
+        .collect::<Vec<AlgebraicExpression<Fp>>>();
+    let sub_constants = (0..STATE_WIDTH)
+        .map(|i| (STATE_WIDTH + i).periodic())
+        .collect::<Vec<AlgebraicExpression<Fp>>>();
+    let round_selector = cycle_zerofier(NUM_ROUNDS) / &trace_zerofier;
+    for i in 0..STATE_WIDTH {
+        // state after the first half of the round computed from the current row
+        let forward = (0..STATE_WIDTH)
+            .map(|j| (STATE + j).curr().pow(ALPHA as usize) * FieldConstant::Fp(mds[i][j]))
+            .sum::<AlgebraicExpression<Fp>>()
+            + &add_constants[i];
+        // the inverse S-box input computed backwards from the next row
+        let backward = (0..STATE_WIDTH)
+            .map(|j| ((STATE + j).next() - &sub_constants[j]) * FieldConstant::Fp(mds_inv[i][j]))
+            .sum::<AlgebraicExpression<Fp>>();
+        constraints.push((backward.pow(ALPHA as usize) - forward) * &round_selector);
+    }
+    constraints.push((INDEX.next() - INDEX.curr()) * &round_selector);
+    constraints.push((POWER.next() - POWER.curr()) * &round_selector);
+
+    // the first row of each cycle absorbs a node and its sibling
+    let input_selector = cycle_zerofier(0);
+    for i in 0..CAPACITY {
+        let value = match i {
+            LENGTH_INDEX => Fp::from((2 * DIGEST_SIZE) as u64),
+            DOMAIN_INDEX => Fp::from(ELEMENT_DOMAIN),
+            _ => Fp::zero(),
+        };
+        constraints.push(((STATE + i).curr() - FieldConstant::Fp(value)) / &input_selector);
+    }
+    let bit: AlgebraicExpression<Fp> = BIT.curr();
+    constraints.push(&bit * (&bit - one) / &input_selector);
+
+    // the digest on the last row of a cycle is absorbed by the next cycle
+    let load_selector = row_zerofier(trace_len - 1) / cycle_zerofier(NUM_ROUNDS);
+    let next_bit: AlgebraicExpression<Fp> = BIT.next();
+    let next_not_bit = -&next_bit + one;
+    for i in 0..DIGEST_SIZE {
+        let digest: AlgebraicExpression<Fp> = (DIGEST + i).curr();
+        let sibling: AlgebraicExpression<Fp> = (SIBLING + i).next();
+        let left = &digest * &next_not_bit + &sibling * &next_bit;
+        let right = digest * &next_bit + sibling * &next_not_bit;
+        constraints.push(((LEFT + i).next() - left) * &load_selector);
+        constraints.push(((RIGHT + i).next() - right) * &load_selector);
+    }
+    constraints.push((INDEX.next() - (INDEX.curr() + next_bit * POWER.next())) * &load_selector);
+    constraints
+        .push((POWER.next() - POWER.curr() * FieldConstant::Fp(Fp::from(2u64))) * &load_selector);
+
+    // the first cycle absorbs the leaf
+    let first_row = row_zerofier(0);
+    let not_bit = -&bit + one;
+    for i in 0..DIGEST_SIZE {
+        let leaf = FieldConstant::Fp(inputs.leaf[i]);
+        let sibling: AlgebraicExpression<Fp> = (SIBLING + i).curr();
+        let left = &not_bit * leaf + &sibling * &bit;
+        let right = &bit * leaf + sibling * &not_bit;
+        constraints.push(((LEFT + i).curr() - left) / &first_row);
+        constraints.push(((RIGHT + i).curr() - right) / &first_row);
+    }
+    constraints.push((POWER.curr() - one) / &first_row);
+    constraints.push((INDEX.curr() - bit) / &first_row);
+
+    // the last cycle of the path outputs the root
+    let root_row = row_zerofier(inputs.depth * HASH_CYCLE_LEN - 1);
+    for i in 0..DIGEST_SIZE {
+        let root = FieldConstant::Fp(inputs.root[i]);
+        constraints.push(((DIGEST + i).curr() - root) / &root_row);
+    }
+    let index = FieldConstant::Fp(Fp::from(inputs.index));
+    constraints.push((INDEX.curr() - index) / &root_row);
+
+    constraints
+}
+
+/// Returns the values of a round constant at each step of a cycle. `half` is
+/// 0 for the constants added after the S-box and 1 for the constants added
+/// after the inverse S-box.
+fn round_constants(half: usize, element: usize) -> Vec<Fp> {
+    (0..HASH_CYCLE_LEN)
+        .map(|step| match step {
+            round if round < NUM_ROUNDS => {
+                Fp::from(rescue_prime::ROUND_CONSTANTS[2 * round + half][element])
+            }
+            _ => Fp::zero(),
+        })
+        .collect()
+}
+
+/// Inverts a matrix with Gauss-Jordan elimination
+#[allow(clippy::needless_range_loop)]
+fn invert<const N: usize>(mut matrix: [[Fp; N]; N]) -> [[Fp; N]; N] {
+    let mut inverse = [[Fp::zero(); N]; N];
+    for i in 0..N {
+        inverse[i][i] = Fp::one();
+    }
+
+    for col in 0..N {
+        let pivot = (col..N)
+            .find(|&row| !matrix[row][col].is_zero())
+            .expect("matrix is singular");
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let pivot_inv = matrix[col][col].inverse().unwrap();
+        for j in 0..N {
+            matrix[col][j] *= pivot_inv;
+            inverse[col][j] *= pivot_inv;
+        }
+
+        for row in 0..N {
+            let factor = matrix[row][col];
+            if row == col || factor.is_zero() {
+                continue;
+            }
+
+            for j in 0..N {
+                let (a, b) = (matrix[col][j], inverse[col][j]);
+                matrix[row][j] -= factor * a;
+                inverse[row][j] -= factor * b;
+            }
+        }
+    }
+
+    inverse
+}
+
+/// Execution trace of [MerklePathAir]
+#[cfg(feature = "prover")]
+pub struct MerklePathTrace {
+    base_columns: Matrix<Fp>,
+    inputs: MerklePathInputs,
+}
+
+#[cfg(feature = "prover")]
+impl MerklePathTrace {
+    /// Builds the trace for the path from `leaf` to the root. `siblings` are
+    /// ordered from the sibling of the leaf up to the sibling of the root's
+    /// child. Cycles after the root hash the previous node with a zero sibling.
+    pub fn new(leaf: [Fp; DIGEST_SIZE], index: u64, siblings: &[[Fp; DIGEST_SIZE]]) -> Self {
+        let depth = siblings.len();
+        assert!(depth > 0, "path must contain at least one hash");
+        assert!(depth < 64 && index >> depth == 0, "index is out of range");
+        let trace_len = trace_len(depth);
+
+        let mut rows = Vec::with_capacity(trace_len);
+        let mut node = leaf;
+        let mut root = leaf;
+        let mut acc = Fp::zero();
+        let mut power = Fp::one();
+        for cycle in 0..trace_len / HASH_CYCLE_LEN {
+            let (bit, sibling) = match siblings.get(cycle) {
+                Some(sibling) => ((index >> cycle) & 1, *sibling),
+                None => (0, [Fp::zero(); DIGEST_SIZE]),
+            };
+            acc += power * Fp::from(bit);
+            let (left, right) = if bit == 0 {
+                (node, sibling)
+            } else {
+                (sibling, node)
+            };
+
+            let mut state = [Fp::zero(); STATE_WIDTH];
+            state[LENGTH_INDEX] = Fp::from((2 * DIGEST_SIZE) as u64);
+            state[DOMAIN_INDEX] = Fp::from(ELEMENT_DOMAIN);
+            state[LEFT..LEFT + DIGEST_SIZE].copy_from_slice(&left);
+            state[RIGHT..RIGHT + DIGEST_SIZE].copy_from_slice(&right);
+            for step in 0..HASH_CYCLE_LEN {
+                let mut row = [Fp::zero(); NUM_COLUMNS];
+                row[STATE..STATE + STATE_WIDTH].copy_from_slice(&state);
+                row[SIBLING..SIBLING + DIGEST_SIZE].copy_from_slice(&sibling);
+                row[BIT] = Fp::from(bit);
+                row[INDEX] = acc;
+                row[POWER] = power;
+                rows.push(row);
+
+                if step < NUM_ROUNDS {
+                    rescue_prime::apply_round(&mut state, step);
+                }
+            }
+
+            node = state[DIGEST..DIGEST + DIGEST_SIZE].try_into().unwrap();
+            if cycle + 1 == depth {
+                root = node;
+            }
+            power = power.double();
+        }
+
+        let columns = (0..NUM_COLUMNS)
+            .map(|col| {
+                let mut column = Vec::with_capacity_in(trace_len, PageAlignedAllocator);
+                column.extend(rows.iter().map(|row| row[col]));
+                column
+            })
+            .collect();
+
+        MerklePathTrace {
+            base_columns: Matrix::new(columns),
+            inputs: MerklePathInputs {
+                leaf,
+                root,
+                index,
+                depth,
+            },
+        }
+    }
+
+    pub fn inputs(&self) -> &MerklePathInputs {
+        &self.inputs
+    }
+}
+
+#[cfg(feature = "prover")]
+impl Trace for MerklePathTrace {
+    const NUM_BASE_COLUMNS: usize = NUM_COLUMNS;
+
+    type Fp = Fp;
+    type Fq = Fp;
+
+    fn base_columns(&self) -> &Matrix<Fp> {
+        &self.base_columns
+    }
+}
@@ -0,0 +1,373 @@
+//! Proofs of several independent tables that share one transcript.
+//!
+//! Each table has its own [Air], trace length and trace commitments. All
+//! tables draw their challenges from the same public coin so constraints of
+//! different tables can reference the same challenges e.g. for permutation
+//! arguments between tables. The DEEP composition polynomials of all tables
+//! are combined and checked with a single FRI instance.
+//!
+//! Every table is committed to over the LDE domain of the longest trace. This
+//! lets all tables be opened at the same query positions at the cost of a
+//! larger blowup for shorter traces. Preprocessed columns must be extended
+//! over the same domain. Zero-knowledge hides the traces of every table but
+//! the values of the [MultiAir::terminal_columns] are always public.
+use crate::challenges::Challenges;
+use crate::fri::FriProof;
+use crate::hash::HashFn;
+use crate::hints::Hints;
+#[cfg(feature = "prover")]
+use crate::observer::ProverObserver;
+#[cfg(feature = "prover")]
+use crate::prover::prove_tables;
+#[cfg(feature = "prover")]
+use crate::prover::ProverTable;
+#[cfg(feature = "prover")]
+use crate::prover::ProvingError;
+#[cfg(feature = "prover")]
+use crate::prover::TablesProof;
+use crate::random::PublicCoin;
+#[cfg(feature = "prover")]
+use crate::trace::PreprocessedTrace;
+use crate::trace::Queries;
+#[cfg(feature = "prover")]
+pub use crate::trace::TableTrace;
+use crate::utils;
+use crate::verifier::check_options;
+use crate::verifier::serialize;
+use crate::verifier::verify_tables;
+use crate::verifier::VerifierContext;
+use crate::verifier::VerifierTable;
+use crate::Air;
+#[cfg(feature = "prover")]
+use crate::GpuStarkExtensionOf;
+use crate::ProofOptions;
+use crate::StarkExtensionOf;
+use crate::TraceInfo;
+use crate::VerificationError;
+use crate::VerificationKey;
+use crate::VerificationPolicy;
+use alloc::boxed::Box;
+use alloc::string::String;
+#[cfg(feature = "prover")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use ark_ff::BigInteger;
+use ark_ff::FftField;
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use core::marker::PhantomData;
+#[cfg(feature = "prover")]
+use gpu_poly::GpuFftField;
+use sha2::Sha256;
+
+/// AIR of a single table in a multi-table proof
+pub type TableAir<A> = dyn Air<
+    Fp = <A as MultiAir>::Fp,
+    Fq = <A as MultiAir>::Fq,
+    PublicInputs = <A as MultiAir>::PublicInputs,
+>;
+
+pub trait MultiAir: Sized {
+    type Fp: FftField;
+    type Fq: StarkExtensionOf<Self::Fp>;
+    type PublicInputs: CanonicalSerialize + CanonicalDeserialize + Clone;
+
+    /// Identifier of the AIR. Defaults to the type name which isn't guaranteed
+    /// to be stable between compiler versions.
+    fn id() -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Builds the AIR of each table. There is one trace info per table in the
+    /// same order as the traces given to the prover.
+    fn new(trace_infos: Vec<TraceInfo>, inputs: Self::PublicInputs, options: ProofOptions) -> Self;
+
+    /// AIRs of the tables. Tables must use the default domain offset.
+    fn tables(&self) -> &[Box<TableAir<Self>>];
+
+    fn pub_inputs(&self) -> &Self::PublicInputs;
+
+    fn options(&self) -> &ProofOptions;
+
+    /// Extension columns, given as `(table, column)`, whose last values are
+    /// revealed by the proof. These terminals are bound to the transcript
+    /// before the hints are built so tables can be linked through them e.g. a
+    /// permutation argument between two tables can check both running products
+    /// end on the same terminal.
+    fn terminal_columns(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Hints of a table. `terminals` holds the last values of the
+    /// [MultiAir::terminal_columns].
+    fn get_hints(
+        &self,
+        table: usize,
+        challenges: &Challenges<Self::Fq>,
+        _terminals: &[Self::Fq],
+    ) -> Hints<Self::Fq> {
+        self.tables()[table].get_hints(challenges)
+    }
+}
+
+/// Commitments, out-of-domain evaluations and queries of a single table
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct TableProof<A: Air + ?Sized> {
+    pub trace_info: TraceInfo,
+    pub base_trace_commitment: Vec<u8>,
+    pub extension_trace_commitment: Option<Vec<u8>>,
+    pub composition_trace_commitment: Vec<u8>,
+    pub execution_trace_ood_evals: Vec<A::Fq>,
+    pub composition_trace_ood_evals: Vec<A::Fq>,
+    pub trace_queries: Queries<A>,
+}
+
+/// A proof of several tables generated by a [MultiProver]
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiProof<A: MultiAir, D: HashFn = Sha256> {
+    /// Name of the hash function used to generate the proof
+    pub hash_fn: String,
+    pub options: ProofOptions,
+    pub public_inputs: A::PublicInputs,
+    pub tables: Vec<TableProof<TableAir<A>>>,
+    /// Last values of the [MultiAir::terminal_columns]
+    pub terminals: Vec<A::Fq>,
+    pub fri_proof: FriProof<A::Fq>,
+    pub pow_nonce: u64,
+    _digest: PhantomData<D>,
+}
+
+impl<A: MultiAir, D: HashFn> MultiProof<A, D> {
+    pub fn conjectured_security_level(&self) -> usize {
+        let prime_field_bits = <<A::Fp as Field>::BasePrimeField as PrimeField>::MODULUS.num_bits();
+        let fq_bits = prime_field_bits as usize * A::Fq::extension_degree() as usize;
+        let lde_blowup_factor = self.options.lde_blowup_factor as usize;
+        let fri_blowup_factor = self.options.fri_blowup_factor() as usize;
+        // FRI is run over the LDE domain of the longest trace
+        let max_trace_len = self.tables.iter().map(|table| table.trace_info.trace_len);
+        let max_trace_len = max_trace_len.max().unwrap_or(0);
+        let fri_degree_bound = (max_trace_len * lde_blowup_factor)
+            .checked_div(fri_blowup_factor)
+            .unwrap_or(0);
+        utils::conjectured_security_level(
+            fq_bits,
+            D::COLLISION_RESISTANCE,
+            fri_blowup_factor,
+            fri_degree_bound,
+            self.options.num_queries.into(),
+            self.options.grinding_factor.into(),
+        )
+    }
+
+    /// Verifies the proof attests to the expected public inputs. The tables are
+    /// checked against the AIRs built by `A` from the proof's trace infos.
+    pub fn verify(
+        &self,
+        expected_public_inputs: &A::PublicInputs,
+        policy: &VerificationPolicy,
+    ) -> Result<(), VerificationError> {
+        self.verify_with_keys(expected_public_inputs, &[], policy)
+    }
+
+    /// Verifies a proof with tables that open preprocessed columns. Openings of
+    /// each table are checked against the key at the same position in `keys`.
+    pub fn verify_with_keys(
+        &self,
+        expected_public_inputs: &A::PublicInputs,
+        keys: &[Option<&VerificationKey>],
+        policy: &VerificationPolicy,
+    ) -> Result<(), VerificationError> {
+        use VerificationError::*;
+
+        check_options(&self.options)?;
+
+        if self.hash_fn != D::NAME {
+            return Err(HashFnMismatch {
+                expected: D::NAME,
+                actual: self.hash_fn.clone(),
+            });
+        }
+
+        if serialize(&self.public_inputs) != serialize(expected_public_inputs) {
+            return Err(PublicInputsMismatch);
+        }
+
+        let security_level = self.conjectured_security_level();
+        if security_level < policy.min_security_level {
+            return Err(InsufficientSecurity {
+                security_level,
+                min_security_level: policy.min_security_level,
+            });
+        }
+
+        let options = self.options;
+        let trace_infos = self.tables.iter().map(|t| t.trace_info.clone()).collect();
+        let air = A::new(trace_infos, expected_public_inputs.clone(), options);
+        let table_airs = air.tables();
+        if self.tables.is_empty() || table_airs.len() != self.tables.len() {
+            return Err(TableCountMismatch {
+                expected: table_airs.len(),
+                actual: self.tables.len(),
+            });
+        }
+
+        let num_terminals = air.terminal_columns().len();
+        if self.terminals.len() != num_terminals {
+            return Err(TerminalCountMismatch {
+                expected: num_terminals,
+                actual: self.terminals.len(),
+            });
+        }
+
+        let contexts = table_airs
+            .iter()
+            .map(|table_air| VerifierContext::new(table_air.as_ref()))
+            .collect::<Vec<_>>();
+        let tables = self
+            .tables
+            .iter()
+            .zip(table_airs)
+            .zip(&contexts)
+            .enumerate()
+            .map(|(i, ((table, table_air), context))| VerifierTable {
+                air: table_air.as_ref(),
+                context,
+                key: keys.get(i).copied().flatten(),
+                base_trace_commitment: &table.base_trace_commitment,
+                extension_trace_commitment: table.extension_trace_commitment.as_deref(),
+                composition_trace_commitment: &table.composition_trace_commitment,
+                execution_trace_ood_evals: &table.execution_trace_ood_evals,
+                composition_trace_ood_evals: &table.composition_trace_ood_evals,
+                trace_queries: &table.trace_queries,
+            })
+            .collect::<Vec<_>>();
+        let mut public_coin = PublicCoin::<D>::new(&public_coin_seed::<A, D>(&air));
+        verify_tables(
+            &mut public_coin,
+            &tables,
+            &self.terminals,
+            |i, challenges, terminals| air.get_hints(i, challenges, terminals),
+            options,
+            &self.fri_proof,
+            self.pow_nonce,
+        )
+    }
+}
+
+/// Returns the bytes used to seed the public coin of a multi-table proof
+fn public_coin_seed<A: MultiAir, D: HashFn>(air: &A) -> Vec<u8> {
+    let mut seed = Vec::new();
+    air.pub_inputs().serialize_compressed(&mut seed).unwrap();
+    let trace_infos = air
+        .tables()
+        .iter()
+        .map(|table| table.trace_info().clone())
+        .collect::<Vec<TraceInfo>>();
+    trace_infos.serialize_compressed(&mut seed).unwrap();
+    air.options().serialize_compressed(&mut seed).unwrap();
+    seed.extend_from_slice(D::NAME.as_bytes());
+    seed
+}
+
+#[cfg(feature = "prover")]
+pub trait MultiProver {
+    type Fp: GpuFftField + PrimeField;
+    type Fq: GpuStarkExtensionOf<Self::Fp>;
+    type Air: MultiAir<Fp = Self::Fp, Fq = Self::Fq>;
+    type Digest: HashFn;
+
+    fn new(options: ProofOptions) -> Self;
+
+    fn get_pub_inputs(
+        &self,
+        traces: &[&dyn TableTrace<Self::Fp, Self::Fq>],
+    ) -> <Self::Air as MultiAir>::PublicInputs;
+
+    fn options(&self) -> ProofOptions;
+
+    /// Columns of a table that are committed to ahead of time. They must be
+    /// extended over the LDE domain of the longest table (see
+    /// [PreprocessedTrace::with_lde_size]).
+    fn preprocessed_trace(
+        &self,
+        _table: usize,
+    ) -> Option<&PreprocessedTrace<Self::Fp, Self::Digest>> {
+        None
+    }
+
+    /// Generates a single proof for all traces. Traces must be in the same
+    /// order as the tables of the AIR.
+    async fn generate_proof(
+        &self,
+        traces: &[&dyn TableTrace<Self::Fp, Self::Fq>],
+    ) -> Result<MultiProof<Self::Air, Self::Digest>, ProvingError> {
+        self.generate_proof_with_observer(traces, &mut ()).await
+    }
+
+    /// Generates a proof and reports the progress of each stage of each table
+    /// to `observer`
+    async fn generate_proof_with_observer(
+        &self,
+        traces: &[&dyn TableTrace<Self::Fp, Self::Fq>],
+        observer: &mut impl ProverObserver,
+    ) -> Result<MultiProof<Self::Air, Self::Digest>, ProvingError> {
+        let options = self.options();
+        options.validate()?;
+        for trace in traces {
+            trace.validate()?;
+        }
+
+        let trace_infos = traces.iter().map(|trace| trace.info()).collect();
+        let pub_inputs = self.get_pub_inputs(traces);
+        let air = Self::Air::new(trace_infos, pub_inputs, options);
+        let table_airs = air.tables();
+        if traces.is_empty() || table_airs.len() != traces.len() {
+            return Err(ProvingError::TableCountMismatch {
+                expected: table_airs.len(),
+                actual: traces.len(),
+            });
+        }
+
+        for table_air in table_airs {
+            table_air.validate()?;
+        }
+
+        let tables = table_airs
+            .iter()
+            .zip(traces)
+            .enumerate()
+            .map(|(i, (table_air, &trace))| ProverTable {
+                air: table_air.as_ref(),
+                trace,
+                preprocessed_trace: self.preprocessed_trace(i),
+            })
+            .collect::<Vec<_>>();
+        let mut public_coin = PublicCoin::new(&public_coin_seed::<Self::Air, Self::Digest>(&air));
+        let TablesProof {
+            tables,
+            terminals,
+            fri_proof,
+            pow_nonce,
+        } = prove_tables(
+            &mut public_coin,
+            &tables,
+            &air.terminal_columns(),
+            |i, challenges, terminals| air.get_hints(i, challenges, terminals),
+            options,
+            observer,
+        )?;
+
+        Ok(MultiProof {
+            hash_fn: Self::Digest::NAME.to_string(),
+            options,
+            public_inputs: air.pub_inputs().clone(),
+            tables,
+            terminals,
+            fri_proof,
+            pow_nonce,
+            _digest: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Hooks for tracking the progress and cost of proof generation.
+//!
+//! The prover reports the start and end of each stage to a [ProverObserver].
+//! Durations are measured with `std::time::Instant` so are only available
+//! when the `std` feature is enabled. The observer for `()` does nothing which
+//! lets the hooks compile away entirely when they're not needed.
+use core::time::Duration;
+
+/// Part of the trace a stage is operating on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceSegment {
+    Base,
+    Extension,
+    Composition,
+}
+
+/// A stage of proof generation and the sizes involved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingStage {
+    /// Interpolating trace columns over the trace domain
+    Interpolation {
+        segment: TraceSegment,
+        num_cols: usize,
+        trace_len: usize,
+    },
+    /// Evaluating trace polynomials over the LDE domain
+    LowDegreeExtension {
+        segment: TraceSegment,
+        num_cols: usize,
+        lde_size: usize,
+    },
+    /// Committing to the rows of a trace LDE
+    Commitment {
+        segment: TraceSegment,
+        num_cols: usize,
+        lde_size: usize,
+    },
+    /// Evaluating the constraints over the constraint evaluation domain
+    ConstraintEvaluation { ce_size: usize },
+    /// Building the DEEP composition polynomial and evaluating it over the LDE
+    /// domain
+    DeepComposition { lde_size: usize },
+    /// Committing to and folding a single FRI layer
+    FriLayer { layer: usize, domain_size: usize },
+    /// Searching for a proof-of-work nonce
+    Grinding { grinding_factor: u8 },
+    /// Opening trace rows and FRI layers at the query positions
+    Queries { num_queries: usize },
+}
+
+/// Receives progress updates from the prover
+pub trait ProverObserver {
+    fn stage_started(&mut self, _stage: &ProvingStage) {}
+
+    /// Called once a stage is complete. The duration is `None` if the `std`
+    /// feature is disabled.
+    fn stage_finished(&mut self, _stage: &ProvingStage, _duration: Option<Duration>) {}
+}
+
+impl ProverObserver for () {}
+
+impl<T: ProverObserver + ?Sized> ProverObserver for &mut T {
+    fn stage_started(&mut self, stage: &ProvingStage) {
+        (**self).stage_started(stage)
+    }
+
+    fn stage_finished(&mut self, stage: &ProvingStage, duration: Option<Duration>) {
+        (**self).stage_finished(stage, duration)
+    }
+}
+
+/// Prints each stage and its duration to stdout
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PrintObserver;
+
+#[cfg(feature = "std")]
+impl ProverObserver for PrintObserver {
+    fn stage_finished(&mut self, stage: &ProvingStage, duration: Option<Duration>) {
+        println!("{stage:?} in {:?}", duration.unwrap_or_default());
+    }
+}
+
+/// Runs `f` as the given stage and reports it to the observer
+#[cfg(feature = "prover")]
+pub(crate) fn observe<T>(
+    observer: &mut impl ProverObserver,
+    stage: ProvingStage,
+    f: impl FnOnce() -> T,
+) -> T {
+    observer.stage_started(&stage);
+    #[cfg(feature = "std")]
+    let now = std::time::Instant::now();
+    let res = f();
+    #[cfg(feature = "std")]
+    let duration = Some(now.elapsed());
+    #[cfg(not(feature = "std"))]
+    let duration = None;
+    observer.stage_finished(&stage, duration);
+    res
+}
@@ -0,0 +1,65 @@
+//! Columns of public values that repeat throughout the execution trace.
+//!
+//! A periodic column with values `v_0, ..., v_{k-1}` has value `v_{i mod k}`
+//! at row `i`. If `p` interpolates the values over the subgroup of size `k`
+//! then the column is represented by the polynomial `p(x^(n/k))` where `n` is
+//! the trace length. Unlike trace columns the values don't need to be
+//! committed to since the verifier can evaluate this polynomial directly.
+use crate::utils::horner_evaluate;
+use alloc::vec::Vec;
+use ark_ff::FftField;
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use core::ops::Add;
+
+/// Public values that repeat every `period` rows of the execution trace e.g.
+/// the round constants of a hash function
+#[derive(Clone, Debug)]
+pub struct PeriodicColumn<F: FftField> {
+    values: Vec<F>,
+    /// Coefficients of the polynomial that interpolates the values over the
+    /// subgroup of size `period`
+    coeffs: Vec<F>,
+}
+
+impl<F: FftField> PeriodicColumn<F> {
+    /// Creates a column that repeats the given values. The number of values
+    /// must be a power of two no larger than the trace length.
+    pub fn new(values: Vec<F>) -> Self {
+        assert!(
+            values.len().is_power_of_two(),
+            "period must be a power of two"
+        );
+        let domain = Radix2EvaluationDomain::new(values.len()).unwrap();
+        let coeffs = domain.ifft(&values);
+        PeriodicColumn { values, coeffs }
+    }
+
+    /// Number of rows after which the values repeat
+    pub fn period(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Value of the column at the given row of the trace
+    pub fn value_at(&self, row: usize) -> F {
+        self.values[row % self.period()]
+    }
+
+    /// Evaluates the column's polynomial at `x` for a trace of `trace_len` rows
+    pub fn evaluate<T: Field + for<'a> Add<&'a F, Output = T>>(&self, x: T, trace_len: usize) -> T {
+        let exponent = (trace_len / self.period()) as u64;
+        horner_evaluate(&self.coeffs, &x.pow([exponent]))
+    }
+
+    /// Evaluates the column's polynomial over a domain that contains the trace
+    /// domain or one of its cosets. Evaluations repeat with the period of the
+    /// output so only one period is returned i.e. the evaluation at the `i`th
+    /// element of the domain is at index `i % output.len()`.
+    pub fn evaluate_over(&self, domain: Radix2EvaluationDomain<F>, trace_len: usize) -> Vec<F> {
+        let exponent = trace_len / self.period();
+        let offset = domain.coset_offset().pow([exponent as u64]);
+        let domain = Radix2EvaluationDomain::new_coset(domain.size() / exponent, offset).unwrap();
+        domain.fft(&self.coeffs)
+    }
+}
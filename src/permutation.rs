@@ -0,0 +1,212 @@
+//! Multiset permutation argument.
+//!
+//! Proves the rows of two tuples of expressions are permutations of each
+//! other. Tuples are compressed to a single value `c = t_0 + α*t_1 + ...`
+//! with a random challenge `α` and each side accumulates the running product
+//! of `γ - c` in an extension column for a random challenge `γ`. The tuples are
+//! permutations of each other (with high probability) if the final products
+//! are equal.
+//!
+//! Sides in the same trace are compared with a terminal constraint. Sides in
+//! different traces e.g. tables of a [MultiAir](crate::multi::MultiAir) can
+//! only be compared through a hint so the verifier must be able to compute
+//! the final product on its own e.g. if one side is a public program.
+#[cfg(feature = "prover")]
+use crate::challenges::Challenges;
+use crate::constraints::AlgebraicExpression;
+use crate::constraints::ExecutionTraceColumn;
+use crate::constraints::FieldConstant;
+use crate::constraints::Hint;
+use crate::constraints::VerifierChallenge;
+use crate::lookup::compress;
+#[cfg(feature = "prover")]
+use crate::lookup::eval_rows;
+#[cfg(feature = "prover")]
+use crate::Matrix;
+use crate::StarkExtensionOf;
+use alloc::vec::Vec;
+use ark_ff::FftField;
+use ark_ff::One;
+use ark_ff::Zero;
+#[cfg(feature = "prover")]
+use gpu_poly::prelude::PageAlignedAllocator;
+#[cfg(feature = "prover")]
+use gpu_poly::GpuVec;
+
+/// Running product of one side of a permutation argument
+#[derive(Clone, Debug)]
+pub struct RunningProduct<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+    tuple: Vec<AlgebraicExpression<Fp, Fq>>,
+    selector: Option<AlgebraicExpression<Fp, Fq>>,
+    column: usize,
+    alpha: usize,
+    gamma: usize,
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> RunningProduct<Fp, Fq> {
+    /// Accumulates the tuple of every row in `column`. Expressions can only
+    /// reference base columns and challenges. The challenge `alpha` is only
+    /// used if the tuple has more than one element.
+    pub fn new(
+        tuple: Vec<AlgebraicExpression<Fp, Fq>>,
+        column: impl ExecutionTraceColumn,
+        alpha: impl VerifierChallenge,
+        gamma: impl VerifierChallenge,
+    ) -> Self {
+        assert!(!tuple.is_empty(), "tuple can't be empty");
+        RunningProduct {
+            tuple,
+            selector: None,
+            column: column.index(),
+            alpha: alpha.index(),
+            gamma: gamma.index(),
+        }
+    }
+
+    /// Only accumulates rows where `selector` is one. The selector must be
+    /// zero on all other rows e.g. padding rows.
+    pub fn with_selector(mut self, selector: AlgebraicExpression<Fp, Fq>) -> Self {
+        self.selector = Some(selector);
+        self
+    }
+
+    /// Index of the running product column
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Factor each row contributes to the product
+    fn factor(&self, offset: isize) -> AlgebraicExpression<Fp, Fq> {
+        let tuple = self
+            .tuple
+            .iter()
+            .map(|element| shift(element, offset))
+            .collect::<Vec<AlgebraicExpression<Fp, Fq>>>();
+        let factor = self.gamma.challenge() - compress(&tuple, self.alpha);
+        match &self.selector {
+            Some(selector) => {
+                let one = FieldConstant::Fp(Fp::one());
+                let selector = shift(selector, offset);
+                &selector * factor - selector + one
+            }
+            None => factor,
+        }
+    }
+
+    /// Boundary and transition constraints for a trace with `trace_len` rows
+    pub fn constraints(&self, trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        let product = self.column;
+        vec![
+            (product.curr() - self.factor(0)).on_first_row(trace_len),
+            (product.next() - product.curr() * self.factor(1)).on_transition(trace_len),
+        ]
+    }
+
+    /// Terminal constraint that the final product equals a hint. The hint is
+    /// the [RunningProduct::product] of the tuples known to the verifier.
+    pub fn terminal(&self, trace_len: usize, hint: impl Hint) -> AlgebraicExpression<Fp, Fq> {
+        (self.column.curr() - hint.hint()).on_last_row(trace_len)
+    }
+
+    /// Computes the final product of some public tuples
+    pub fn product<'a>(&self, tuples: impl IntoIterator<Item = &'a [Fq]>, challenges: &[Fq]) -> Fq {
+        let alpha = challenges.get(self.alpha).copied().unwrap_or_default();
+        let gamma = challenges[self.gamma];
+        tuples
+            .into_iter()
+            .map(|tuple| {
+                assert_eq!(self.tuple.len(), tuple.len());
+                let compressed = tuple
+                    .iter()
+                    .rev()
+                    .fold(Fq::zero(), |acc, v| acc * alpha + v);
+                gamma - compressed
+            })
+            .product()
+    }
+
+    /// Builds the running product column from the base columns of a trace
+    #[cfg(feature = "prover")]
+    pub fn build_column(
+        &self,
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fq>,
+    ) -> GpuVec<Fq> {
+        let factors = eval_rows(&self.factor(0), base_columns, challenges);
+        let mut column = Vec::with_capacity_in(factors.len(), PageAlignedAllocator);
+        let mut acc = Fq::one();
+        for factor in factors {
+            acc *= factor;
+            column.push(acc);
+        }
+        column
+    }
+}
+
+/// Permutation argument between two tuples of the same trace
+#[derive(Clone, Debug)]
+pub struct Permutation<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+    left: RunningProduct<Fp, Fq>,
+    right: RunningProduct<Fp, Fq>,
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Permutation<Fp, Fq> {
+    pub fn new(left: RunningProduct<Fp, Fq>, right: RunningProduct<Fp, Fq>) -> Self {
+        assert_eq!(
+            left.tuple.len(),
+            right.tuple.len(),
+            "tuples must have the same size"
+        );
+        assert_eq!(
+            left.alpha, right.alpha,
+            "tuples must be compressed with the same challenge"
+        );
+        assert_eq!(
+            left.gamma, right.gamma,
+            "products must use the same challenge"
+        );
+        Permutation { left, right }
+    }
+
+    pub fn left(&self) -> &RunningProduct<Fp, Fq> {
+        &self.left
+    }
+
+    pub fn right(&self) -> &RunningProduct<Fp, Fq> {
+        &self.right
+    }
+
+    /// Constraints of both running products and a terminal constraint that
+    /// checks the final products are equal
+    pub fn constraints(&self, trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        let mut constraints = self.left.constraints(trace_len);
+        constraints.extend(self.right.constraints(trace_len));
+        let difference = self.left.column.curr() - self.right.column.curr();
+        constraints.push(difference.on_last_row(trace_len));
+        constraints
+    }
+
+    /// Builds the left and right running product columns
+    #[cfg(feature = "prover")]
+    pub fn build_columns(
+        &self,
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fq>,
+    ) -> (GpuVec<Fq>, GpuVec<Fq>) {
+        (
+            self.left.build_column(base_columns, challenges),
+            self.right.build_column(base_columns, challenges),
+        )
+    }
+}
+
+/// Shifts all trace references in an expression by `offset` rows
+fn shift<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
+    expr: &AlgebraicExpression<Fp, Fq>,
+    offset: isize,
+) -> AlgebraicExpression<Fp, Fq> {
+    expr.map(&mut |node| match node {
+        AlgebraicExpression::Trace(col, row) => AlgebraicExpression::Trace(col, row + offset),
+        node => node,
+    })
+}
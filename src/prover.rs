@@ -1,29 +1,162 @@
-use crate::channel::ProverChannel;
+use crate::air::draw_challenges;
+use crate::air::draw_constraint_composition_coeffs;
+use crate::air::draw_deep_composition_coeffs;
+use crate::air::DeepCompositionCoeffs;
+use crate::challenges::Challenges;
 use crate::composer::ConstraintComposer;
 use crate::composer::DeepPolyComposer;
+use crate::fri::FriProof;
 use crate::fri::FriProver;
+use crate::hash::HashFn;
+use crate::hints::Hints;
+use crate::merkle::MerkleTree;
+use crate::merkle::MerkleTreeError;
+use crate::merkle::Salt;
+use crate::multi::TableProof;
+use crate::observer::observe;
+use crate::observer::ProverObserver;
+use crate::observer::ProvingStage;
+use crate::observer::TraceSegment;
+use crate::random::public_coin_seed;
+use crate::random::PublicCoin;
+use crate::trace::PreprocessedTrace;
 use crate::trace::Queries;
+use crate::trace::TableTrace;
+use crate::utils;
 use crate::Air;
+use crate::GpuAir;
+use crate::GpuStarkExtensionOf;
+use crate::Matrix;
 use crate::Proof;
 use crate::ProofOptions;
-use crate::StarkExtensionOf;
 use crate::Trace;
+use crate::TraceInfo;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use ark_ff::Field;
 use ark_ff::PrimeField;
+use ark_ff::UniformRand;
+use ark_ff::Zero;
+use ark_poly::domain::DomainCoeff;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use ark_std::rand::Rng;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use digest::Digest;
+use gpu_poly::allocator::PageAlignedAllocator;
 use gpu_poly::GpuFftField;
-use sha2::Sha256;
+use gpu_poly::GpuField;
+use snafu::Snafu;
 
 /// Errors that can occur during the proving stage
-#[derive(Debug)]
+#[derive(Debug, Snafu)]
 pub enum ProvingError {
-    Fail,
-    // TODO
+    #[snafu(display("trace has {actual} base columns but {expected} were expected"))]
+    BaseTraceWidth { expected: usize, actual: usize },
+    #[snafu(display("trace has {actual} extension columns but {expected} were expected"))]
+    ExtensionTraceWidth { expected: usize, actual: usize },
+    #[snafu(display("trace has {actual} preprocessed columns but {expected} were expected"))]
+    PreprocessedTraceWidth { expected: usize, actual: usize },
+    #[snafu(display(
+        "preprocessed trace doesn't match the trace length or LDE domain of the proof"
+    ))]
+    PreprocessedTraceDomainMismatch,
+    #[snafu(display("trace has no base columns"))]
+    NoBaseColumns,
+    #[snafu(display("trace has {num_columns} columns but at most {max} are supported"))]
+    TraceTooWide { num_columns: usize, max: usize },
+    #[snafu(display("trace has {num_bytes} bytes of metadata but at most {max} are supported"))]
+    TraceMetaTooLarge { num_bytes: usize, max: usize },
+    #[snafu(display("trace length {trace_len} is not a power of two"))]
+    TraceLengthNotPowerOfTwo { trace_len: usize },
+    #[snafu(display("trace length {trace_len} is smaller than the minimum of {min}"))]
+    TraceTooShort { trace_len: usize, min: usize },
+    #[snafu(display(
+        "constraint evaluation blowup factor {ce_blowup_factor} is larger than the lde blowup \
+         factor {lde_blowup_factor}"
+    ))]
+    ConstraintDegreeTooHigh {
+        ce_blowup_factor: usize,
+        lde_blowup_factor: usize,
+    },
+    #[snafu(display(
+        "constraint {constraint} has degree {degree} which exceeds the composition degree {max}"
+    ))]
+    ConstraintDegree {
+        constraint: usize,
+        degree: usize,
+        max: usize,
+    },
+    #[snafu(display(
+        "{num_trace_randomizers} trace randomizers exceed the trace length {trace_len}"
+    ))]
+    TooManyTraceRandomizers {
+        num_trace_randomizers: usize,
+        trace_len: usize,
+    },
+    #[snafu(display(
+        "constraint {constraint} references column {column} but the trace has {num_columns} \
+         columns"
+    ))]
+    ColumnOutOfRange {
+        constraint: usize,
+        column: usize,
+        num_columns: usize,
+    },
+    #[snafu(display(
+        "constraint {constraint} references periodic column {column} but the AIR has \
+         {num_periodic_columns} periodic columns"
+    ))]
+    PeriodicColumnOutOfRange {
+        constraint: usize,
+        column: usize,
+        num_periodic_columns: usize,
+    },
+    #[snafu(display(
+        "periodic column {column} has period {period} which exceeds the trace length {trace_len}"
+    ))]
+    PeriodTooLong {
+        column: usize,
+        period: usize,
+        trace_len: usize,
+    },
+    #[snafu(display("number of queries {num_queries} is not supported"))]
+    InvalidNumQueries { num_queries: u8 },
+    #[snafu(display("lde blowup factor {lde_blowup_factor} is not supported"))]
+    InvalidBlowupFactor { lde_blowup_factor: u8 },
+    #[snafu(display("grinding factor {grinding_factor} is not supported"))]
+    GrindingFactorTooLarge { grinding_factor: u8 },
+    #[snafu(display("fri folding factor {fri_folding_factor} is not supported"))]
+    UnsupportedFriFoldingFactor { fri_folding_factor: u8 },
+    #[snafu(display(
+        "zero-knowledge needs an lde blowup factor of at least 4 but got {lde_blowup_factor}"
+    ))]
+    ZeroKnowledgeBlowupFactor { lde_blowup_factor: u8 },
+    /// Only checked in debug builds. Evaluating every constraint over the
+    /// trace domain is too slow for release builds.
+    #[snafu(display("constraint {constraint} is not satisfied at row {row}"))]
+    UnsatisfiedConstraint { constraint: usize, row: usize },
+    #[snafu(context(false))]
+    #[snafu(display("failed to open queried rows: {source}"))]
+    QueryOpening { source: MerkleTreeError },
+    #[snafu(display("no proof-of-work nonce has {grinding_factor} leading zeros"))]
+    GrindingExhausted { grinding_factor: u32 },
+    #[snafu(display("invalid DEEP composition polynomial: {reason}"))]
+    InvalidDeepComposition { reason: String },
+    #[snafu(display("{actual} traces were given but the AIR has {expected} tables"))]
+    TableCountMismatch { expected: usize, actual: usize },
+    #[snafu(display("column {column} of table {table} is not an extension column"))]
+    TerminalColumnOutOfRange { table: usize, column: usize },
 }
 
 pub trait Prover {
     type Fp: GpuFftField + PrimeField;
-    type Fq: StarkExtensionOf<Self::Fp>;
+    type Fq: GpuStarkExtensionOf<Self::Fp>;
     type Air: Air<Fp = Self::Fp, Fq = Self::Fq>;
     type Trace: Trace<Fp = Self::Fp, Fq = Self::Fq>;
+    type Digest: HashFn;
 
     fn new(options: ProofOptions) -> Self;
 
@@ -31,88 +164,579 @@ pub trait Prover {
 
     fn options(&self) -> ProofOptions;
 
-    async fn generate_proof(&self, trace: Self::Trace) -> Result<Proof<Self::Air>, ProvingError> {
+    /// Columns shared by all proofs that are committed to ahead of time. Must
+    /// have [Trace::NUM_PREPROCESSED_COLUMNS] columns.
+    fn preprocessed_trace(&self) -> Option<&PreprocessedTrace<Self::Fp, Self::Digest>> {
+        None
+    }
+
+    async fn generate_proof(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<Proof<Self::Air, Self::Digest>, ProvingError> {
+        self.generate_proof_with_observer(trace, &mut ()).await
+    }
+
+    /// Generates a proof and reports the progress of each stage to `observer`
+    async fn generate_proof_with_observer(
+        &self,
+        trace: Self::Trace,
+        observer: &mut impl ProverObserver,
+    ) -> Result<Proof<Self::Air, Self::Digest>, ProvingError> {
         let options = self.options();
+        options.validate()?;
+        validate_trace(&trace)?;
         let trace_info = trace.info();
         let pub_inputs = self.get_pub_inputs(&trace);
         let air = Self::Air::new(trace_info, pub_inputs, options);
-        air.validate();
-        let mut channel = ProverChannel::<Self::Air, Sha256>::new(&air);
-
-        let trace_xs = air.trace_domain();
-        let lde_xs = air.lde_domain();
-        let base_trace = trace.base_columns();
-        let base_trace_polys = base_trace.interpolate(trace_xs);
-        assert_eq!(Self::Trace::NUM_BASE_COLUMNS, base_trace_polys.num_cols());
-        let base_trace_lde = base_trace_polys.evaluate(lde_xs);
-        let base_trace_lde_tree = base_trace_lde.commit_to_rows();
-        channel.commit_base_trace(base_trace_lde_tree.root());
-        let challenges = air.get_challenges(&mut channel.public_coin);
-        let hints = air.get_hints(&challenges);
-
-        let extension_trace = trace.build_extension_columns(&challenges);
-        let num_extension_columns = extension_trace.as_ref().map_or(0, |t| t.num_cols());
-        assert_eq!(Self::Trace::NUM_EXTENSION_COLUMNS, num_extension_columns);
-        let extension_trace_polys = extension_trace.as_ref().map(|t| t.interpolate(trace_xs));
-        let extension_trace_lde = extension_trace_polys.as_ref().map(|p| p.evaluate(lde_xs));
-        let extension_trace_tree = extension_trace_lde.as_ref().map(|lde| lde.commit_to_rows());
-        if let Some(t) = extension_trace_tree.as_ref() {
-            channel.commit_extension_trace(t.root())
+        air.validate()?;
+        let mut public_coin = PublicCoin::new(&public_coin_seed::<Self::Air, Self::Digest>(&air));
+        let table = ProverTable {
+            air: &air,
+            trace: &trace,
+            preprocessed_trace: self.preprocessed_trace(),
+        };
+        let TablesProof {
+            mut tables,
+            fri_proof,
+            pow_nonce,
+            ..
+        } = prove_tables(
+            &mut public_coin,
+            &[table],
+            &[],
+            |_, challenges, _| air.get_hints(challenges),
+            options,
+            observer,
+        )?;
+        let TableProof {
+            trace_info,
+            base_trace_commitment,
+            extension_trace_commitment,
+            composition_trace_commitment,
+            execution_trace_ood_evals,
+            composition_trace_ood_evals,
+            trace_queries,
+        } = tables.pop().expect("a single table was proved");
+        Ok(Proof {
+            hash_fn: Self::Digest::NAME.to_string(),
+            options,
+            trace_info,
+            base_trace_commitment,
+            extension_trace_commitment,
+            composition_trace_commitment,
+            fri_proof,
+            pow_nonce,
+            trace_queries,
+            public_inputs: air.pub_inputs().clone(),
+            execution_trace_ood_evals,
+            composition_trace_ood_evals,
+            _digest: PhantomData,
+        })
+    }
+}
+
+/// A table to prove with [prove_tables]
+pub(crate) struct ProverTable<'a, A: GpuAir + ?Sized, D: Digest> {
+    pub air: &'a A,
+    pub trace: &'a dyn TableTrace<A::Fp, A::Fq>,
+    pub preprocessed_trace: Option<&'a PreprocessedTrace<A::Fp, D>>,
+}
+
+/// Output of [prove_tables]
+pub(crate) struct TablesProof<A: Air + ?Sized> {
+    pub tables: Vec<TableProof<A>>,
+    pub terminals: Vec<A::Fq>,
+    pub fri_proof: FriProof<A::Fq>,
+    pub pow_nonce: u64,
+}
+
+/// Proves one or more tables that share a transcript. Proofs with a single
+/// table are regular [Proof]s.
+///
+/// All tables are committed to over the LDE domain of the longest table and
+/// draw the same challenges. The last rows of the `terminal_columns` (given as
+/// `(table, column)`) are added to the transcript before the hints are built.
+/// The DEEP composition polynomials of shorter tables have their degree shifted
+/// so a single FRI instance checks every table against its own degree bound.
+pub(crate) fn prove_tables<A: GpuAir + ?Sized, D: HashFn>(
+    public_coin: &mut PublicCoin<D>,
+    tables: &[ProverTable<'_, A, D>],
+    terminal_columns: &[(usize, usize)],
+    get_hints: impl Fn(usize, &Challenges<A::Fq>, &[A::Fq]) -> Hints<A::Fq>,
+    options: ProofOptions,
+    observer: &mut impl ProverObserver,
+) -> Result<TablesProof<A>, ProvingError> {
+    let lde_xs = tables
+        .iter()
+        .map(|table| table.air.lde_domain())
+        .max_by_key(|domain| domain.size())
+        .expect("there is at least one table");
+    let lde_size = lde_xs.size();
+    for table in tables {
+        validate_preprocessed_trace(table.air, table.preprocessed_trace, lde_xs)?;
+        if let Some(preprocessed_trace) = table.preprocessed_trace {
+            public_coin.reseed(&preprocessed_trace.commitment().deref());
         }
+    }
+    // randomness for zero-knowledge. Must not be derived from the public coin.
+    let mut rng = rand::thread_rng();
+    let zero_knowledge = options.zero_knowledge;
+
+    let mut base_trace_segments = Vec::new();
+    let mut base_trace_ldes = Vec::new();
+    for table in tables {
+        let (segment, lde) = commit_segment::<A, A::Fp, D>(
+            observer,
+            TraceSegment::Base,
+            table.air,
+            table.trace.base_columns(),
+            lde_xs,
+            zero_knowledge.then_some(&mut rng),
+        );
+        public_coin.reseed(&segment.tree.root().deref());
+        base_trace_segments.push(segment);
+        base_trace_ldes.push(lde);
+    }
+    let num_challenges = tables.iter().map(|table| table.air.num_challenges());
+    let challenges = draw_challenges(public_coin, num_challenges.max().unwrap_or(0));
 
-        #[cfg(all(feature = "std", debug_assertions))]
-        air.validate_constraints(&challenges, &hints, base_trace, extension_trace.as_ref());
-        drop((base_trace, extension_trace));
+    let mut extension_traces = Vec::new();
+    let mut extension_trace_segments = Vec::new();
+    let mut extension_trace_ldes = Vec::new();
+    for table in tables {
+        let extension_trace = table.trace.build_extension_columns(&challenges);
+        let expected = table.air.trace_info().num_extension_columns;
+        let actual = extension_trace.as_ref().map_or(0, |t| t.num_cols());
+        if actual != expected {
+            return Err(ProvingError::ExtensionTraceWidth { expected, actual });
+        }
+        let (segment, lde) = match &extension_trace {
+            Some(extension_trace) => {
+                let (segment, lde) = commit_segment::<A, A::Fq, D>(
+                    observer,
+                    TraceSegment::Extension,
+                    table.air,
+                    extension_trace,
+                    lde_xs,
+                    zero_knowledge.then_some(&mut rng),
+                );
+                public_coin.reseed(&segment.tree.root().deref());
+                (Some(segment), Some(lde))
+            }
+            None => (None, None),
+        };
+        extension_traces.push(extension_trace);
+        extension_trace_segments.push(segment);
+        extension_trace_ldes.push(lde);
+    }
 
-        let composition_coeffs = air.get_constraint_composition_coeffs(&mut channel.public_coin);
-        let constraint_coposer = ConstraintComposer::new(&air, composition_coeffs);
-        // TODO: move commitment here
+    let terminals = terminal_columns
+        .iter()
+        .map(|&(table, column)| terminal(tables, &extension_traces, table, column))
+        .collect::<Result<Vec<A::Fq>, ProvingError>>()?;
+    if !terminals.is_empty() {
+        public_coin.reseed(&terminals);
+    }
+    let hints = (0..tables.len())
+        .map(|i| get_hints(i, &challenges, &terminals))
+        .collect::<Vec<Hints<A::Fq>>>();
+
+    #[cfg(debug_assertions)]
+    for ((table, extension_trace), hints) in tables.iter().zip(&extension_traces).zip(&hints) {
+        table.air.validate_constraints(
+            &challenges,
+            hints,
+            table.trace.base_columns(),
+            extension_trace.as_ref(),
+            table.preprocessed_trace.map(|trace| trace.columns()),
+        )?;
+    }
+    drop(extension_traces);
+
+    let composition_coeffs = tables
+        .iter()
+        .map(|table| {
+            let num_constraints = table.air.constraints().len();
+            draw_constraint_composition_coeffs(public_coin, num_constraints)
+        })
+        .collect::<Vec<_>>();
+    let mut composition_trace_segments = Vec::new();
+    let mut composition_trace_polys = Vec::new();
+    let mut deep_randomizers = Vec::new();
+    let trace_ldes = base_trace_ldes.into_iter().zip(extension_trace_ldes);
+    for (((table, coeffs), hints), (base_trace_lde, extension_trace_lde)) in tables
+        .iter()
+        .zip(composition_coeffs)
+        .zip(&hints)
+        .zip(trace_ldes)
+    {
+        let constraint_composer = ConstraintComposer::new(table.air, coeffs);
         // NOTE: consuming LDEs here requires more compute later but saves on memory
-        let (composition_trace_lde, composition_trace_polys, composition_trace_lde_tree) =
-            constraint_coposer.build_commitment(
-                &challenges,
-                &hints,
-                base_trace_lde,
-                extension_trace_lde,
-            );
-        channel.commit_composition_trace(composition_trace_lde_tree.root());
+        let polys = observe(
+            observer,
+            ProvingStage::ConstraintEvaluation {
+                ce_size: table.air.ce_domain().size(),
+            },
+            || {
+                constraint_composer.build_trace_polys(
+                    &challenges,
+                    hints,
+                    base_trace_lde,
+                    extension_trace_lde,
+                    table.preprocessed_trace.map(PreprocessedTrace::lde),
+                )
+            },
+        )?;
+        let mut lde = observe(
+            observer,
+            ProvingStage::LowDegreeExtension {
+                segment: TraceSegment::Composition,
+                num_cols: polys.num_cols(),
+                lde_size,
+            },
+            || polys.evaluate(lde_xs),
+        );
+        // in zero-knowledge mode a random polynomial is committed to alongside the
+        // composition trace. It's later used to mask the DEEP composition polynomial.
+        let deep_randomizer = zero_knowledge.then(|| {
+            let num_coeffs = table.air.trace_degree() + 1;
+            let mut coeffs = Vec::with_capacity_in(num_coeffs, PageAlignedAllocator);
+            coeffs.resize_with(num_coeffs, || A::Fq::rand(&mut rng));
+            coeffs
+        });
+        if let Some(randomizer) = &deep_randomizer {
+            lde.append(Matrix::new(vec![randomizer.clone()]).evaluate(lde_xs));
+        }
+        let salts = zero_knowledge.then(|| gen_salts(&mut rng, lde_size));
+        let tree = observe(
+            observer,
+            ProvingStage::Commitment {
+                segment: TraceSegment::Composition,
+                num_cols: lde.num_cols(),
+                lde_size,
+            },
+            || commit_to_rows(&lde, salts.as_deref()),
+        );
+        public_coin.reseed(&tree.root().deref());
+        composition_trace_segments.push(CommittedSegment {
+            columns: lde,
+            tree,
+            salts,
+        });
+        composition_trace_polys.push(polys);
+        deep_randomizers.push(deep_randomizer);
+    }
 
+    let z = public_coin.draw::<A::Fq>();
+    let mut deep_poly_composers = Vec::new();
+    let mut ood_evals = Vec::new();
+    let segments = base_trace_segments.iter().zip(&extension_trace_segments);
+    for ((table, (base_trace, extension_trace)), composition_trace_polys) in
+        tables.iter().zip(segments).zip(composition_trace_polys)
+    {
         let mut deep_poly_composer = DeepPolyComposer::new(
-            &air,
-            channel.get_ood_point(),
-            &base_trace_polys,
-            extension_trace_polys.as_ref(),
+            table.air,
+            z,
+            &base_trace.columns,
+            extension_trace.as_ref().map(|segment| &segment.columns),
+            table.preprocessed_trace.map(|trace| trace.polys()),
             composition_trace_polys,
-        );
+        )?;
         let (execution_trace_oods, composition_trace_oods) = deep_poly_composer.get_ood_evals();
-        channel.send_execution_trace_ood_evals(execution_trace_oods);
-        channel.send_composition_trace_ood_evals(composition_trace_oods);
-        let deep_coeffs = air.get_deep_composition_coeffs(&mut channel.public_coin);
-        let deep_composition_poly = deep_poly_composer.into_deep_poly(deep_coeffs);
-        let deep_composition_lde = deep_composition_poly.into_evaluations(lde_xs);
-
-        let mut fri_prover = FriProver::<Self::Fq, Sha256>::new(air.options().into_fri_options());
-        #[cfg(feature = "std")]
-        let now = std::time::Instant::now();
-        fri_prover.build_layers(&mut channel, deep_composition_lde.try_into().unwrap());
-        #[cfg(feature = "std")]
-        println!("yo {:?}", now.elapsed());
-
-        channel.grind_fri_commitments();
-
-        let query_positions = channel.get_fri_query_positions();
+        public_coin.reseed(&execution_trace_oods);
+        public_coin.reseed(&composition_trace_oods);
+        deep_poly_composers.push(deep_poly_composer);
+        ood_evals.push((execution_trace_oods, composition_trace_oods));
+    }
+    let deep_coeffs = tables
+        .iter()
+        .map(|table| {
+            let num_trace_arguments = table.air.trace_arguments().len();
+            let num_composition_trace_cols = table.air.ce_blowup_factor();
+            draw_deep_composition_coeffs(
+                public_coin,
+                num_trace_arguments,
+                num_composition_trace_cols,
+            )
+        })
+        .collect::<Vec<_>>();
+    let max_trace_degree = tables.iter().map(|table| table.air.trace_degree()).max();
+    let max_trace_degree = max_trace_degree.unwrap_or(0);
+    let deep_composition_lde =
+        observe(observer, ProvingStage::DeepComposition { lde_size }, || {
+            let num_coeffs = utils::ceil_power_of_two(max_trace_degree + 1);
+            let mut deep_composition_coeffs =
+                Vec::with_capacity_in(num_coeffs, PageAlignedAllocator);
+            deep_composition_coeffs.resize(num_coeffs, A::Fq::zero());
+            let composers = deep_poly_composers.into_iter().zip(deep_coeffs);
+            for ((table, (deep_poly_composer, coeffs)), deep_randomizer) in
+                tables.iter().zip(composers).zip(&deep_randomizers)
+            {
+                let DeepCompositionCoeffs {
+                    execution_trace,
+                    composition_trace,
+                    degree: (degree_alpha, degree_beta),
+                } = coeffs;
+                let quotients =
+                    deep_poly_composer.into_quotients_sum(execution_trace, composition_trace);
+                // P(x) * (alpha + x^shift * beta). The highest coefficient of the quotients
+                // is zero so nothing is lost past the largest degree.
+                let degree_shift = max_trace_degree - table.air.trace_degree() + 1;
+                for (i, quotient) in quotients.iter().enumerate() {
+                    deep_composition_coeffs[i] += *quotient * degree_alpha;
+                    if let Some(coeff) = deep_composition_coeffs.get_mut(i + degree_shift) {
+                        *coeff += *quotient * degree_beta;
+                    }
+                }
+                if let Some(randomizer) = deep_randomizer {
+                    for (coeff, r) in deep_composition_coeffs.iter_mut().zip(randomizer) {
+                        *coeff += r;
+                    }
+                }
+            }
+            Matrix::new(vec![deep_composition_coeffs]).into_evaluations(lde_xs)
+        });
+
+    let mut fri_prover = FriProver::<A::Fq, D>::new(options.into_fri_options());
+    let deep_composition_lde = deep_composition_lde
+        .try_into()
+        .map_err(|reason| ProvingError::InvalidDeepComposition { reason })?;
+    fri_prover.build_layers(public_coin, deep_composition_lde, observer);
+
+    let grinding_factor = options.grinding_factor;
+    let pow_nonce = observe(observer, ProvingStage::Grinding { grinding_factor }, || {
+        let grinding_factor = grinding_factor as u32;
+        if grinding_factor == 0 {
+            // skip if there is no grinding required
+            return Ok(0);
+        }
+
+        let pow_nonce = public_coin
+            .grind(grinding_factor)
+            .ok_or(ProvingError::GrindingExhausted { grinding_factor })?;
+        public_coin.reseed(&pow_nonce);
+        Ok::<_, ProvingError>(pow_nonce)
+    })?;
+
+    let query_positions = public_coin.draw_queries(options.num_queries as usize, lde_size);
+    let num_queries = query_positions.len();
+    observe(observer, ProvingStage::Queries { num_queries }, || {
         let fri_proof = fri_prover.into_proof(&query_positions);
+        let segments = base_trace_segments
+            .iter()
+            .zip(&extension_trace_segments)
+            .zip(&composition_trace_segments);
+        let mut table_proofs = Vec::new();
+        for ((table, ((base_trace, extension_trace), composition_trace)), ood_evals) in
+            tables.iter().zip(segments).zip(ood_evals)
+        {
+            let trace_queries = Queries::new(
+                lde_xs,
+                &base_trace.columns,
+                extension_trace.as_ref().map(|segment| &segment.columns),
+                &composition_trace.columns,
+                table.preprocessed_trace,
+                &base_trace.tree,
+                extension_trace.as_ref().map(|segment| &segment.tree),
+                &composition_trace.tree,
+                base_trace.salts.as_deref(),
+                extension_trace
+                    .as_ref()
+                    .and_then(|segment| segment.salts.as_deref()),
+                composition_trace.salts.as_deref(),
+                &query_positions,
+            )?;
+            let (execution_trace_ood_evals, composition_trace_ood_evals) = ood_evals;
+            table_proofs.push(TableProof {
+                trace_info: table.air.trace_info().clone(),
+                base_trace_commitment: base_trace.tree.root().to_vec(),
+                extension_trace_commitment: extension_trace
+                    .as_ref()
+                    .map(|segment| segment.tree.root().to_vec()),
+                composition_trace_commitment: composition_trace.tree.root().to_vec(),
+                execution_trace_ood_evals,
+                composition_trace_ood_evals,
+                trace_queries,
+            });
+        }
+        Ok::<_, ProvingError>(TablesProof {
+            tables: table_proofs,
+            terminals,
+            fri_proof,
+            pow_nonce,
+        })
+    })
+}
 
-        let queries = Queries::new(
-            &air,
-            &base_trace_polys,
-            extension_trace_polys.as_ref(),
-            &composition_trace_lde,
-            base_trace_lde_tree,
-            extension_trace_tree,
-            composition_trace_lde_tree,
-            &query_positions,
-        );
-        Ok(channel.build_proof(queries, fri_proof))
+/// A trace segment that has been committed to
+struct CommittedSegment<F: Field, D: Digest> {
+    /// Trace polynomials of the execution trace or the LDE of the composition
+    /// trace. Queried rows are read from these.
+    columns: Matrix<F>,
+    tree: MerkleTree<D>,
+    salts: Option<Vec<Salt>>,
+}
+
+/// Interpolates, extends and commits to the columns of a trace segment. The
+/// trace polynomials are randomized and the rows salted if `rng` is given.
+/// Also returns the LDE.
+fn commit_segment<A: GpuAir + ?Sized, F, D: Digest>(
+    observer: &mut impl ProverObserver,
+    segment: TraceSegment,
+    air: &A,
+    columns: &Matrix<F>,
+    lde_xs: Radix2EvaluationDomain<A::Fp>,
+    mut rng: Option<&mut impl Rng>,
+) -> (CommittedSegment<F, D>, Matrix<F>)
+where
+    F: Field + GpuField<FftField = A::Fp> + DomainCoeff<A::Fp>,
+{
+    let num_cols = columns.num_cols();
+    let lde_size = lde_xs.size();
+    let num_trace_randomizers = air.num_trace_randomizers();
+    let polys = observe(
+        observer,
+        ProvingStage::Interpolation {
+            segment,
+            num_cols,
+            trace_len: columns.num_rows(),
+        },
+        || {
+            let mut polys = columns.interpolate(air.trace_domain());
+            if let Some(rng) = rng.as_deref_mut() {
+                for column in polys.iter_mut() {
+                    utils::randomize_poly(column, num_trace_randomizers, rng);
+                }
+            }
+            polys
+        },
+    );
+    let lde = observe(
+        observer,
+        ProvingStage::LowDegreeExtension {
+            segment,
+            num_cols,
+            lde_size,
+        },
+        || polys.evaluate(lde_xs),
+    );
+    let salts = rng.map(|rng| gen_salts(rng, lde_size));
+    let tree = observe(
+        observer,
+        ProvingStage::Commitment {
+            segment,
+            num_cols,
+            lde_size,
+        },
+        || commit_to_rows(&lde, salts.as_deref()),
+    );
+    let segment = CommittedSegment {
+        columns: polys,
+        tree,
+        salts,
+    };
+    (segment, lde)
+}
+
+/// Last value of an extension column of a table
+fn terminal<A: GpuAir + ?Sized, D: Digest>(
+    tables: &[ProverTable<'_, A, D>],
+    extension_traces: &[Option<Matrix<A::Fq>>],
+    table: usize,
+    column: usize,
+) -> Result<A::Fq, ProvingError> {
+    let value = tables.get(table).and_then(|prover_table| {
+        let trace_info = prover_table.air.trace_info();
+        if !trace_info.extension_columns_range().contains(&column) {
+            return None;
+        }
+        let extension_trace = extension_traces[table].as_ref()?;
+        extension_trace[column - trace_info.num_base_columns]
+            .last()
+            .copied()
+    });
+    value.ok_or(ProvingError::TerminalColumnOutOfRange { table, column })
+}
+
+/// Checks the dimensions of a trace before any proving work is done
+pub(crate) fn validate_trace<T: Trace>(trace: &T) -> Result<(), ProvingError> {
+    if T::NUM_BASE_COLUMNS == 0 {
+        return Err(ProvingError::NoBaseColumns);
+    }
+
+    let num_columns = T::NUM_BASE_COLUMNS + T::NUM_EXTENSION_COLUMNS + T::NUM_PREPROCESSED_COLUMNS;
+    if num_columns > TraceInfo::MAX_TRACE_WIDTH {
+        return Err(ProvingError::TraceTooWide {
+            num_columns,
+            max: TraceInfo::MAX_TRACE_WIDTH,
+        });
+    }
+
+    let num_meta_bytes = trace.meta().map_or(0, <[u8]>::len);
+    if num_meta_bytes > TraceInfo::MAX_META_BYTES {
+        return Err(ProvingError::TraceMetaTooLarge {
+            num_bytes: num_meta_bytes,
+            max: TraceInfo::MAX_META_BYTES,
+        });
+    }
+
+    let num_base_columns = trace.base_columns().num_cols();
+    if num_base_columns != T::NUM_BASE_COLUMNS {
+        return Err(ProvingError::BaseTraceWidth {
+            expected: T::NUM_BASE_COLUMNS,
+            actual: num_base_columns,
+        });
+    }
+
+    let trace_len = trace.len();
+    if !trace_len.is_power_of_two() {
+        return Err(ProvingError::TraceLengthNotPowerOfTwo { trace_len });
+    }
+
+    if trace_len < TraceInfo::MIN_TRACE_LENGTH {
+        return Err(ProvingError::TraceTooShort {
+            trace_len,
+            min: TraceInfo::MIN_TRACE_LENGTH,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks the preprocessed trace has the expected width and was extended over
+/// the same domain as the rest of the trace
+fn validate_preprocessed_trace<A: GpuAir + ?Sized, D: Digest>(
+    air: &A,
+    preprocessed_trace: Option<&PreprocessedTrace<A::Fp, D>>,
+    lde_domain: Radix2EvaluationDomain<A::Fp>,
+) -> Result<(), ProvingError> {
+    let expected = air.trace_info().num_preprocessed_columns;
+    let actual = preprocessed_trace.map_or(0, |trace| trace.num_cols());
+    if actual != expected {
+        return Err(ProvingError::PreprocessedTraceWidth { expected, actual });
+    }
+
+    if let Some(preprocessed_trace) = preprocessed_trace {
+        let preprocessed_lde_domain = preprocessed_trace.lde_domain();
+        if preprocessed_trace.columns().num_rows() != air.trace_len()
+            || lde_domain.size() != preprocessed_lde_domain.size()
+            || lde_domain.coset_offset() != preprocessed_lde_domain.coset_offset()
+        {
+            return Err(ProvingError::PreprocessedTraceDomainMismatch);
+        }
+    }
+
+    Ok(())
+}
+
+fn commit_to_rows<F: Field, D: Digest>(lde: &Matrix<F>, salts: Option<&[Salt]>) -> MerkleTree<D> {
+    match salts {
+        Some(salts) => lde.commit_to_salted_rows(salts),
+        None => lde.commit_to_rows(),
     }
 }
+
+fn gen_salts(rng: &mut impl Rng, n: usize) -> Vec<Salt> {
+    (0..n).map(|_| rng.gen()).collect()
+}
@@ -1,18 +1,39 @@
+use crate::hash::HashFn;
+use crate::Air;
+use alloc::collections::BTreeSet;
 use alloc::vec::Vec;
 use ark_ff::Field;
 use ark_serialize::CanonicalSerialize;
-use digest::Digest;
 use digest::Output;
-use rand_chacha::rand_core::SeedableRng;
-use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Returns the bytes used to seed the public coin of a proof
+pub fn public_coin_seed<A: Air, D: HashFn>(air: &A) -> Vec<u8> {
+    let mut seed = Vec::new();
+    // Seed the public coin with:
+    // 1. serialized public imputs
+    air.pub_inputs().serialize_compressed(&mut seed).unwrap();
+    // 2. various metadata about the air and proof
+    // TODO: field bytes?
+    air.trace_info().serialize_compressed(&mut seed).unwrap();
+    air.options().serialize_compressed(&mut seed).unwrap();
+    // 3. the hash function used by the proof
+    seed.extend_from_slice(D::NAME.as_bytes());
+    seed
+}
 
 // TODO: refactor public coin/channel stuff
-pub struct PublicCoin<D: Digest> {
+/// Fiat-Shamir transcript of a proof. Items are absorbed by hashing them with
+/// the current seed. Field elements are drawn directly from digests with
+/// [HashFn::digest_to_field] so a field-native hash function keeps the whole
+/// transcript in the field.
+pub struct PublicCoin<D: HashFn> {
     pub seed: Output<D>,
     counter: usize,
 }
 
-impl<D: Digest> PublicCoin<D> {
+impl<D: HashFn> PublicCoin<D> {
     pub fn new(seed: &[u8]) -> Self {
         PublicCoin {
             seed: D::new_with_prefix(seed).finalize(),
@@ -43,24 +64,62 @@ impl<D: Digest> PublicCoin<D> {
         leading_zeros(&hasher.finalize())
     }
 
+    /// Searches for a proof-of-work nonce with at least `grinding_factor`
+    /// leading zeros
+    pub fn grind(&self, grinding_factor: u32) -> Option<u64> {
+        #[cfg(not(feature = "parallel"))]
+        return (1..u64::MAX).find(|&nonce| self.check_leading_zeros(nonce) >= grinding_factor);
+
+        #[cfg(feature = "parallel")]
+        return (1..u64::MAX)
+            .into_par_iter()
+            .find_any(|&nonce| self.check_leading_zeros(nonce) >= grinding_factor);
+    }
+
+    /// Draws a field element from the digest `hash(seed || counter)` of the
+    /// next counter value. Digests rejected by [HashFn::digest_to_field] are
+    /// skipped.
     pub fn draw<F: Field>(&mut self) -> F {
-        F::rand(&mut self.draw_rng())
+        loop {
+            if let Some(element) = D::digest_to_field(&self.next()) {
+                return element;
+            }
+        }
     }
 
-    // TODO: make this generic
-    pub fn draw_rng(&mut self) -> ChaCha20Rng {
-        let mut seed: [u8; 32] = Default::default();
-        seed.copy_from_slice(&self.next()[0..32]);
-        ChaCha20Rng::from_seed(seed)
+    /// Draws `num_queries` unique positions in `0..domain_size`. Positions are
+    /// sampled as follows:
+    /// 1. compute `hash(seed || counter)` for the next counter value
+    /// 2. split the hash into 8 byte chunks and read each as a little-endian u64
+    /// 3. mask each u64 to the bit length of `domain_size` and reject values
+    ///    that are out of range or have already been drawn
+    ///
+    /// Steps repeat until enough positions have been drawn. Positions are
+    /// returned in ascending order.
+    pub fn draw_queries(&mut self, num_queries: usize, domain_size: usize) -> Vec<usize> {
+        let num_queries = core::cmp::min(num_queries, domain_size);
+        let mask = domain_size.next_power_of_two() as u64 - 1;
+        let mut positions = BTreeSet::new();
+        while positions.len() < num_queries {
+            let hash = self.next();
+            for chunk in hash.chunks_exact(8) {
+                let value = u64::from_le_bytes(chunk.try_into().unwrap()) & mask;
+                if value < domain_size as u64 && positions.len() < num_queries {
+                    positions.insert(value as usize);
+                }
+            }
+        }
+        positions.into_iter().collect()
     }
 
     /// Updates the state by incrementing the counter and returns hash(seed ||
-    /// counter)
+    /// counter). The counter is a little-endian u64 so field-native hash
+    /// functions absorb it as a single field element.
     fn next(&mut self) -> Output<D> {
         self.counter += 1;
         let mut hasher = D::new();
         hasher.update(&self.seed);
-        hasher.update(self.counter.to_be_bytes());
+        hasher.update((self.counter as u64).to_le_bytes());
         hasher.finalize()
     }
 }
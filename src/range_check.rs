@@ -0,0 +1,158 @@
+//! Range check of a base column.
+//!
+//! Proves every value of a column is in `[0, 2^bits)` by looking the values up
+//! in a table column with a [LogUp] lookup. The table column starts at zero,
+//! increases by zero or one each row and ends at `2^bits - 1` so it contains
+//! exactly the allowed values. The table must fit in the trace i.e.
+//! `2^bits <= trace_len`.
+//!
+//! A range check is attached to a trace with:
+//! - [RangeCheck::NUM_EXTENSION_COLUMNS] added to the trace's extension columns
+//! - [RangeCheck::build_columns] appended to the trace's extension columns
+//! - [RangeCheck::constraints] added to the AIR's constraints
+//!
+//! Its three columns (the table, the multiplicity of each table value and the
+//! running sum of the lookup) are extension columns so attaching a range check
+//! doesn't move the base or extension columns of the trace. The lookup is fully
+//! contained in the trace so no hints are needed.
+#[cfg(feature = "prover")]
+use crate::challenges::Challenges;
+use crate::constraints::AlgebraicExpression;
+use crate::constraints::ExecutionTraceColumn;
+use crate::constraints::FieldConstant;
+use crate::constraints::VerifierChallenge;
+use crate::lookup::LogUp;
+#[cfg(feature = "prover")]
+use crate::Matrix;
+use crate::StarkExtensionOf;
+#[cfg(feature = "prover")]
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+#[cfg(feature = "prover")]
+use ark_ff::batch_inversion;
+use ark_ff::FftField;
+use ark_ff::One;
+use ark_ff::Zero;
+#[cfg(feature = "prover")]
+use gpu_poly::prelude::PageAlignedAllocator;
+
+/// Checks the values of a base column are in `[0, 2^bits)`
+#[derive(Clone, Debug)]
+pub struct RangeCheck<Fp: FftField, Fq: StarkExtensionOf<Fp> = Fp> {
+    column: usize,
+    bits: u32,
+    table: usize,
+    z: usize,
+    lookup: LogUp<Fp, Fq>,
+}
+
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> RangeCheck<Fp, Fq> {
+    /// Number of extension columns added to the trace
+    pub const NUM_EXTENSION_COLUMNS: usize = 3;
+
+    /// Range checks the base column `column`. The range check's columns are
+    /// the extension columns starting at `first_column` and `z` is a challenge
+    /// used by the lookup.
+    pub fn new(
+        column: impl ExecutionTraceColumn,
+        bits: u32,
+        first_column: impl ExecutionTraceColumn,
+        z: impl VerifierChallenge,
+    ) -> Self {
+        let column = column.index();
+        let table = first_column.index();
+        let multiplicities = table + 1;
+        let running_sum = table + 2;
+        // tuples have one element so the compression challenge is never used
+        // and the lookup's hint is never referenced
+        let z = z.index();
+        let lookup = LogUp::new(
+            vec![column.curr()],
+            vec![table.curr()],
+            multiplicities.curr(),
+            running_sum,
+            z,
+            z,
+            0,
+        );
+        RangeCheck {
+            column,
+            bits,
+            table,
+            z,
+            lookup,
+        }
+    }
+
+    /// Index of the column that's range checked
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Constraints for a trace with `trace_len` rows
+    pub fn constraints(&self, trace_len: usize) -> Vec<AlgebraicExpression<Fp, Fq>> {
+        let table_len = 1usize.checked_shl(self.bits).unwrap();
+        assert!(table_len <= trace_len, "table doesn't fit in the trace");
+        let one = FieldConstant::Fp(Fp::one());
+        let max = FieldConstant::Fp(Fp::from((1u64 << self.bits) - 1));
+        let table = self.table;
+        let step = table.next() - table.curr();
+        let mut constraints = vec![
+            table.curr().on_first_row(trace_len),
+            (&step * (&step - one)).on_transition(trace_len),
+            (table.curr() - max).on_last_row(trace_len),
+        ];
+        let sum = AlgebraicExpression::Constant(FieldConstant::Fp(Fp::zero()));
+        constraints.extend(self.lookup.constraints_with_sum(trace_len, sum));
+        constraints
+    }
+
+    /// Builds the table, multiplicity and running sum columns
+    #[cfg(feature = "prover")]
+    pub fn build_columns(
+        &self,
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fq>,
+    ) -> Matrix<Fq> {
+        let trace_len = base_columns.num_rows();
+        let max = (1 << self.bits) - 1;
+        let table = (0..trace_len)
+            .map(|row| Fp::from(core::cmp::min(row, max) as u64))
+            .collect::<Vec<Fp>>();
+
+        // values outside the range aren't counted which makes the lookup fail
+        let rows = BTreeMap::from_iter((0..=max).map(|row| (table[row], row)));
+        let mut multiplicities = vec![Fp::zero(); trace_len];
+        for value in &base_columns[self.column] {
+            if let Some(&row) = rows.get(value) {
+                multiplicities[row] += Fp::one();
+            }
+        }
+
+        let z = challenges[self.z];
+        let mut input_evals = base_columns[self.column]
+            .iter()
+            .map(|&value| z - value)
+            .collect::<Vec<Fq>>();
+        let mut table_evals = table.iter().map(|&value| z - value).collect::<Vec<Fq>>();
+        batch_inversion(&mut input_evals);
+        batch_inversion(&mut table_evals);
+
+        let mut running_sum = Vec::with_capacity_in(trace_len, PageAlignedAllocator);
+        let mut acc = Fq::zero();
+        for ((input_inv, table_inv), &multiplicity) in input_evals
+            .into_iter()
+            .zip(table_evals)
+            .zip(&multiplicities)
+        {
+            running_sum.push(acc);
+            acc += input_inv - table_inv * multiplicity;
+        }
+
+        let mut table_column = Vec::with_capacity_in(trace_len, PageAlignedAllocator);
+        table_column.extend(table.into_iter().map(Fq::from));
+        let mut multiplicity_column = Vec::with_capacity_in(trace_len, PageAlignedAllocator);
+        multiplicity_column.extend(multiplicities.into_iter().map(Fq::from));
+        Matrix::new(vec![table_column, multiplicity_column, running_sum])
+    }
+}
@@ -0,0 +1,146 @@
+use crate::format::ProofHeader;
+use crate::format::FRAMING_SIZE;
+use crate::hash::HashFn;
+use crate::Air;
+use crate::Proof;
+use alloc::vec::Vec;
+use ark_serialize::CanonicalSerialize;
+use core::fmt::Display;
+
+/// Number of bytes used by each part of a proof serialized with
+/// [Proof::to_bytes]. Each item includes the length prefixes of its own vectors
+/// so the parts add up to the size of the serialized proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofSizeReport {
+    /// Magic, version and section length prefixes of the proof format
+    pub framing: usize,
+    /// Proof header, proof options and trace info
+    pub metadata: usize,
+    pub public_inputs: usize,
+    /// Base, extension and composition trace commitments
+    pub commitments: usize,
+    /// Out-of-domain evaluations of the execution and composition traces
+    pub ood_evaluations: usize,
+    /// Values of the queried base, extension and composition trace rows
+    pub trace_query_values: usize,
+    /// Merkle paths of the queried trace rows
+    pub trace_merkle_paths: usize,
+    /// Salts of the queried trace rows. Zero unless zero-knowledge is enabled.
+    pub trace_salts: usize,
+    pub fri_layers: Vec<FriLayerSize>,
+    /// FRI remainder, its commitment and the number of FRI layers
+    pub fri_remainder: usize,
+    pub pow_nonce: usize,
+    pub conjectured_security_level: usize,
+}
+
+/// Number of bytes used by a single FRI layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriLayerSize {
+    pub commitment: usize,
+    pub values: usize,
+    pub merkle_paths: usize,
+}
+
+impl FriLayerSize {
+    pub fn total(&self) -> usize {
+        self.commitment + self.values + self.merkle_paths
+    }
+}
+
+impl ProofSizeReport {
+    /// Returns the size of the serialized proof in bytes
+    pub fn total(&self) -> usize {
+        self.framing
+            + self.metadata
+            + self.public_inputs
+            + self.commitments
+            + self.ood_evaluations
+            + self.trace_query_values
+            + self.trace_merkle_paths
+            + self.trace_salts
+            + self
+                .fri_layers
+                .iter()
+                .map(FriLayerSize::total)
+                .sum::<usize>()
+            + self.fri_remainder
+            + self.pow_nonce
+    }
+}
+
+impl Display for ProofSizeReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "framing: {}B", self.framing)?;
+        writeln!(f, "metadata: {}B", self.metadata)?;
+        writeln!(f, "public inputs: {}B", self.public_inputs)?;
+        writeln!(f, "commitments: {}B", self.commitments)?;
+        writeln!(f, "ood evaluations: {}B", self.ood_evaluations)?;
+        writeln!(f, "trace query values: {}B", self.trace_query_values)?;
+        writeln!(f, "trace merkle paths: {}B", self.trace_merkle_paths)?;
+        writeln!(f, "trace salts: {}B", self.trace_salts)?;
+        for (i, layer) in self.fri_layers.iter().enumerate() {
+            writeln!(
+                f,
+                "fri layer {i}: {}B (values: {}B, merkle paths: {}B)",
+                layer.total(),
+                layer.values,
+                layer.merkle_paths
+            )?;
+        }
+        writeln!(f, "fri remainder: {}B", self.fri_remainder)?;
+        writeln!(f, "pow nonce: {}B", self.pow_nonce)?;
+        writeln!(f, "total: {}B", self.total())?;
+        write!(
+            f,
+            "conjectured security: {}bit",
+            self.conjectured_security_level
+        )
+    }
+}
+
+impl<A: Air, D: HashFn> Proof<A, D> {
+    /// Itemizes the bytes in the proof. Sizes are calculated without
+    /// serializing the proof.
+    pub fn size_report(&self) -> ProofSizeReport {
+        let queries = &self.trace_queries;
+        let fri_layers = self
+            .fri_proof
+            .layers()
+            .iter()
+            .map(|layer| FriLayerSize {
+                commitment: layer.commitment().compressed_size(),
+                values: layer.values().compressed_size(),
+                merkle_paths: layer.proof().compressed_size(),
+            })
+            .collect::<Vec<FriLayerSize>>();
+        let fri_layers_size = fri_layers.iter().map(FriLayerSize::total).sum::<usize>();
+        ProofSizeReport {
+            framing: FRAMING_SIZE,
+            metadata: ProofHeader::new::<A, D>().compressed_size()
+                + self.options.compressed_size()
+                + self.trace_info.compressed_size(),
+            public_inputs: self.public_inputs.compressed_size(),
+            commitments: self.base_trace_commitment.compressed_size()
+                + self.extension_trace_commitment.compressed_size()
+                + self.composition_trace_commitment.compressed_size(),
+            ood_evaluations: self.execution_trace_ood_evals.compressed_size()
+                + self.composition_trace_ood_evals.compressed_size(),
+            trace_query_values: queries.base_trace_values.compressed_size()
+                + queries.extension_trace_values.compressed_size()
+                + queries.composition_trace_values.compressed_size()
+                + queries.preprocessed_trace_values.compressed_size(),
+            trace_merkle_paths: queries.base_trace_proof.compressed_size()
+                + queries.extension_trace_proof.compressed_size()
+                + queries.composition_trace_proof.compressed_size()
+                + queries.preprocessed_trace_proof.compressed_size(),
+            trace_salts: queries.base_trace_salts.compressed_size()
+                + queries.extension_trace_salts.compressed_size()
+                + queries.composition_trace_salts.compressed_size(),
+            fri_remainder: self.fri_proof.compressed_size() - fri_layers_size,
+            fri_layers,
+            pow_nonce: self.pow_nonce.compressed_size(),
+            conjectured_security_level: self.conjectured_security_level(),
+        }
+    }
+}
@@ -0,0 +1,606 @@
+//! Rescue-Prime hash function over the Goldilocks field
+//! <https://eprint.iacr.org/2020/1143.pdf>
+//!
+//! Parameters are `m = 12`, `capacity = 4`, `alpha = 7` and `N = 7` rounds.
+//! Round constants and the MDS matrix were generated with algorithms 4 and 5
+//! of the specification using a 128 bit security level:
+//! - round constants are read from SHAKE256 seeded with
+//!   `Rescue-XLIX(p,m,capacity,security_level)`, nine little-endian bytes per
+//!   constant reduced modulo `p`
+//! - the MDS matrix is the transposed right half of the reduced echelon form
+//!   of the `m x 2m` matrix with entries `g^(i*j)` where `g = 7` generates the
+//!   multiplicative group
+//!
+//! Both are regenerated from the specification by the tests.
+use crate::hash::HashFn;
+use alloc::vec::Vec;
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_ff::Zero;
+use ark_ff_optimized::fp64::Fp;
+use ark_serialize::CanonicalDeserialize;
+use digest::consts::U32;
+use digest::FixedOutput;
+use digest::HashMarker;
+use digest::Output;
+use digest::OutputSizeUser;
+use digest::Reset;
+use digest::Update;
+
+/// Number of field elements in the permutation state
+pub const STATE_WIDTH: usize = 12;
+
+/// Number of field elements in the capacity portion of the state
+pub const CAPACITY: usize = 4;
+
+/// Number of field elements absorbed by each application of the permutation
+pub const RATE: usize = STATE_WIDTH - CAPACITY;
+
+/// Number of field elements in a digest
+pub const DIGEST_SIZE: usize = 4;
+
+/// Number of rounds of the permutation
+pub const NUM_ROUNDS: usize = 7;
+
+const MODULUS: u64 = 0xFFFFFFFF00000001;
+pub(crate) const ALPHA: u64 = 7;
+const ALPHA_INV: u64 = 10540996611094048183;
+
+// Capacity elements used for domain separation. The first holds the length of
+// the input and the second holds the type of the input (field elements/bytes).
+pub(crate) const LENGTH_INDEX: usize = 0;
+pub(crate) const DOMAIN_INDEX: usize = 1;
+pub(crate) const ELEMENT_DOMAIN: u64 = 0;
+const BYTE_DOMAIN: u64 = 1;
+
+// Number of bytes packed into a field element when hashing arbitrary bytes
+const BYTES_PER_ELEMENT: usize = 7;
+
+/// Applies the Rescue-Prime permutation to the state
+pub fn permute(state: &mut [Fp; STATE_WIDTH]) {
+    for round in 0..NUM_ROUNDS {
+        apply_round(state, round);
+    }
+}
+
+/// Applies a single round of the permutation to the state
+pub fn apply_round(state: &mut [Fp; STATE_WIDTH], round: usize) {
+    state.iter_mut().for_each(|v| *v = v.pow([ALPHA]));
+    apply_mds(state);
+    add_constants(state, &ROUND_CONSTANTS[2 * round]);
+    state.iter_mut().for_each(|v| *v = v.pow([ALPHA_INV]));
+    apply_mds(state);
+    add_constants(state, &ROUND_CONSTANTS[2 * round + 1]);
+}
+
+/// Hashes a sequence of field elements. Inputs of different lengths are
+/// domain separated so no padding ambiguity exists.
+pub fn hash_elements(elements: &[Fp]) -> [Fp; DIGEST_SIZE] {
+    sponge(elements, elements.len(), ELEMENT_DOMAIN)
+}
+
+/// 2-to-1 hash used for merging Merkle tree nodes. The result is the same as
+/// hashing the concatenation of `a` and `b` with [hash_elements].
+pub fn merge(a: &[Fp; DIGEST_SIZE], b: &[Fp; DIGEST_SIZE]) -> [Fp; DIGEST_SIZE] {
+    let mut elements = [Fp::zero(); 2 * DIGEST_SIZE];
+    elements[..DIGEST_SIZE].copy_from_slice(a);
+    elements[DIGEST_SIZE..].copy_from_slice(b);
+    hash_elements(&elements)
+}
+
+/// Converts a byte digest into field elements. Returns `None` if the bytes
+/// don't represent canonical field elements.
+pub fn digest_to_elements(digest: &[u8]) -> Option<[Fp; DIGEST_SIZE]> {
+    let elements = bytes_to_elements(digest)?;
+    elements.try_into().ok()
+}
+
+/// Converts field elements into a byte digest
+pub fn elements_to_digest(elements: &[Fp; DIGEST_SIZE]) -> Output<RescuePrime> {
+    let mut digest = Output::<RescuePrime>::default();
+    for (chunk, element) in digest.chunks_mut(8).zip(elements) {
+        chunk.copy_from_slice(&element.into_bigint().0[0].to_le_bytes());
+    }
+    digest
+}
+
+/// Byte oriented interface to Rescue-Prime so it can be used anywhere a
+/// [digest::Digest] is expected i.e. the Merkle tree and public coin.
+///
+/// Inputs made up of canonical little-endian field elements (the digests of
+/// this hash function and serialized `Fp` elements) are hashed natively with
+/// [hash_elements]. Any other input is packed seven bytes per field element.
+/// The public coin only absorbs digests, field elements and counters which
+/// are all canonical and draws field elements straight from digests so its
+/// transcript never leaves the field.
+#[derive(Clone, Default, Debug)]
+pub struct RescuePrime {
+    buffer: Vec<u8>,
+}
+
+impl HashMarker for RescuePrime {}
+
+impl Update for RescuePrime {
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+}
+
+impl OutputSizeUser for RescuePrime {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for RescuePrime {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        let digest = match bytes_to_elements(&self.buffer) {
+            Some(elements) => hash_elements(&elements),
+            None => hash_bytes(&self.buffer),
+        };
+        *out = elements_to_digest(&digest);
+    }
+}
+
+impl Reset for RescuePrime {
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+impl HashFn for RescuePrime {
+    const NAME: &'static str = "rescue-prime-goldilocks";
+    const COLLISION_RESISTANCE: usize = 128;
+
+    /// Uses the elements of the digest as the coefficients of the drawn
+    /// element. The field must be an extension of the Goldilocks field of
+    /// degree at most [DIGEST_SIZE].
+    fn digest_to_field<F: Field>(digest: &Output<Self>) -> Option<F> {
+        let degree = F::extension_degree() as usize;
+        assert!(
+            degree <= DIGEST_SIZE,
+            "extension degree {degree} is too large"
+        );
+        let coeffs = digest
+            .chunks(8)
+            .take(degree)
+            .map(|chunk| {
+                F::BasePrimeField::deserialize_compressed(chunk)
+                    .expect("field must be an extension of the Goldilocks field")
+            })
+            .collect::<Vec<F::BasePrimeField>>();
+        F::from_base_prime_field_elems(&coeffs)
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> [Fp; DIGEST_SIZE] {
+    let elements = bytes
+        .chunks(BYTES_PER_ELEMENT)
+        .map(|chunk| {
+            let mut buf = [0; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Fp::from(u64::from_le_bytes(buf))
+        })
+        .collect::<Vec<Fp>>();
+    sponge(&elements, bytes.len(), BYTE_DOMAIN)
+}
+
+/// Interprets bytes as little-endian canonical field elements
+fn bytes_to_elements(bytes: &[u8]) -> Option<Vec<Fp>> {
+    if bytes.len() % 8 != 0 {
+        return None;
+    }
+
+    bytes
+        .chunks(8)
+        .map(|chunk| {
+            let v = u64::from_le_bytes(chunk.try_into().unwrap());
+            (v < MODULUS).then(|| Fp::from(v))
+        })
+        .collect()
+}
+
+fn sponge(elements: &[Fp], length: usize, domain: u64) -> [Fp; DIGEST_SIZE] {
+    let mut state = [Fp::zero(); STATE_WIDTH];
+    state[LENGTH_INDEX] = Fp::from(length as u64);
+    state[DOMAIN_INDEX] = Fp::from(domain);
+
+    // the length is bound to the capacity so zero padding is unambiguous
+    if elements.is_empty() {
+        permute(&mut state);
+    }
+    for chunk in elements.chunks(RATE) {
+        for (s, v) in state[CAPACITY..].iter_mut().zip(chunk) {
+            *s += v;
+        }
+        permute(&mut state);
+    }
+
+    state[CAPACITY..CAPACITY + DIGEST_SIZE].try_into().unwrap()
+}
+
+fn apply_mds(state: &mut [Fp; STATE_WIDTH]) {
+    let mut result = [Fp::zero(); STATE_WIDTH];
+    for (res, row) in result.iter_mut().zip(&MDS) {
+        for (&coeff, v) in row.iter().zip(state.iter()) {
+            *res += Fp::from(coeff) * v;
+        }
+    }
+    *state = result;
+}
+
+fn add_constants(state: &mut [Fp; STATE_WIDTH], constants: &[u64; STATE_WIDTH]) {
+    for (v, &c) in state.iter_mut().zip(constants) {
+        *v += Fp::from(c);
+    }
+}
+
+/// MDS matrix of the permutation (algorithm 4 of the specification)
+pub const MDS: [[u64; STATE_WIDTH]; STATE_WIDTH] = [
+    [
+        2108866337646019936,
+        11223275256334781131,
+        2318414738826783588,
+        11240468238955543594,
+        8007389560317667115,
+        11080831380224887131,
+        3922954383102346493,
+        17194066286743901609,
+        152620255842323114,
+        7203302445933022224,
+        17781531460838764471,
+        2306881200,
+    ],
+    [
+        3368836954250922620,
+        5531382716338105518,
+        7747104620279034727,
+        14164487169476525880,
+        4653455932372793639,
+        5504123103633670518,
+        3376629427948045767,
+        1687083899297674997,
+        8324288417826065247,
+        17651364087632826504,
+        15568475755679636039,
+        4656488262337620150,
+    ],
+    [
+        2560535215714666606,
+        10793518538122219186,
+        408467828146985886,
+        13894393744319723897,
+        17856013635663093677,
+        14510101432365346218,
+        12175743201430386993,
+        12012700097100374591,
+        976880602086740182,
+        3187015135043748111,
+        4630899319883688283,
+        17674195666610532297,
+    ],
+    [
+        10940635879119829731,
+        9126204055164541072,
+        13441880452578323624,
+        13828699194559433302,
+        6245685172712904082,
+        3117562785727957263,
+        17389107632996288753,
+        3643151412418457029,
+        10484080975961167028,
+        4066673631745731889,
+        8847974898748751041,
+        9548808324754121113,
+    ],
+    [
+        15656099696515372126,
+        309741777966979967,
+        16075523529922094036,
+        5384192144218250710,
+        15171244241641106028,
+        6660319859038124593,
+        6595450094003204814,
+        15330207556174961057,
+        2687301105226976975,
+        15907414358067140389,
+        2767130804164179683,
+        8135839249549115549,
+    ],
+    [
+        14687393836444508153,
+        8122848807512458890,
+        16998154830503301252,
+        2904046703764323264,
+        11170142989407566484,
+        5448553946207765015,
+        9766047029091333225,
+        3852354853341479440,
+        14577128274897891003,
+        11994931371916133447,
+        8299269445020599466,
+        2859592328380146288,
+    ],
+    [
+        4920761474064525703,
+        13379538658122003618,
+        3169184545474588182,
+        15753261541491539618,
+        622292315133191494,
+        14052907820095169428,
+        5159844729950547044,
+        17439978194716087321,
+        9945483003842285313,
+        13647273880020281344,
+        14750994260825376,
+        12575187259316461486,
+    ],
+    [
+        3371852905554824605,
+        8886257005679683950,
+        15677115160380392279,
+        13242906482047961505,
+        12149996307978507817,
+        1427861135554592284,
+        4033726302273030373,
+        14761176804905342155,
+        11465247508084706095,
+        12112647677590318112,
+        17343938135425110721,
+        14654483060427620352,
+    ],
+    [
+        5421794552262605237,
+        14201164512563303484,
+        5290621264363227639,
+        1020180205893205576,
+        14311345105258400438,
+        7828111500457301560,
+        9436759291445548340,
+        5716067521736967068,
+        15357555109169671716,
+        4131452666376493252,
+        16785275933585465720,
+        11180136753375315897,
+    ],
+    [
+        10451661389735482801,
+        12128852772276583847,
+        10630876800354432923,
+        6884824371838330777,
+        16413552665026570512,
+        13637837753341196082,
+        2558124068257217718,
+        4327919242598628564,
+        4236040195908057312,
+        2081029262044280559,
+        2047510589162918469,
+        6835491236529222042,
+    ],
+    [
+        5675273097893923172,
+        8120839782755215647,
+        9856415804450870143,
+        1960632704307471239,
+        15279057263127523057,
+        17999325337309257121,
+        72970456904683065,
+        8899624805082057509,
+        16980481565524365258,
+        6412696708929498357,
+        13917768671775544479,
+        5505378218427096880,
+    ],
+    [
+        10318314766641004576,
+        17320192463105632563,
+        11540812969169097044,
+        7270556942018024148,
+        4755326086930560682,
+        2193604418377108959,
+        11681945506511803967,
+        8000243866012209465,
+        6746478642521594042,
+        12096331252283646217,
+        13208137848575217268,
+        5548519654341606996,
+    ],
+];
+
+/// Constants added in each half round (algorithm 5 of the specification)
+pub const ROUND_CONSTANTS: [[u64; STATE_WIDTH]; 2 * NUM_ROUNDS] = [
+    [
+        16089809142501829443,
+        3960375389654894755,
+        2341987601489900096,
+        16513505200733590422,
+        2491992808872511534,
+        2243959319871113313,
+        1072250566756987431,
+        9576211715023554739,
+        13816740116943445245,
+        1013981081016507493,
+        6469202228346393176,
+        651486455260752235,
+    ],
+    [
+        10659391161334081468,
+        6658732499907968660,
+        13472970356821082105,
+        11254129182906430457,
+        2200184099877207561,
+        9367536782889046900,
+        5776283441396365529,
+        15880305242785227614,
+        15064577366950298089,
+        17182365414675952436,
+        221227465681839092,
+        10904420836212840752,
+    ],
+    [
+        6770068611756627448,
+        9429015895190610092,
+        6345154718738704426,
+        1348264131729825254,
+        11257253180296854021,
+        10209505772531486556,
+        13936278878169192368,
+        465229985152496221,
+        16122840733837976660,
+        15126432412337961371,
+        18195743520412640434,
+        4482481892207055145,
+    ],
+    [
+        9371429429698492981,
+        15659859461375396037,
+        3395558493871255061,
+        660144660555450404,
+        5074125520981119417,
+        17453702653133595770,
+        11221110160893954851,
+        6495862879055376432,
+        17061625752140729123,
+        12368428993775985339,
+        8908366829754037876,
+        2078111330029178445,
+    ],
+    [
+        4392703580426358869,
+        1665895348145983,
+        4219736658995217386,
+        1227613135081507795,
+        8190773212267744239,
+        8282001820492621236,
+        15836395107332526493,
+        5607076305580595108,
+        8785440730814333716,
+        15628355668353690236,
+        15635676168256493691,
+        8231009457495604357,
+    ],
+    [
+        13168535446547922823,
+        18239226123757899503,
+        7641189915286036988,
+        7820691679952216969,
+        1111836394951152974,
+        139835781513562161,
+        7076109422888404220,
+        5005587840202053100,
+        6487413309175970078,
+        5695661949695470409,
+        18151333218502551049,
+        12789465505850716019,
+    ],
+    [
+        3242413417035426569,
+        10974415453760425628,
+        18279530845486603448,
+        14045481066120861736,
+        12525452082923300704,
+        1905254592892409109,
+        9346668368089967636,
+        1735104742415647612,
+        3317525224474295113,
+        3946195652028520851,
+        444992070656934445,
+        3102693390775176900,
+    ],
+    [
+        17167036726114384788,
+        5848569342998419381,
+        14114543252495674018,
+        15114629034072612072,
+        5270549373288442547,
+        12129247407828856056,
+        18281855207204785420,
+        597402865817114738,
+        6042112508927673927,
+        112810046686999112,
+        2881728079621071110,
+        3443512534203368354,
+    ],
+    [
+        11524270175738513568,
+        16596131169768068084,
+        12046592239696686456,
+        10335258789985873044,
+        3804833210737803414,
+        4871342344579357943,
+        5506150606643613730,
+        1144769156473837296,
+        15770771149643607584,
+        22835664835299105,
+        15624512048862012204,
+        8438597895149015250,
+    ],
+    [
+        13297012143576436426,
+        7353183188832933627,
+        14475065819552011569,
+        1989958170371263671,
+        2759712450935595252,
+        5888211745553259072,
+        3366223208861836535,
+        10871170457430163614,
+        7436939156294010029,
+        10083282185253045512,
+        1727628517966770716,
+        15876537645083757620,
+    ],
+    [
+        2077569020629574154,
+        29247543278389127,
+        7513950682870485886,
+        14493142396838430095,
+        13137935083971782251,
+        17044896521696396448,
+        8358879158995995396,
+        6631372338926182917,
+        16141080336903561376,
+        12097878985033236818,
+        16582826484887094232,
+        11184522740344979309,
+    ],
+    [
+        14491184939776942308,
+        16755331289686337123,
+        4204064227783814013,
+        17375825663893345502,
+        16513382692712470059,
+        12671191098792302109,
+        7367953856881804491,
+        4828831248603618923,
+        605213678344474020,
+        10779667723419446880,
+        15588592678889744953,
+        16719715619459928934,
+    ],
+    [
+        11545814656420730331,
+        7520668505762229291,
+        5433441394427246897,
+        17588828388580402390,
+        8308794351872961990,
+        14007549481740032380,
+        15898890571959671932,
+        812931430828255689,
+        6818534534911166209,
+        12562621953249472036,
+        3817830678013523962,
+        16954219307307160453,
+    ],
+    [
+        7976559292405617294,
+        10624879739965265183,
+        11858994588137577101,
+        6953938202587799945,
+        15487983798101099477,
+        828942630404743552,
+        15918441202173246890,
+        10151280024237311966,
+        10562603357011259664,
+        18397974285238070711,
+        878544804620014725,
+        16579617335735550589,
+    ],
+];
@@ -1,88 +1,151 @@
+#[cfg(feature = "prover")]
 use crate::challenges::Challenges;
 use crate::merkle::MerkleProof;
+#[cfg(feature = "prover")]
 use crate::merkle::MerkleTree;
+#[cfg(feature = "prover")]
+use crate::merkle::MerkleTreeError;
+use crate::merkle::Salt;
+#[cfg(feature = "prover")]
+use crate::prover::validate_trace;
+#[cfg(feature = "prover")]
+use crate::prover::ProvingError;
 use crate::Air;
+#[cfg(feature = "prover")]
+use crate::GpuAir;
+#[cfg(feature = "prover")]
 use crate::Matrix;
+#[cfg(feature = "prover")]
+use crate::ProofOptions;
+#[cfg(feature = "prover")]
+use crate::VerificationKey;
 use alloc::vec::Vec;
+#[cfg(feature = "prover")]
+use ark_ff::FftField;
+#[cfg(feature = "prover")]
 use ark_ff::Field;
+#[cfg(feature = "prover")]
 use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
 use ark_poly::EvaluationDomain;
+#[cfg(feature = "prover")]
+use ark_poly::Radix2EvaluationDomain;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
 use core::ops::Range;
+#[cfg(feature = "prover")]
 use digest::Digest;
+#[cfg(feature = "prover")]
+use digest::Output;
+#[cfg(feature = "prover")]
+use gpu_poly::GpuFftField;
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct Queries<A: Air> {
+pub struct Queries<A: Air + ?Sized> {
     pub base_trace_values: Vec<A::Fp>,
     pub extension_trace_values: Vec<A::Fq>,
     pub composition_trace_values: Vec<A::Fq>,
-    pub base_trace_proofs: Vec<MerkleProof>,
-    pub extension_trace_proofs: Vec<MerkleProof>,
-    pub composition_trace_proofs: Vec<MerkleProof>,
+    pub preprocessed_trace_values: Vec<A::Fp>,
+    /// Batch proofs for all queried rows
+    pub base_trace_proof: MerkleProof,
+    pub extension_trace_proof: Option<MerkleProof>,
+    pub composition_trace_proof: MerkleProof,
+    pub preprocessed_trace_proof: Option<MerkleProof>,
+    /// Salts of the queried rows. Empty unless zero-knowledge is enabled.
+    pub base_trace_salts: Vec<Salt>,
+    pub extension_trace_salts: Vec<Salt>,
+    pub composition_trace_salts: Vec<Salt>,
 }
 
-impl<A: Air> Queries<A> {
+#[cfg(feature = "prover")]
+impl<A: GpuAir + ?Sized> Queries<A> {
+    /// Opens the rows of each trace at the given positions of the LDE domain
     #[allow(clippy::too_many_arguments)]
     pub fn new<D: Digest>(
-        air: &A,
+        lde_xs: Radix2EvaluationDomain<A::Fp>,
         base_trace_polys: &Matrix<A::Fp>,
         extension_trace_polys: Option<&Matrix<A::Fq>>,
         composition_trace_lde: &Matrix<A::Fq>,
-        base_commitment: MerkleTree<D>,
-        extension_commitment: Option<MerkleTree<D>>,
-        composition_commitment: MerkleTree<D>,
+        preprocessed_trace: Option<&PreprocessedTrace<A::Fp, D>>,
+        base_commitment: &MerkleTree<D>,
+        extension_commitment: Option<&MerkleTree<D>>,
+        composition_commitment: &MerkleTree<D>,
+        base_salts: Option<&[Salt]>,
+        extension_salts: Option<&[Salt]>,
+        composition_salts: Option<&[Salt]>,
         positions: &[usize],
-    ) -> Self {
-        let lde_xs = air.lde_domain();
+    ) -> Result<Self, MerkleTreeError> {
         let mut base_trace_values = Vec::new();
         let mut extension_trace_values = Vec::new();
         let mut composition_trace_values = Vec::new();
-        let mut base_trace_proofs = Vec::new();
-        let mut extension_trace_proofs = Vec::new();
-        let mut composition_trace_proofs = Vec::new();
+        let mut preprocessed_trace_values = Vec::new();
         for &position in positions {
             // execution trace
             let lde_x = lde_xs.element(position);
             let base_trace_row = base_trace_polys.evaluate_at(lde_x);
             base_trace_values.extend(base_trace_row);
-            let base_proof = base_commitment.prove(position).unwrap();
-            base_trace_proofs.push(base_proof);
 
             if let Some(extension_trace_polys) = extension_trace_polys {
                 // TODO: suport ark DomainCoeff on evaluate_at
                 let extension_trace_row = extension_trace_polys.evaluate_at(A::Fq::from(lde_x));
                 extension_trace_values.extend(extension_trace_row);
-                let extension_proof = extension_commitment
-                    .as_ref()
-                    .unwrap()
-                    .prove(position)
-                    .unwrap();
-                extension_trace_proofs.push(extension_proof);
             }
 
             // composition trace
-            let composition_trace_row = composition_trace_lde.get_row(position).unwrap();
+            let composition_trace_row = get_row(composition_trace_lde, position)?;
             composition_trace_values.extend(composition_trace_row);
-            let composition_proof = composition_commitment.prove(position).unwrap();
-            composition_trace_proofs.push(composition_proof);
+
+            // preprocessed trace
+            if let Some(preprocessed_trace) = preprocessed_trace {
+                let preprocessed_trace_row = get_row(&preprocessed_trace.lde, position)?;
+                preprocessed_trace_values.extend(preprocessed_trace_row);
+            }
         }
-        Queries {
+        let base_trace_proof = base_commitment.prove_batch(positions)?;
+        let extension_trace_proof = extension_commitment
+            .map(|commitment| commitment.prove_batch(positions))
+            .transpose()?;
+        let composition_trace_proof = composition_commitment.prove_batch(positions)?;
+        let preprocessed_trace_proof = preprocessed_trace
+            .map(|trace| trace.tree.prove_batch(positions))
+            .transpose()?;
+        let query_salts = |salts: Option<&[Salt]>| match salts {
+            Some(salts) => positions.iter().map(|&position| salts[position]).collect(),
+            None => Vec::new(),
+        };
+        Ok(Queries {
+            base_trace_salts: query_salts(base_salts),
+            extension_trace_salts: query_salts(extension_salts),
+            composition_trace_salts: query_salts(composition_salts),
             base_trace_values,
             extension_trace_values,
             composition_trace_values,
-            base_trace_proofs,
-            extension_trace_proofs,
-            composition_trace_proofs,
-        }
+            preprocessed_trace_values,
+            base_trace_proof,
+            extension_trace_proof,
+            composition_trace_proof,
+            preprocessed_trace_proof,
+        })
     }
 }
 
+#[cfg(feature = "prover")]
+fn get_row<F: Field>(lde: &Matrix<F>, position: usize) -> Result<Vec<F>, MerkleTreeError> {
+    lde.get_row(position)
+        .ok_or(MerkleTreeError::LeafIndexOutOfBounds {
+            i: position,
+            n: lde.num_rows(),
+        })
+}
+
 /// Public metadata about a trace.
 #[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct TraceInfo {
     pub num_base_columns: usize,
     pub num_extension_columns: usize,
+    /// Columns that only depend on the AIR e.g. selectors or lookup tables.
+    /// They're committed to ahead of time rather than by each proof.
+    pub num_preprocessed_columns: usize,
     pub trace_len: usize,
     // TODO: want to change this to auxiliary data
     pub meta: Vec<u8>,
@@ -92,7 +155,8 @@ impl TraceInfo {
     /// Smallest execution trace length
     /// TODO: justify
     pub const MIN_TRACE_LENGTH: usize = 2048;
-    /// Maximum number of columns (base + extension) in an execution trace
+    /// Maximum number of columns (base + extension + preprocessed) in an
+    /// execution trace
     pub const MAX_TRACE_WIDTH: usize = 255;
     /// Maximum number of bytes in trace metadata; currently set at 64KiB.
     pub const MAX_META_BYTES: usize = 65535;
@@ -103,20 +167,26 @@ impl TraceInfo {
         trace_len: usize,
         meta: Option<Vec<u8>>,
     ) -> Self {
-        let num_total_cols = num_base_columns + num_extension_columns;
-        let meta = meta.unwrap_or_default();
-        assert!(num_base_columns > 0, "not enough base columns");
-        assert!(num_total_cols <= Self::MAX_TRACE_WIDTH, "too many columns");
-        assert!(meta.len() <= Self::MAX_META_BYTES, "too much meta data");
-        assert!(trace_len >= Self::MIN_TRACE_LENGTH, "trace too small");
         TraceInfo {
             num_base_columns,
             num_extension_columns,
+            num_preprocessed_columns: 0,
             trace_len,
-            meta,
+            meta: meta.unwrap_or_default(),
         }
     }
 
+    /// Sets the number of columns in the trace's [PreprocessedTrace]
+    pub fn with_preprocessed_columns(mut self, num_preprocessed_columns: usize) -> Self {
+        self.num_preprocessed_columns = num_preprocessed_columns;
+        self
+    }
+
+    /// Total number of base, extension and preprocessed columns
+    pub fn num_columns(&self) -> usize {
+        self.num_base_columns + self.num_extension_columns + self.num_preprocessed_columns
+    }
+
     pub fn base_columns_range(&self) -> Range<usize> {
         0..self.num_base_columns
     }
@@ -124,13 +194,22 @@ impl TraceInfo {
     pub fn extension_columns_range(&self) -> Range<usize> {
         self.num_base_columns..self.num_base_columns + self.num_extension_columns
     }
+
+    /// Preprocessed columns are indexed after the base and extension columns
+    pub fn preprocessed_columns_range(&self) -> Range<usize> {
+        let start = self.num_base_columns + self.num_extension_columns;
+        start..start + self.num_preprocessed_columns
+    }
 }
 
 // TODO: docs: An execution trace of a computation, or the trace in short, is a
 // sequence of machine states, one per clock cycle source: https://medium.com/starkware/starkdex-deep-dive-the-stark-core-engine-497942d0f0ab
+#[cfg(feature = "prover")]
 pub trait Trace {
     const NUM_BASE_COLUMNS: usize;
     const NUM_EXTENSION_COLUMNS: usize = 0;
+    /// Number of columns in the prover's [PreprocessedTrace]
+    const NUM_PREPROCESSED_COLUMNS: usize = 0;
 
     type Fp: PrimeField;
     type Fq: Field<BasePrimeField = Self::Fp>;
@@ -161,6 +240,7 @@ pub trait Trace {
             self.len(),
             self.meta().map(|meta| meta.to_vec()),
         )
+        .with_preprocessed_columns(Self::NUM_PREPROCESSED_COLUMNS)
     }
 
     /// Returns metadata associated with this trace.
@@ -172,3 +252,113 @@ pub trait Trace {
         self.len() == 0
     }
 }
+
+/// Object safe view of a [Trace] so traces of different types can be proved
+/// together
+#[cfg(feature = "prover")]
+pub trait TableTrace<Fp: Field, Fq: Field> {
+    fn info(&self) -> TraceInfo;
+
+    fn base_columns(&self) -> &Matrix<Fp>;
+
+    fn build_extension_columns(&self, challenges: &Challenges<Fq>) -> Option<Matrix<Fq>>;
+
+    /// Checks the dimensions of the trace
+    fn validate(&self) -> Result<(), ProvingError>;
+}
+
+#[cfg(feature = "prover")]
+impl<T: Trace> TableTrace<T::Fp, T::Fq> for T {
+    fn info(&self) -> TraceInfo {
+        Trace::info(self)
+    }
+
+    fn base_columns(&self) -> &Matrix<T::Fp> {
+        Trace::base_columns(self)
+    }
+
+    fn build_extension_columns(&self, challenges: &Challenges<T::Fq>) -> Option<Matrix<T::Fq>> {
+        Trace::build_extension_columns(self, challenges)
+    }
+
+    fn validate(&self) -> Result<(), ProvingError> {
+        validate_trace(self)
+    }
+}
+
+/// Columns that only depend on the AIR rather than the execution e.g. selectors
+/// or lookup tables. They're interpolated, extended and committed to once and
+/// can then be used by any number of proofs. Verifiers check openings against
+/// the commitment in the [VerificationKey].
+#[cfg(feature = "prover")]
+pub struct PreprocessedTrace<F: GpuFftField<FftField = F> + FftField, D: Digest> {
+    columns: Matrix<F>,
+    polys: Matrix<F>,
+    lde: Matrix<F>,
+    lde_domain: Radix2EvaluationDomain<F>,
+    tree: MerkleTree<D>,
+}
+
+#[cfg(feature = "prover")]
+impl<F: GpuFftField<FftField = F> + FftField, D: Digest> PreprocessedTrace<F, D> {
+    /// Commits to the columns over the LDE domain of proofs with the given
+    /// options. The LDE domain is offset by the field's generator.
+    pub fn new(columns: Matrix<F>, options: ProofOptions) -> Self {
+        let lde_size = columns.num_rows() * options.lde_blowup_factor as usize;
+        Self::with_lde_size(columns, lde_size)
+    }
+
+    /// Commits to the columns over an LDE domain of `lde_size` rows. Tables of a
+    /// [MultiProof](crate::multi::MultiProof) are extended over the LDE domain
+    /// of the longest table.
+    pub fn with_lde_size(columns: Matrix<F>, lde_size: usize) -> Self {
+        let trace_domain = Radix2EvaluationDomain::new(columns.num_rows()).unwrap();
+        let lde_domain = Radix2EvaluationDomain::new_coset(lde_size, F::GENERATOR).unwrap();
+        let polys = columns.interpolate(trace_domain);
+        let lde = polys.evaluate(lde_domain);
+        let tree = lde.commit_to_rows();
+        PreprocessedTrace {
+            columns,
+            polys,
+            lde,
+            lde_domain,
+            tree,
+        }
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.columns.num_cols()
+    }
+
+    pub fn columns(&self) -> &Matrix<F> {
+        &self.columns
+    }
+
+    pub fn polys(&self) -> &Matrix<F> {
+        &self.polys
+    }
+
+    /// Evaluations of the columns over the LDE domain
+    pub fn lde(&self) -> &Matrix<F> {
+        &self.lde
+    }
+
+    pub fn lde_domain(&self) -> Radix2EvaluationDomain<F> {
+        self.lde_domain
+    }
+
+    /// Root of the Merkle tree of the LDE rows
+    pub fn commitment(&self) -> &Output<D> {
+        self.tree.root()
+    }
+
+    /// Returns the key that verifiers use to check proofs that use these columns
+    pub fn verification_key(&self) -> VerificationKey {
+        VerificationKey {
+            preprocessed_trace_commitment: self.commitment().to_vec(),
+            num_columns: self.columns.num_cols(),
+            trace_len: self.columns.num_rows(),
+            lde_size: self.lde_domain.size(),
+        }
+    }
+}
@@ -3,34 +3,16 @@ use ark_ff::FftField;
 use ark_ff::Field;
 use ark_poly::domain::Radix2EvaluationDomain;
 use ark_poly::EvaluationDomain;
+#[cfg(feature = "prover")]
+use ark_std::rand::Rng;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Mul;
+#[cfg(feature = "prover")]
 use gpu_poly::GpuVec;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-#[cfg(feature = "std")]
-pub struct Timer<'a> {
-    name: &'a str,
-    start: std::time::Instant,
-}
-
-#[cfg(feature = "std")]
-impl<'a> Timer<'a> {
-    pub fn new(name: &'a str) -> Timer<'a> {
-        let start = std::time::Instant::now();
-        Timer { name, start }
-    }
-}
-
-#[cfg(feature = "std")]
-impl<'a> Drop for Timer<'a> {
-    fn drop(&mut self) {
-        println!("{} in {:?}", self.name, self.start.elapsed());
-    }
-}
-
 pub fn interleave<T: Copy + Send + Sync + Default, const RADIX: usize>(
     source: &[T],
 ) -> Vec<[T; RADIX]> {
@@ -62,6 +44,13 @@ pub fn ceil_power_of_two(value: usize) -> usize {
     }
 }
 
+/// Smallest constraint evaluation blowup factor that can fit a constraint of
+/// the given degree i.e. the smallest power of two `b` with
+/// `degree < b * trace_len`
+pub fn ce_blowup_factor(degree: usize, trace_len: usize) -> usize {
+    ceil_power_of_two(degree / trace_len + 1)
+}
+
 // from arkworks
 /// This evaluates the vanishing polynomial for this domain at tau.
 pub fn evaluate_vanishing_polynomial<F: FftField, T: Field>(
@@ -102,6 +91,22 @@ pub fn fill_vanishing_polynomial<F: FftField>(
         });
 }
 
+/// Adds `r(x) * (x^n - 1)` to a polynomial with `n` coefficients where `r(x)`
+/// has `num_coeffs` random coefficients. The polynomial evaluates to the same
+/// values over the size `n` domain but its evaluations anywhere else are
+/// masked. The polynomial is resized to `2n` coefficients.
+#[cfg(feature = "prover")]
+pub fn randomize_poly<F: Field>(coeffs: &mut GpuVec<F>, num_coeffs: usize, rng: &mut impl Rng) {
+    let n = coeffs.len();
+    assert!(num_coeffs <= n);
+    coeffs.resize(2 * n, F::zero());
+    for i in 0..num_coeffs {
+        let r = F::rand(rng);
+        coeffs[i] -= r;
+        coeffs[n + i] += r;
+    }
+}
+
 // taken from arkworks-rs
 /// Horner's method for polynomial evaluation
 #[inline]
@@ -152,10 +157,11 @@ pub fn conjectured_security_level(
     grinding_factor: usize,
 ) -> usize {
     // compute max security we can get for a given field size
-    let field_security = field_bits - (lde_blowup_factor * trace_len).trailing_zeros() as usize;
+    let domain_bits = (lde_blowup_factor * trace_len).trailing_zeros() as usize;
+    let field_security = field_bits.saturating_sub(domain_bits);
 
     // compute security we get by executing multiple query rounds
-    let security_per_query = lde_blowup_factor.ilog2() as usize;
+    let security_per_query = lde_blowup_factor.checked_ilog2().unwrap_or(0) as usize;
     let mut query_security = security_per_query * num_fri_quiries;
 
     // include grinding factor contributions only for proofs adequate security
@@ -164,12 +170,13 @@ pub fn conjectured_security_level(
     }
 
     core::cmp::min(
-        core::cmp::min(field_security, query_security) - 1,
+        core::cmp::min(field_security, query_security).saturating_sub(1),
         hash_fn_security,
     )
 }
 
 // TODO: docs
+#[cfg(feature = "prover")]
 pub fn reduce_lde_blowup_factor<T: Copy>(
     lde: &mut GpuVec<T>,
     blowup_from: usize,
@@ -1,28 +1,43 @@
+use crate::air::draw_challenges;
+use crate::air::draw_constraint_composition_coeffs;
+use crate::air::draw_deep_composition_coeffs;
+use crate::air::DeepCompositionCoeffs;
 use crate::challenges::Challenges;
-use crate::composer::DeepCompositionCoeffs;
+use crate::constraints::AlgebraicExpression;
 use crate::constraints::FieldConstant;
 use crate::fri;
+use crate::fri::FriOptions;
+use crate::fri::FriProof;
 use crate::fri::FriVerifier;
+use crate::hash::HashFn;
 use crate::hints::Hints;
 use crate::merkle::MerkleProof;
 use crate::merkle::MerkleTree;
 use crate::merkle::MerkleTreeError;
+use crate::merkle::Salt;
+use crate::random::public_coin_seed;
 use crate::random::PublicCoin;
+use crate::trace::Queries;
 use crate::Air;
 // use crate::channel::VerifierChannel;
 use crate::Proof;
+use crate::ProofOptions;
+use crate::StarkExtensionOf;
+use crate::TraceInfo;
 use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::vec::Vec;
+use ark_ff::FftField;
 use ark_ff::Field;
 use ark_ff::One;
 use ark_ff::Zero;
 use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
 use core::ops::Deref;
 use digest::Digest;
 use digest::Output;
-use rand::Rng;
-use sha2::Sha256;
 use snafu::Snafu;
 
 /// Errors that are returned during verification of a STARK proof
@@ -39,69 +54,385 @@ pub enum VerificationError {
     ExtensionTraceQueryDoesNotMatchCommitment,
     #[snafu(display("query does not resolve to the composition trace commitment"))]
     CompositionTraceQueryDoesNotMatchCommitment,
+    #[snafu(display("query does not resolve to the preprocessed trace commitment"))]
+    PreprocessedTraceQueryDoesNotMatchCommitment,
     #[snafu(display("insufficient proof of work on fri commitments"))]
     FriProofOfWork,
+    #[snafu(display("proof was generated with {actual} but is being verified with {expected}"))]
+    HashFnMismatch {
+        expected: &'static str,
+        actual: String,
+    },
+    #[snafu(display("public inputs of the proof do not match the expected public inputs"))]
+    PublicInputsMismatch,
+    #[snafu(display(
+        "proof has {security_level} bits of conjectured security but at least \
+         {min_security_level} are required"
+    ))]
+    InsufficientSecurity {
+        security_level: usize,
+        min_security_level: usize,
+    },
+    #[snafu(display("proof has {actual} tables but the AIR has {expected}"))]
+    TableCountMismatch { expected: usize, actual: usize },
+    #[snafu(display("proof has {actual} terminals but the AIR has {expected}"))]
+    TerminalCountMismatch { expected: usize, actual: usize },
+    #[snafu(display("proof has preprocessed columns but no verification key was given"))]
+    MissingVerificationKey,
+    #[snafu(display("verification key has {field} {expected} but the proof has {actual}"))]
+    VerificationKeyMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[snafu(display("{commitment} commitment is {actual} bytes but expected {expected}"))]
+    CommitmentLength {
+        commitment: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[snafu(display("number of queries {num_queries} is not supported"))]
+    InvalidNumQueries { num_queries: u8 },
+    #[snafu(display(
+        "lde blowup factor {lde_blowup_factor} with fri blowup factor {fri_blowup_factor} is not \
+         supported"
+    ))]
+    InvalidBlowupFactor {
+        lde_blowup_factor: u8,
+        fri_blowup_factor: u8,
+    },
+    #[snafu(display("grinding factor {grinding_factor} is not supported"))]
+    GrindingFactorTooLarge { grinding_factor: u8 },
+    #[snafu(display("fri folding factor {fri_folding_factor} is not supported"))]
+    UnsupportedFriFoldingFactor { fri_folding_factor: u8 },
 }
 
-impl<A: Air> Proof<A> {
-    pub fn verify(self) -> Result<(), VerificationError> {
+/// Requirements a proof must meet before it's verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    /// Minimum conjectured security level of the proof in bits
+    pub min_security_level: usize,
+}
+
+impl VerificationPolicy {
+    pub fn new(min_security_level: usize) -> Self {
+        VerificationPolicy { min_security_level }
+    }
+}
+
+/// Commitments that are made ahead of time and shared by many proofs
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerificationKey {
+    /// Root of the Merkle tree of the preprocessed trace LDE rows
+    pub preprocessed_trace_commitment: Vec<u8>,
+    /// Number of preprocessed columns
+    pub num_columns: usize,
+    /// Number of rows in the preprocessed trace
+    pub trace_len: usize,
+    /// Size of the LDE domain the preprocessed trace is committed over
+    pub lde_size: usize,
+}
+
+impl VerificationKey {
+    /// Checks the key was made for a table with the given trace info and LDE
+    /// domain size
+    fn check(&self, trace_info: &TraceInfo, lde_size: usize) -> Result<(), VerificationError> {
+        let fields = [
+            (
+                "columns",
+                self.num_columns,
+                trace_info.num_preprocessed_columns,
+            ),
+            ("trace length", self.trace_len, trace_info.trace_len),
+            ("LDE size", self.lde_size, lde_size),
+        ];
+        for (field, expected, actual) in fields {
+            if expected != actual {
+                return Err(VerificationError::VerificationKeyMismatch {
+                    field,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verification data that only depends on the AIR's trace info, proof options
+/// and public inputs. It's computed once per proof or once per group of proofs
+/// by a [BatchVerifier](crate::BatchVerifier).
+pub(crate) struct VerifierContext<A: Air + ?Sized> {
+    pub trace_arguments: Vec<(usize, isize)>,
+    /// The AIR's constraints after [AlgebraicExpression::simplify]
+    pub constraints: Vec<AlgebraicExpression<A::Fp, A::Fq>>,
+    /// Degree adjustment of each simplified constraint in the composition
+    /// polynomial
+    pub degree_adjustments: Vec<u64>,
+    pub num_challenges: usize,
+    pub ce_blowup_factor: usize,
+    pub trace_degree: usize,
+    pub trace_domain: Radix2EvaluationDomain<A::Fp>,
+    pub lde_domain: Radix2EvaluationDomain<A::Fp>,
+    pub fri_options: FriOptions,
+    pub fri_domain: Radix2EvaluationDomain<A::Fp>,
+}
+
+impl<A: Air + ?Sized> VerifierContext<A> {
+    pub fn new(air: &A) -> Self {
+        let trace_degree = air.trace_degree();
+        let composition_degree = air.composition_degree();
+        let constraints = air
+            .constraints()
+            .iter()
+            .map(AlgebraicExpression::simplify)
+            .collect::<Vec<_>>();
+        let degree_adjustments = constraints
+            .iter()
+            .map(|constraint| {
+                let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
+                let evaluation_degree = numerator_degree - denominator_degree;
+                assert!(evaluation_degree <= composition_degree);
+                (composition_degree - evaluation_degree) as u64
+            })
+            .collect();
+        let fri_options = air.options().into_fri_options();
+        VerifierContext {
+            trace_arguments: air.trace_arguments().into_iter().collect(),
+            constraints,
+            degree_adjustments,
+            num_challenges: air.num_challenges(),
+            ce_blowup_factor: air.ce_blowup_factor(),
+            trace_degree,
+            trace_domain: air.trace_domain(),
+            lde_domain: air.lde_domain(),
+            fri_options,
+            fri_domain: fri_options.domain(trace_degree),
+        }
+    }
+}
+
+impl<A: Air, D: HashFn> Proof<A, D> {
+    /// Verifies the proof attests to the expected public inputs. The expected
+    /// public inputs, rather than the ones embedded in the proof, are bound
+    /// into the transcript. Proofs with options that don't satisfy the policy
+    /// are rejected.
+    pub fn verify(
+        &self,
+        expected_public_inputs: &A::PublicInputs,
+        policy: &VerificationPolicy,
+    ) -> Result<(), VerificationError> {
+        self.check_policy(expected_public_inputs, policy)?;
+        let air = A::new(
+            self.trace_info.clone(),
+            expected_public_inputs.clone(),
+            self.options,
+        );
+        self.verify_with_context(&air, &VerifierContext::new(&air), None)
+    }
+
+    /// Verifies a proof that opens preprocessed columns. Openings are checked
+    /// against the commitment in `key`.
+    pub fn verify_with_key(
+        &self,
+        expected_public_inputs: &A::PublicInputs,
+        key: &VerificationKey,
+        policy: &VerificationPolicy,
+    ) -> Result<(), VerificationError> {
+        self.check_policy(expected_public_inputs, policy)?;
+        let air = A::new(
+            self.trace_info.clone(),
+            expected_public_inputs.clone(),
+            self.options,
+        );
+        self.verify_with_context(&air, &VerifierContext::new(&air), Some(key))
+    }
+
+    /// Checks the hash function, public inputs and security level of the proof
+    pub(crate) fn check_policy(
+        &self,
+        expected_public_inputs: &A::PublicInputs,
+        policy: &VerificationPolicy,
+    ) -> Result<(), VerificationError> {
         use VerificationError::*;
 
-        let Proof {
-            base_trace_commitment,
-            extension_trace_commitment,
-            composition_trace_commitment,
-            execution_trace_ood_evals,
-            composition_trace_ood_evals,
-            trace_queries,
-            trace_info,
-            public_inputs,
-            options,
-            fri_proof,
-            pow_nonce,
-            ..
-        } = self;
-
-        let mut seed = Vec::new();
-        public_inputs.serialize_compressed(&mut seed).unwrap();
-        trace_info.serialize_compressed(&mut seed).unwrap();
-        options.serialize_compressed(&mut seed).unwrap();
-        let mut public_coin = PublicCoin::<Sha256>::new(&seed);
-
-        let air = A::new(trace_info, public_inputs, options);
-
-        let base_trace_comitment = Output::<Sha256>::from_iter(base_trace_commitment);
-        public_coin.reseed(&base_trace_comitment.deref());
-        let challenges = air.get_challenges(&mut public_coin);
-        let hints = air.get_hints(&challenges);
-
-        let extension_trace_commitment =
-            extension_trace_commitment.map(|extension_trace_commitment| {
-                let extension_trace_commitment =
-                    Output::<Sha256>::from_iter(extension_trace_commitment);
-                public_coin.reseed(&extension_trace_commitment.deref());
-                extension_trace_commitment
+        check_options(&self.options)?;
+
+        if self.hash_fn != D::NAME {
+            return Err(HashFnMismatch {
+                expected: D::NAME,
+                actual: self.hash_fn.clone(),
             });
+        }
+
+        if serialize(&self.public_inputs) != serialize(expected_public_inputs) {
+            return Err(PublicInputsMismatch);
+        }
+
+        let security_level = self.conjectured_security_level();
+        if security_level < policy.min_security_level {
+            return Err(InsufficientSecurity {
+                security_level,
+                min_security_level: policy.min_security_level,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn verify_with_context(
+        &self,
+        air: &A,
+        context: &VerifierContext<A>,
+        key: Option<&VerificationKey>,
+    ) -> Result<(), VerificationError> {
+        let mut public_coin = PublicCoin::<D>::new(&public_coin_seed::<A, D>(air));
+        let table = VerifierTable {
+            air,
+            context,
+            key,
+            base_trace_commitment: &self.base_trace_commitment,
+            extension_trace_commitment: self.extension_trace_commitment.as_deref(),
+            composition_trace_commitment: &self.composition_trace_commitment,
+            execution_trace_ood_evals: &self.execution_trace_ood_evals,
+            composition_trace_ood_evals: &self.composition_trace_ood_evals,
+            trace_queries: &self.trace_queries,
+        };
+        verify_tables(
+            &mut public_coin,
+            &[table],
+            &[],
+            |_, challenges, _| air.get_hints(challenges),
+            self.options,
+            &self.fri_proof,
+            self.pow_nonce,
+        )
+    }
+}
+
+/// A table of a proof and the AIR it's verified against
+pub(crate) struct VerifierTable<'a, A: Air + ?Sized> {
+    pub air: &'a A,
+    pub context: &'a VerifierContext<A>,
+    /// Key of the table's preprocessed columns
+    pub key: Option<&'a VerificationKey>,
+    pub base_trace_commitment: &'a [u8],
+    pub extension_trace_commitment: Option<&'a [u8]>,
+    pub composition_trace_commitment: &'a [u8],
+    pub execution_trace_ood_evals: &'a [A::Fq],
+    pub composition_trace_ood_evals: &'a [A::Fq],
+    pub trace_queries: &'a Queries<A>,
+}
+
+/// Verifies one or more tables that share a transcript. This mirrors the
+/// prover: tables are opened over the LDE domain of the longest table and the
+/// DEEP composition polynomials of shorter tables have their degree shifted so
+/// a single FRI instance checks every table against its own degree bound.
+pub(crate) fn verify_tables<A: Air + ?Sized, D: HashFn>(
+    public_coin: &mut PublicCoin<D>,
+    tables: &[VerifierTable<'_, A>],
+    terminals: &[A::Fq],
+    get_hints: impl Fn(usize, &Challenges<A::Fq>, &[A::Fq]) -> Hints<A::Fq>,
+    options: ProofOptions,
+    fri_proof: &FriProof<A::Fq>,
+    pow_nonce: u64,
+) -> Result<(), VerificationError> {
+    use VerificationError::*;
+
+    // the longest table determines the LDE and FRI domains
+    let largest = tables
+        .iter()
+        .map(|table| table.context)
+        .max_by_key(|context| context.trace_degree)
+        .expect("there is at least one table");
+    let max_trace_degree = largest.trace_degree;
+
+    let mut preprocessed_trace_commitments = Vec::new();
+    for table in tables {
+        let trace_info = table.air.trace_info();
+        let commitment = match table.key {
+            Some(key) => {
+                // a key must not be dropped by declaring its columns as base columns
+                key.check(trace_info, largest.lde_domain.size())?;
+                let commitment = parse_commitment::<D>(
+                    &key.preprocessed_trace_commitment,
+                    "preprocessed trace",
+                )?;
+                public_coin.reseed(&commitment.deref());
+                Some(commitment)
+            }
+            None if trace_info.num_preprocessed_columns > 0 => return Err(MissingVerificationKey),
+            None => None,
+        };
+        preprocessed_trace_commitments.push(commitment);
+    }
+
+    let mut base_trace_commitments = Vec::new();
+    for table in tables {
+        let commitment = parse_commitment::<D>(table.base_trace_commitment, "base trace")?;
+        public_coin.reseed(&commitment.deref());
+        base_trace_commitments.push(commitment);
+    }
+    let num_challenges = tables.iter().map(|table| table.context.num_challenges);
+    let challenges = draw_challenges(public_coin, num_challenges.max().unwrap_or(0));
+
+    let mut extension_trace_commitments = Vec::new();
+    for table in tables {
+        let commitment = table
+            .extension_trace_commitment
+            .map(|commitment| parse_commitment::<D>(commitment, "extension trace"))
+            .transpose()?;
+        if let Some(commitment) = &commitment {
+            public_coin.reseed(&commitment.deref());
+        }
+        extension_trace_commitments.push(commitment);
+    }
 
-        let composition_coeffs = air.get_constraint_composition_coeffs(&mut public_coin);
-        let composition_trace_commitment =
-            Output::<Sha256>::from_iter(composition_trace_commitment);
-        public_coin.reseed(&composition_trace_commitment.deref());
+    if !terminals.is_empty() {
+        public_coin.reseed(&terminals);
+    }
+    let hints = (0..tables.len())
+        .map(|i| get_hints(i, &challenges, terminals))
+        .collect::<Vec<Hints<A::Fq>>>();
+
+    let composition_coeffs = tables
+        .iter()
+        .map(|table| {
+            let num_constraints = table.context.constraints.len();
+            draw_constraint_composition_coeffs(public_coin, num_constraints)
+        })
+        .collect::<Vec<_>>();
+    let mut composition_trace_commitments = Vec::new();
+    for table in tables {
+        let commitment =
+            parse_commitment::<D>(table.composition_trace_commitment, "composition trace")?;
+        public_coin.reseed(&commitment.deref());
+        composition_trace_commitments.push(commitment);
+    }
 
-        let z = public_coin.draw::<A::Fq>();
+    let z = public_coin.draw::<A::Fq>();
+    let mut trace_ood_eval_maps = Vec::new();
+    for (i, table) in tables.iter().enumerate() {
+        let context = table.context;
+        let execution_trace_ood_evals = table.execution_trace_ood_evals;
+        let composition_trace_ood_evals = table.composition_trace_ood_evals;
         public_coin.reseed(&execution_trace_ood_evals);
         // execution trace ood evaluation map
-        let trace_ood_eval_map = air
-            .trace_arguments()
-            .into_iter()
+        let trace_ood_eval_map = context
+            .trace_arguments
+            .iter()
+            .copied()
             .zip(execution_trace_ood_evals.iter().copied())
             .collect::<BTreeMap<(usize, isize), A::Fq>>();
+        let periodic_values = periodic_ood_evals(table.air, z);
         let calculated_ood_constraint_evaluation = ood_constraint_evaluation(
-            &composition_coeffs,
+            &context.constraints,
+            &context.degree_adjustments,
+            &composition_coeffs[i],
             &challenges,
-            &hints,
+            &hints[i],
+            &periodic_values,
             &trace_ood_eval_map,
-            &air,
             z,
         );
 
@@ -120,112 +451,249 @@ impl<A: Air> Proof<A> {
             return Err(InconsistentOodConstraintEvaluations);
         }
 
-        let deep_coeffs = air.get_deep_composition_coeffs(&mut public_coin);
-        let fri_verifier = FriVerifier::<A::Fq, Sha256>::new(
-            &mut public_coin,
-            options.into_fri_options(),
-            fri_proof,
-            air.trace_len() - 1,
-        )?;
-
-        if options.grinding_factor != 0 {
-            public_coin.reseed(&pow_nonce);
-            if public_coin.seed_leading_zeros() < options.grinding_factor as u32 {
-                return Err(FriProofOfWork);
-            }
-        }
+        trace_ood_eval_maps.push(trace_ood_eval_map);
+    }
 
-        let mut rng = public_coin.draw_rng();
-        let lde_domain_size = air.trace_len() * air.lde_blowup_factor();
-        let query_positions = (0..options.num_queries)
-            .map(|_| rng.gen_range(0..lde_domain_size))
-            .collect::<Vec<usize>>();
+    let deep_coeffs = tables
+        .iter()
+        .map(|table| {
+            let num_trace_arguments = table.context.trace_arguments.len();
+            let num_composition_trace_cols = table.context.ce_blowup_factor;
+            draw_deep_composition_coeffs(
+                public_coin,
+                num_trace_arguments,
+                num_composition_trace_cols,
+            )
+        })
+        .collect::<Vec<_>>();
+    let fri_verifier = FriVerifier::<A::Fq, D>::with_domain(
+        public_coin,
+        largest.fri_options,
+        fri_proof,
+        largest.fri_domain,
+    )?;
+
+    if options.grinding_factor != 0 {
+        public_coin.reseed(&pow_nonce);
+        if public_coin.seed_leading_zeros() < options.grinding_factor as u32 {
+            return Err(FriProofOfWork);
+        }
+    }
 
+    let lde_domain = largest.lde_domain;
+    let lde_domain_size = lde_domain.size();
+    let query_positions = public_coin.draw_queries(options.num_queries.into(), lde_domain_size);
+
+    // in zero-knowledge mode the composition trace has an additional column
+    // for the polynomial that masks the DEEP composition polynomial
+    let zero_knowledge = options.zero_knowledge;
+    let mut deep_evaluations = vec![A::Fq::zero(); query_positions.len()];
+    let commitments = base_trace_commitments
+        .into_iter()
+        .zip(extension_trace_commitments)
+        .zip(preprocessed_trace_commitments)
+        .zip(composition_trace_commitments);
+    for (((table, commitments), deep_coeffs), trace_ood_eval_map) in tables
+        .iter()
+        .zip(commitments)
+        .zip(deep_coeffs)
+        .zip(trace_ood_eval_maps)
+    {
+        let (
+            ((base_trace_commitment, extension_trace_commitment), preprocessed_trace_commitment),
+            composition_trace_commitment,
+        ) = commitments;
+        let trace_info = table.air.trace_info();
+        let trace_queries = table.trace_queries;
         let base_trace_rows = trace_queries
             .base_trace_values
-            .chunks(air.trace_info().num_base_columns)
+            .chunks(trace_info.num_base_columns)
             .collect::<Vec<&[A::Fp]>>();
-        let extension_trace_rows = if air.trace_info().num_extension_columns > 0 {
+        let extension_trace_rows = if trace_info.num_extension_columns > 0 {
             trace_queries
                 .extension_trace_values
-                .chunks(air.trace_info().num_extension_columns)
+                .chunks(trace_info.num_extension_columns)
                 .collect::<Vec<&[A::Fq]>>()
         } else {
             Vec::new()
         };
-
+        let preprocessed_trace_rows = if trace_info.num_preprocessed_columns > 0 {
+            trace_queries
+                .preprocessed_trace_values
+                .chunks(trace_info.num_preprocessed_columns)
+                .collect::<Vec<&[A::Fp]>>()
+        } else {
+            Vec::new()
+        };
+        let num_composition_trace_cols = table.context.ce_blowup_factor + zero_knowledge as usize;
         let composition_trace_rows = trace_queries
             .composition_trace_values
-            .chunks(air.ce_blowup_factor())
+            .chunks(num_composition_trace_cols)
             .collect::<Vec<&[A::Fq]>>();
 
         // base trace positions
-        verify_positions::<Sha256>(
-            base_trace_comitment,
+        verify_positions::<D>(
+            base_trace_commitment,
             &query_positions,
             &base_trace_rows,
-            trace_queries.base_trace_proofs,
+            zero_knowledge.then_some(trace_queries.base_trace_salts.as_slice()),
+            &trace_queries.base_trace_proof,
+            lde_domain_size,
         )
         .map_err(|_| BaseTraceQueryDoesNotMatchCommitment)?;
 
         if let Some(extension_trace_commitment) = extension_trace_commitment {
             // extension trace positions
-            verify_positions::<Sha256>(
+            verify_positions::<D>(
                 extension_trace_commitment,
                 &query_positions,
                 &extension_trace_rows,
-                trace_queries.extension_trace_proofs,
+                zero_knowledge.then_some(trace_queries.extension_trace_salts.as_slice()),
+                trace_queries
+                    .extension_trace_proof
+                    .as_ref()
+                    .ok_or(ExtensionTraceQueryDoesNotMatchCommitment)?,
+                lde_domain_size,
             )
             .map_err(|_| ExtensionTraceQueryDoesNotMatchCommitment)?;
         }
 
+        if let Some(preprocessed_trace_commitment) = preprocessed_trace_commitment {
+            // preprocessed trace positions. These rows are never salted.
+            verify_positions::<D>(
+                preprocessed_trace_commitment,
+                &query_positions,
+                &preprocessed_trace_rows,
+                None,
+                trace_queries
+                    .preprocessed_trace_proof
+                    .as_ref()
+                    .ok_or(PreprocessedTraceQueryDoesNotMatchCommitment)?,
+                lde_domain_size,
+            )
+            .map_err(|_| PreprocessedTraceQueryDoesNotMatchCommitment)?;
+        }
+
         // composition trace positions
-        verify_positions::<Sha256>(
+        verify_positions::<D>(
             composition_trace_commitment,
             &query_positions,
             &composition_trace_rows,
-            trace_queries.composition_trace_proofs,
+            zero_knowledge.then_some(trace_queries.composition_trace_salts.as_slice()),
+            &trace_queries.composition_trace_proof,
+            lde_domain_size,
         )
         .map_err(|_| CompositionTraceQueryDoesNotMatchCommitment)?;
 
-        let deep_evaluations = deep_composition_evaluations(
-            &air,
+        let degree_shift = max_trace_degree - table.context.trace_degree + 1;
+        let table_evaluations = deep_composition_evaluations(
+            table.air,
+            table.context,
+            lde_domain,
             &query_positions,
             deep_coeffs,
             base_trace_rows,
             extension_trace_rows,
+            preprocessed_trace_rows,
             composition_trace_rows,
             z,
             trace_ood_eval_map,
-            composition_trace_ood_evals,
+            table.composition_trace_ood_evals,
+            degree_shift as u64,
         );
+        for (evaluation, table_evaluation) in deep_evaluations.iter_mut().zip(table_evaluations) {
+            *evaluation += table_evaluation;
+        }
+    }
 
-        Ok(fri_verifier.verify(&query_positions, &deep_evaluations)?)
+    Ok(fri_verifier.verify(&query_positions, &deep_evaluations)?)
+}
+
+/// Reads a Merkle root from the proof
+pub(crate) fn parse_commitment<D: Digest>(
+    bytes: &[u8],
+    commitment: &'static str,
+) -> Result<Output<D>, VerificationError> {
+    if bytes.len() != D::output_size() {
+        return Err(VerificationError::CommitmentLength {
+            commitment,
+            expected: D::output_size(),
+            actual: bytes.len(),
+        });
     }
+    Ok(Output::<D>::from_slice(bytes).clone())
 }
 
-fn ood_constraint_evaluation<A: Air>(
-    composition_coefficients: &[(A::Fq, A::Fq)],
-    challenges: &Challenges<A::Fq>,
-    hints: &Hints<A::Fq>,
-    trace_ood_eval_map: &BTreeMap<(usize, isize), A::Fq>,
-    air: &A,
-    x: A::Fq,
-) -> A::Fq {
-    let mut result = A::Fq::zero();
-    let trace_degree = air.trace_len() - 1;
-    let composition_degree = air.composition_degree();
-
-    for (i, constraint) in air.constraints().iter().enumerate() {
-        let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
-        let evaluation_degree = numerator_degree - denominator_degree;
-        assert!(evaluation_degree <= composition_degree);
-        let degree_adjustment = (composition_degree - evaluation_degree) as u64;
+/// Checks options taken from a proof. They're chosen by the prover so they're
+/// checked before they're used to measure security or size domains.
+pub(crate) fn check_options(options: &ProofOptions) -> Result<(), VerificationError> {
+    use VerificationError::*;
+
+    if !(ProofOptions::MIN_NUM_QUERIES..=ProofOptions::MAX_NUM_QUERIES)
+        .contains(&options.num_queries)
+    {
+        return Err(InvalidNumQueries {
+            num_queries: options.num_queries,
+        });
+    }
+
+    // FRI needs a blowup factor of at least two for queries to add security
+    let lde_blowup_factor = options.lde_blowup_factor;
+    let fri_blowup_factor = options.fri_blowup_factor();
+    if !lde_blowup_factor.is_power_of_two()
+        || !(ProofOptions::MIN_BLOWUP_FACTOR..=ProofOptions::MAX_BLOWUP_FACTOR)
+            .contains(&lde_blowup_factor)
+        || fri_blowup_factor < 2
+    {
+        return Err(InvalidBlowupFactor {
+            lde_blowup_factor,
+            fri_blowup_factor,
+        });
+    }
+
+    if options.grinding_factor > ProofOptions::MAX_GRINDING_FACTOR {
+        return Err(GrindingFactorTooLarge {
+            grinding_factor: options.grinding_factor,
+        });
+    }
+
+    if !ProofOptions::FRI_FOLDING_FACTORS.contains(&options.fri_folding_factor) {
+        return Err(UnsupportedFriFoldingFactor {
+            fri_folding_factor: options.fri_folding_factor,
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn serialize(item: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(item.compressed_size());
+    item.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
 
+pub(crate) fn ood_constraint_evaluation<Fp, Fq>(
+    constraints: &[AlgebraicExpression<Fp, Fq>],
+    degree_adjustments: &[u64],
+    composition_coefficients: &[(Fq, Fq)],
+    challenges: &Challenges<Fq>,
+    hints: &Hints<Fq>,
+    periodic_values: &[Fq],
+    trace_ood_eval_map: &BTreeMap<(usize, isize), Fq>,
+    x: Fq,
+) -> Fq
+where
+    Fp: FftField,
+    Fq: StarkExtensionOf<Fp>,
+{
+    let mut result = Fq::zero();
+
+    for (i, constraint) in constraints.iter().enumerate() {
         let eval_result = constraint.eval(
             &FieldConstant::Fq(x),
             &|i| FieldConstant::Fq(hints[i]),
             &|i| FieldConstant::Fq(challenges[i]),
+            &|i| FieldConstant::Fq(periodic_values[i]),
             &|i, j| FieldConstant::Fq(*trace_ood_eval_map.get(&(i, j)).unwrap()),
         );
 
@@ -238,51 +706,72 @@ fn ood_constraint_evaluation<A: Air>(
         // TODO: proper errors
         // TODO: don't allow degree 0 constraints
         let (alpha, beta) = composition_coefficients[i];
-        result += eval_result * (alpha * x.pow([degree_adjustment]) + beta)
+        result += eval_result * (alpha * x.pow([degree_adjustments[i]]) + beta)
     }
 
     result
 }
 
-fn verify_positions<D: Digest>(
+/// Evaluates each periodic column of the AIR at the out-of-domain point
+pub(crate) fn periodic_ood_evals<A: Air + ?Sized>(air: &A, z: A::Fq) -> Vec<A::Fq> {
+    let trace_len = air.trace_len();
+    air.periodic_columns()
+        .iter()
+        .map(|column| column.evaluate(z, trace_len))
+        .collect()
+}
+
+pub(crate) fn verify_positions<D: Digest>(
     commitment: Output<D>,
     positions: &[usize],
     rows: &[&[impl CanonicalSerialize]],
-    proofs: Vec<MerkleProof>,
+    salts: Option<&[Salt]>,
+    proof: &MerkleProof,
+    num_leaves: usize,
 ) -> Result<(), MerkleTreeError> {
-    for ((position, proof), row) in positions.iter().zip(proofs).zip(rows) {
-        let proof = proof.parse::<D>();
-        let expected_leaf = &proof[0];
-        let mut row_bytes = Vec::with_capacity(row.compressed_size());
-        row.serialize_compressed(&mut row_bytes).unwrap();
-        let actual_leaf = D::new_with_prefix(&row_bytes).finalize();
-
-        if *expected_leaf != actual_leaf {
-            return Err(MerkleTreeError::InvalidProof);
-        }
-
-        MerkleTree::<D>::verify(&commitment, &proof, *position)?;
+    if matches!(salts, Some(salts) if salts.len() != positions.len()) {
+        return Err(MerkleTreeError::InvalidProof);
     }
 
-    Ok(())
+    let leaves = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row_bytes = Vec::with_capacity(row.compressed_size());
+            row.serialize_compressed(&mut row_bytes).unwrap();
+            let mut hasher = D::new_with_prefix(&row_bytes);
+            if let Some(salts) = salts {
+                hasher.update(salts[i]);
+            }
+            hasher.finalize()
+        })
+        .collect::<Vec<Output<D>>>();
+
+    let proof = proof.parse::<D>();
+    MerkleTree::<D>::verify_batch(&commitment, &proof, positions, &leaves, num_leaves)
 }
 
+/// Evaluates the DEEP composition polynomial at the queried positions of
+/// `lde_domain`. Its degree is adjusted by multiplying with `alpha + beta *
+/// x^degree_shift`.
 #[allow(clippy::too_many_arguments)]
-fn deep_composition_evaluations<A: Air>(
+pub(crate) fn deep_composition_evaluations<A: Air + ?Sized>(
     air: &A,
+    context: &VerifierContext<A>,
+    lde_domain: Radix2EvaluationDomain<A::Fp>,
     query_positions: &[usize],
     composition_coeffs: DeepCompositionCoeffs<A::Fq>,
     base_trace_rows: Vec<&[A::Fp]>,
     extension_trace_rows: Vec<&[A::Fq]>,
+    preprocessed_trace_rows: Vec<&[A::Fp]>,
     composition_trace_rows: Vec<&[A::Fq]>,
     z: A::Fq,
     execution_trace_ood_evals_map: BTreeMap<(usize, isize), A::Fq>,
-    composition_trace_ood_evals: Vec<A::Fq>,
+    composition_trace_ood_evals: &[A::Fq],
+    degree_shift: u64,
 ) -> Vec<A::Fq> {
-    let trace_domain = air.trace_domain();
-    let g = trace_domain.group_gen();
-    let g_inv = trace_domain.group_gen_inv();
-    let lde_domain = air.lde_domain();
+    let g = context.trace_domain.group_gen();
+    let g_inv = context.trace_domain.group_gen_inv();
     let xs = query_positions
         .iter()
         .map(|pos| lde_domain.element(*pos))
@@ -294,12 +783,15 @@ fn deep_composition_evaluations<A: Air>(
     let trace_info = air.trace_info();
     let base_columns_range = trace_info.base_columns_range();
     let extension_columns_range = trace_info.extension_columns_range();
+    let preprocessed_columns_range = trace_info.preprocessed_columns_range();
     for (i, (&x, eval)) in xs.iter().zip(&mut evals).enumerate() {
         for (j, ((column, offset), ood_eval)) in execution_trace_ood_evals_map.iter().enumerate() {
             let trace_value = if base_columns_range.contains(column) {
                 A::Fq::from(base_trace_rows[i][*column])
             } else if extension_columns_range.contains(column) {
                 extension_trace_rows[i][column - trace_info.num_base_columns]
+            } else if preprocessed_columns_range.contains(column) {
+                A::Fq::from(preprocessed_trace_rows[i][column - preprocessed_columns_range.start])
             } else {
                 panic!("column {column} does not exist");
             };
@@ -311,9 +803,10 @@ fn deep_composition_evaluations<A: Air>(
     }
 
     // add composition trace
-    let z_n = z.pow([air.ce_blowup_factor() as u64]);
-    for ((&x, row), eval) in xs.iter().zip(composition_trace_rows).zip(&mut evals) {
-        for (i, value) in row.iter().enumerate() {
+    let num_composition_trace_cols = context.ce_blowup_factor;
+    let z_n = z.pow([num_composition_trace_cols as u64]);
+    for ((&x, row), eval) in xs.iter().zip(&composition_trace_rows).zip(&mut evals) {
+        for (i, value) in row[..num_composition_trace_cols].iter().enumerate() {
             let alpha = composition_coeffs.composition_trace[i];
             let ood_eval = composition_trace_ood_evals[i];
             *eval += alpha * (*value - ood_eval) / (A::Fq::from(x) - z_n);
@@ -323,7 +816,14 @@ fn deep_composition_evaluations<A: Air>(
     // adjust degree
     let (alpha, beta) = composition_coeffs.degree;
     for (x, eval) in xs.iter().zip(&mut evals) {
-        *eval *= alpha + beta * x;
+        *eval *= alpha + beta * x.pow([degree_shift]);
+    }
+
+    // add the random polynomial that masks the DEEP composition polynomial
+    if air.options().zero_knowledge {
+        for (row, eval) in composition_trace_rows.iter().zip(&mut evals) {
+            *eval += row[num_composition_trace_cols];
+        }
     }
 
     evals
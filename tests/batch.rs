@@ -0,0 +1,62 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use common::MerklePathProver;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::merkle_path::MerklePathAir;
+use ministark::merkle_path::MerklePathInputs;
+use ministark::merkle_path::MerklePathTrace;
+use ministark::BatchVerifier;
+use ministark::Proof;
+use ministark::Prover;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+
+mod common;
+
+/// Proves the Merkle path of each leaf at the given indices
+fn gen_proofs(indices: &[usize]) -> Vec<(Proof<MerklePathAir>, MerklePathInputs)> {
+    let (leaves, tree) = common::merkle_tree(16);
+    let prover = MerklePathProver::new(common::options());
+
+    indices
+        .iter()
+        .map(|&index| {
+            let siblings = common::merkle_siblings(&tree, index);
+            let trace = MerklePathTrace::new(leaves[index], index as u64, &siblings);
+            let inputs = trace.inputs().clone();
+            let proof = pollster::block_on(prover.generate_proof(trace)).unwrap();
+            (proof, inputs)
+        })
+        .collect()
+}
+
+#[test]
+fn batch_of_valid_proofs_verifies() {
+    let proofs = gen_proofs(&[0, 5, 11]);
+    let mut verifier = BatchVerifier::new(VerificationPolicy::new(0));
+
+    let report = verifier.verify(&proofs);
+
+    assert_eq!(3, report.num_proofs);
+    assert!(report.is_valid());
+}
+
+#[test]
+fn batch_reports_each_invalid_proof() {
+    let mut proofs = gen_proofs(&[2, 7, 13]);
+    proofs[0].0.execution_trace_ood_evals[0] += Fp::one();
+    proofs[2].1 = proofs[1].1.clone();
+    let mut verifier = BatchVerifier::new(VerificationPolicy::new(0));
+
+    let report = verifier.verify(&proofs);
+
+    assert_eq!(2, report.failures.len());
+    assert!(matches!(
+        report.failures[0],
+        (0, VerificationError::InconsistentOodConstraintEvaluations)
+    ));
+    assert!(matches!(
+        report.failures[1],
+        (2, VerificationError::PublicInputsMismatch)
+    ));
+}
@@ -0,0 +1,347 @@
+//! Trace, AIR and prover shared by the integration tests. A test describes its
+//! columns and constraints with a [Fixture] and gets the rest from here.
+#![allow(dead_code)]
+use ark_ff::One;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use core::marker::PhantomData;
+use gpu_poly::allocator::PageAlignedAllocator;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use gpu_poly::GpuVec;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::format::MAGIC;
+use ministark::hints::Hints;
+use ministark::merkle::MerkleTree;
+use ministark::merkle_path::MerklePathAir;
+use ministark::merkle_path::MerklePathInputs;
+use ministark::merkle_path::MerklePathTrace;
+use ministark::periodic::PeriodicColumn;
+use ministark::prover::ProvingError;
+use ministark::rescue_prime;
+use ministark::rescue_prime::RescuePrime;
+use ministark::rescue_prime::DIGEST_SIZE;
+use ministark::trace::PreprocessedTrace;
+use ministark::utils;
+use ministark::Air;
+use ministark::Matrix;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark::Prover;
+use ministark::Trace;
+use ministark::TraceInfo;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+use sha2::Sha256;
+
+pub const TRACE_LEN: usize = 2048;
+
+/// Proof options used throughout the tests
+pub fn options() -> ProofOptions {
+    ProofOptions::new(32, 8, 0, 4, 16)
+}
+
+/// Columns, public inputs and constraints of a test AIR over [Fp]
+pub trait Fixture: Sized {
+    type PublicInputs: CanonicalSerialize + CanonicalDeserialize + Clone;
+
+    const NUM_BASE_COLUMNS: usize;
+    const NUM_EXTENSION_COLUMNS: usize = 0;
+    const NUM_PREPROCESSED_COLUMNS: usize = 0;
+    const META: &'static [u8] = &[];
+    /// Overrides the constraint evaluation blowup factor derived from the
+    /// constraint degrees
+    const CE_BLOWUP_FACTOR: Option<usize> = None;
+
+    fn pub_inputs(base_columns: &Matrix<Fp>) -> Self::PublicInputs;
+
+    fn constraints(
+        trace_len: usize,
+        pub_inputs: &Self::PublicInputs,
+    ) -> Vec<AlgebraicExpression<Fp>>;
+
+    fn hints(
+        _trace_len: usize,
+        _pub_inputs: &Self::PublicInputs,
+        _challenges: &Challenges<Fp>,
+    ) -> Hints<Fp> {
+        Hints::default()
+    }
+
+    fn periodic_columns() -> Vec<PeriodicColumn<Fp>> {
+        Vec::new()
+    }
+
+    fn build_extension_columns(
+        _base_columns: &Matrix<Fp>,
+        _challenges: &Challenges<Fp>,
+    ) -> Option<Matrix<Fp>> {
+        None
+    }
+
+    /// Columns the prover commits to ahead of time
+    fn preprocessed_columns() -> Option<Matrix<Fp>> {
+        None
+    }
+}
+
+pub struct TestTrace<F>(pub Matrix<Fp>, PhantomData<F>);
+
+impl<F> TestTrace<F> {
+    pub fn new(base_columns: Matrix<Fp>) -> Self {
+        TestTrace(base_columns, PhantomData)
+    }
+}
+
+impl<F: Fixture> Trace for TestTrace<F> {
+    type Fp = Fp;
+    type Fq = Fp;
+
+    const NUM_BASE_COLUMNS: usize = F::NUM_BASE_COLUMNS;
+    const NUM_EXTENSION_COLUMNS: usize = F::NUM_EXTENSION_COLUMNS;
+    const NUM_PREPROCESSED_COLUMNS: usize = F::NUM_PREPROCESSED_COLUMNS;
+
+    fn base_columns(&self) -> &Matrix<Fp> {
+        &self.0
+    }
+
+    fn meta(&self) -> Option<&[u8]> {
+        (!F::META.is_empty()).then_some(F::META)
+    }
+
+    fn build_extension_columns(&self, challenges: &Challenges<Fp>) -> Option<Matrix<Fp>> {
+        F::build_extension_columns(&self.0, challenges)
+    }
+}
+
+pub struct TestAir<F: Fixture> {
+    options: ProofOptions,
+    trace_info: TraceInfo,
+    pub_inputs: F::PublicInputs,
+}
+
+impl<F: Fixture> Air for TestAir<F> {
+    type Fp = Fp;
+    type Fq = Fp;
+    type PublicInputs = F::PublicInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: F::PublicInputs, options: ProofOptions) -> Self {
+        TestAir {
+            options,
+            trace_info,
+            pub_inputs,
+        }
+    }
+
+    fn pub_inputs(&self) -> &F::PublicInputs {
+        &self.pub_inputs
+    }
+
+    fn trace_info(&self) -> &TraceInfo {
+        &self.trace_info
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn constraints(&self) -> Vec<AlgebraicExpression<Fp>> {
+        F::constraints(self.trace_len(), &self.pub_inputs)
+    }
+
+    fn get_hints(&self, challenges: &Challenges<Fp>) -> Hints<Fp> {
+        F::hints(self.trace_len(), &self.pub_inputs, challenges)
+    }
+
+    fn periodic_columns(&self) -> Vec<PeriodicColumn<Fp>> {
+        F::periodic_columns()
+    }
+
+    fn ce_blowup_factor(&self) -> usize {
+        F::CE_BLOWUP_FACTOR.unwrap_or_else(|| {
+            let trace_degree = self.trace_degree();
+            let max_degree = self.constraints().iter().map(|constraint| {
+                let (numerator_degree, denominator_degree) = constraint.degree(trace_degree);
+                numerator_degree - denominator_degree
+            });
+            utils::ce_blowup_factor(max_degree.max().unwrap_or(0), self.trace_len())
+        })
+    }
+}
+
+pub struct TestProver<F>(
+    ProofOptions,
+    pub Option<PreprocessedTrace<Fp, Sha256>>,
+    PhantomData<F>,
+);
+
+impl<F: Fixture> Prover for TestProver<F> {
+    type Fp = Fp;
+    type Fq = Fp;
+    type Air = TestAir<F>;
+    type Trace = TestTrace<F>;
+    type Digest = Sha256;
+
+    fn new(options: ProofOptions) -> Self {
+        let preprocessed_trace =
+            F::preprocessed_columns().map(|columns| PreprocessedTrace::new(columns, options));
+        TestProver(options, preprocessed_trace, PhantomData)
+    }
+
+    fn options(&self) -> ProofOptions {
+        self.0
+    }
+
+    fn get_pub_inputs(&self, trace: &TestTrace<F>) -> F::PublicInputs {
+        F::pub_inputs(&trace.0)
+    }
+
+    fn preprocessed_trace(&self) -> Option<&PreprocessedTrace<Fp, Sha256>> {
+        self.1.as_ref()
+    }
+}
+
+/// Proves the base columns of a fixture with [options]
+pub fn prove<F: Fixture>(base_columns: Matrix<Fp>) -> Result<Proof<TestAir<F>>, ProvingError> {
+    prove_with_options(base_columns, options())
+}
+
+pub fn prove_with_options<F: Fixture>(
+    base_columns: Matrix<Fp>,
+    options: ProofOptions,
+) -> Result<Proof<TestAir<F>>, ProvingError> {
+    let prover = TestProver::<F>::new(options);
+    pollster::block_on(prover.generate_proof(TestTrace::new(base_columns)))
+}
+
+/// Splits a proof serialized with [Proof::to_bytes] into its sections
+pub fn sections(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut bytes = &bytes[MAGIC.len() + 2..];
+    let mut sections = Vec::new();
+    while !bytes.is_empty() {
+        let (len, rest) = bytes.split_at(8);
+        let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+        let (section, rest) = rest.split_at(len);
+        sections.push(section);
+        bytes = rest;
+    }
+    sections
+}
+
+/// Proves the Merkle path traces of the library's [MerklePathAir]
+pub struct MerklePathProver(ProofOptions);
+
+impl Prover for MerklePathProver {
+    type Fp = Fp;
+    type Fq = Fp;
+    type Air = MerklePathAir;
+    type Trace = MerklePathTrace;
+    type Digest = Sha256;
+
+    fn new(options: ProofOptions) -> Self {
+        MerklePathProver(options)
+    }
+
+    fn options(&self) -> ProofOptions {
+        self.0
+    }
+
+    fn get_pub_inputs(&self, trace: &MerklePathTrace) -> MerklePathInputs {
+        trace.inputs().clone()
+    }
+}
+
+/// Rescue-Prime Merkle tree over the hashes of `0..num_leaves`
+pub fn merkle_tree(num_leaves: u64) -> (Vec<[Fp; DIGEST_SIZE]>, MerkleTree<RescuePrime>) {
+    let leaves = (0..num_leaves)
+        .map(|i| rescue_prime::hash_elements(&[Fp::from(i)]))
+        .collect::<Vec<[Fp; DIGEST_SIZE]>>();
+    let tree = MerkleTree::new(
+        leaves
+            .iter()
+            .map(rescue_prime::elements_to_digest)
+            .collect(),
+    );
+    (leaves, tree.unwrap())
+}
+
+/// Sibling of each node on the path from the leaf at `index` to the root
+pub fn merkle_siblings(tree: &MerkleTree<RescuePrime>, index: usize) -> Vec<[Fp; DIGEST_SIZE]> {
+    // the first node of the path is the leaf itself
+    let path = tree.prove(index).unwrap().parse::<RescuePrime>();
+    path[1..]
+        .iter()
+        .map(|node| rescue_prime::digest_to_elements(node).unwrap())
+        .collect()
+}
+
+/// Asserts the base columns don't satisfy `constraint` at `row`. Debug builds
+/// check every constraint while proving so the prover rejects the trace,
+/// otherwise the verifier rejects the proof.
+pub fn assert_unsatisfied<F: Fixture>(base_columns: Matrix<Fp>, constraint: usize, row: usize) {
+    let result = prove::<F>(base_columns);
+    if cfg!(debug_assertions) {
+        assert!(matches!(
+            result,
+            Err(ProvingError::UnsatisfiedConstraint { constraint: c, row: r })
+                if c == constraint && r == row
+        ));
+    } else {
+        let proof = result.unwrap();
+        assert!(matches!(
+            proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+            Err(VerificationError::InconsistentOodConstraintEvaluations)
+        ));
+    }
+}
+
+/// Collects values into a page aligned column
+pub fn column(values: impl IntoIterator<Item = u64>) -> GpuVec<Fp> {
+    let mut column = Vec::new_in(PageAlignedAllocator);
+    column.extend(values.into_iter().map(Fp::from));
+    column
+}
+
+/// Fibonacci sequence spread over two columns starting at `(1, 1)`. The
+/// public input is the last value of the second column.
+pub struct Fib;
+
+impl Fib {
+    pub const A: usize = 0;
+    pub const B: usize = 1;
+
+    pub fn columns() -> Matrix<Fp> {
+        let mut a = Vec::new_in(PageAlignedAllocator);
+        let mut b = Vec::new_in(PageAlignedAllocator);
+        let (mut x, mut y) = (Fp::one(), Fp::one());
+        for _ in 0..TRACE_LEN {
+            a.push(x);
+            b.push(y);
+            (x, y) = (y, x + y);
+        }
+        Matrix::new(vec![a, b])
+    }
+}
+
+impl Fixture for Fib {
+    type PublicInputs = Fp;
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(base_columns: &Matrix<Fp>) -> Fp {
+        *base_columns[Fib::B].last().unwrap()
+    }
+
+    fn constraints(n: usize, result: &Fp) -> Vec<AlgebraicExpression<Fp>> {
+        let one = FieldConstant::Fp(Fp::one());
+        vec![
+            (Fib::A.curr() - one).on_first_row(n),
+            (Fib::B.curr() - one).on_first_row(n),
+            (Fib::A.next() - Fib::B.curr()).on_transition(n),
+            (Fib::B.next() - Fib::A.curr() - Fib::B.curr()).on_transition(n),
+            (Fib::B.curr() - FieldConstant::Fp(*result)).on_last_row(n),
+        ]
+    }
+}
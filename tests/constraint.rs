@@ -14,7 +14,6 @@ use ark_std::rand::Rng;
 use core::marker::PhantomData;
 use gpu_poly::allocator::PageAlignedAllocator;
 use gpu_poly::fields::p18446744069414584321::Fp;
-use gpu_poly::GpuFftField;
 use gpu_poly::GpuField;
 use ministark::constraints::AlgebraicExpression;
 use ministark::constraints::ExecutionTraceColumn;
@@ -29,7 +28,7 @@ use ministark::TraceInfo;
 
 struct TestAir<Fp, Fq = Fp>(TraceInfo, ProofOptions, PhantomData<(Fp, Fq)>);
 
-impl<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>> Air for TestAir<Fp, Fq> {
+impl<Fp: FftField, Fq: StarkExtensionOf<Fp>> Air for TestAir<Fp, Fq> {
     type Fp = Fp;
     type Fq = Fq;
     type PublicInputs = ();
@@ -77,6 +76,42 @@ fn expressions_are_unequal() {
     assert_ne!(left.evaluation_hash(x), right.evaluation_hash(x));
 }
 
+#[test]
+fn simplify_folds_constants_and_cancels_factors() {
+    use AlgebraicExpression::*;
+    let one = FieldConstant::Fp(Fp::one());
+    let zero = FieldConstant::Fp(Fp::zero());
+    let constraint: AlgebraicExpression<Fp> =
+        (0.curr() * one + zero) * 1.curr() * X.pow(3) / (1.curr() * X) - 0.curr() * X.pow(2);
+
+    let x: AlgebraicExpression<Fp> = X;
+
+    assert_eq!(Constant(zero), constraint.simplify());
+    assert_eq!(x.pow(2), (&x * x.pow(3) / x.pow(2)).simplify());
+    assert_eq!(
+        0.curr() - 1.curr(),
+        (0.curr() * 1.challenge().pow(0) - 1.curr() * one).simplify()
+    );
+}
+
+#[test]
+fn simplified_expressions_are_equal() {
+    use AlgebraicExpression::*;
+    let mut rng = ark_std::test_rng();
+    let x = Fp::rand(&mut rng);
+    let two = FieldConstant::Fp(Fp::from(2u8));
+    let constraint: AlgebraicExpression<Fp> = ((0.next() - 0.curr() * two) * (X - two) / (X - two)
+        + 1.challenge() * 0.curr()
+        - 0.curr() * 1.challenge())
+    .on_transition(2048);
+
+    let simplified = constraint.simplify();
+
+    assert_eq!(constraint.evaluation_hash(x), simplified.evaluation_hash(x));
+    let (numerator_degree, denominator_degree) = simplified.degree(2047);
+    assert!(numerator_degree - denominator_degree <= 2047);
+}
+
 #[test]
 fn constraint_with_challenges() {
     // TODO: hints
@@ -89,6 +124,7 @@ fn constraint_with_challenges() {
             &FieldConstant::Fp(Fp::one()),
             &|_| unreachable!(),
             &|i| FieldConstant::Fp(challenges[i]),
+            &|_| unreachable!(),
             &|i, j| {
                 assert_eq!(0, j);
                 FieldConstant::Fp(col_values[i])
@@ -154,6 +190,7 @@ fn constraint_multiplication() {
     let x = FieldConstant::Fp(Fp::one());
     let h = &|_| unreachable!();
     let c = &|_| unreachable!();
+    let p = &|_| unreachable!();
     let t = |val: FieldConstant<Fp, Fp>| {
         move |i, j| {
             assert_eq!(0, i, "for value {val}");
@@ -162,21 +199,21 @@ fn constraint_multiplication() {
         }
     };
 
-    assert!(!between_0_and_10.eval(&x, h, c, &t(-two)).is_zero());
-    assert!(!between_0_and_10.eval(&x, h, c, &t(-one)).is_zero());
-    assert!(!between_0_and_10.eval(&x, h, c, &t(zero)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(one)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(two)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(three)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(four)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(five)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(six)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(seven)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(eight)).is_zero());
-    assert!(between_0_and_10.eval(&x, h, c, &t(nine)).is_zero());
-    assert!(!between_0_and_10.eval(&x, h, c, &t(ten)).is_zero());
-    assert!(!between_0_and_10.eval(&x, h, c, &t(eleven)).is_zero());
-    assert!(!between_0_and_10.eval(&x, h, c, &t(twelve)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(-two)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(-one)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(zero)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(one)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(two)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(three)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(four)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(five)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(six)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(seven)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(eight)).is_zero());
+    assert!(between_0_and_10.eval(&x, h, c, p, &t(nine)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(ten)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(eleven)).is_zero());
+    assert!(!between_0_and_10.eval(&x, h, c, p, &t(twelve)).is_zero());
 }
 
 #[test]
@@ -408,7 +445,7 @@ fn assert_valid_over_transition_domain<F: GpuField + Field>(
 }
 
 /// TODO: consider merging with ConstraintComposer::evaluate_constraint_cpu
-fn evaluate_symbolic<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
+fn evaluate_symbolic<Fp: FftField, Fq: StarkExtensionOf<Fp>>(
     lde_domain: Radix2EvaluationDomain<Fp>,
     blowup_factor: usize,
     hints: &[Fq],
@@ -427,6 +464,7 @@ fn evaluate_symbolic<Fp: GpuFftField + FftField, Fq: StarkExtensionOf<Fp>>(
             &FieldConstant::Fp(x),
             &|h| FieldConstant::Fq(hints[h]),
             &|c| FieldConstant::Fq(challenges[c]),
+            &|_| unreachable!(),
             &|col_idx, offset| {
                 let pos = (i as isize + blowup_factor * offset).rem_euclid(n as isize) as usize;
                 let column = &lde_matrix[col_idx];
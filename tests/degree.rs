@@ -0,0 +1,160 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use gpu_poly::allocator::PageAlignedAllocator;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::degree;
+use ministark::degree::ConstraintDegree;
+use ministark::hints::Hints;
+use ministark::utils;
+use ministark::Air;
+use ministark::AirWarning;
+use ministark::GpuAir;
+use ministark::Matrix;
+use ministark::ProofOptions;
+use ministark::TraceInfo;
+
+const TRACE_LEN: usize = 2048;
+
+const COUNTER: usize = 0;
+const SQUARE: usize = 1;
+
+struct CounterAir {
+    options: ProofOptions,
+    trace_info: TraceInfo,
+}
+
+impl Air for CounterAir {
+    type Fp = Fp;
+    type Fq = Fp;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _: (), options: ProofOptions) -> Self {
+        CounterAir {
+            options,
+            trace_info,
+        }
+    }
+
+    fn pub_inputs(&self) -> &() {
+        &()
+    }
+
+    fn trace_info(&self) -> &TraceInfo {
+        &self.trace_info
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn constraints(&self) -> Vec<AlgebraicExpression<Fp>> {
+        let n = self.trace_len();
+        let one = FieldConstant::Fp(Fp::one());
+        let all_rows = (0..n).collect::<Vec<usize>>();
+        vec![
+            (COUNTER.next() - COUNTER.curr() - one).on_transition(n),
+            (SQUARE.curr() - COUNTER.curr() * COUNTER.curr()).on_rows(n, &all_rows),
+            // the cubes cancel but the symbolic degree doesn't know that
+            (COUNTER.curr().pow(3) - COUNTER.curr().pow(3) + COUNTER.next() - COUNTER.curr() - one)
+                .on_transition(n),
+        ]
+    }
+}
+
+fn counter_trace() -> Matrix<Fp> {
+    let mut counter_column = Vec::new_in(PageAlignedAllocator);
+    let mut square_column = Vec::new_in(PageAlignedAllocator);
+    for i in 0..TRACE_LEN as u64 {
+        counter_column.push(Fp::from(i));
+        square_column.push(Fp::from(i * i));
+    }
+    Matrix::new(vec![counter_column, square_column])
+}
+
+#[test]
+fn exact_degrees_detect_overestimated_constraints() {
+    let n = TRACE_LEN;
+    let trace_info = TraceInfo::new(2, 0, n, None);
+    let air = CounterAir::new(trace_info, (), ProofOptions::new(32, 8, 0, 4, 16));
+
+    let degrees = degree::analyze(
+        &air,
+        &Challenges::default(),
+        &Hints::default(),
+        &counter_trace(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        degrees,
+        [
+            ConstraintDegree {
+                symbolic: 0,
+                exact: 0
+            },
+            ConstraintDegree {
+                symbolic: n - 2,
+                exact: n - 2
+            },
+            ConstraintDegree {
+                symbolic: 2 * n - 2,
+                exact: 0
+            },
+        ]
+    );
+    assert!(!degrees[1].is_overestimated());
+    assert!(degrees[2].is_overestimated());
+    assert!(!degrees[1].wastes_blowup(n));
+    assert!(degrees[2].wastes_blowup(n));
+}
+
+#[test]
+fn warnings_report_unused_challenges_and_wasted_blowup() {
+    let n = TRACE_LEN;
+    let trace_info = TraceInfo::new(2, 0, n, None);
+    let air = CounterAir::new(trace_info, (), ProofOptions::new(32, 8, 0, 4, 16));
+
+    let warnings = air
+        .warnings(
+            &Challenges::from(vec![Fp::one()]),
+            &Hints::default(),
+            &counter_trace(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        warnings,
+        [
+            AirWarning::UnusedChallenge { challenge: 0 },
+            AirWarning::OverestimatedDegree {
+                constraint: 2,
+                degree: ConstraintDegree {
+                    symbolic: 2 * n - 2,
+                    exact: 0
+                }
+            },
+            AirWarning::WastedBlowup {
+                symbolic: 2,
+                exact: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn ce_blowup_factor_fits_degree() {
+    let n = TRACE_LEN;
+
+    assert_eq!(utils::ce_blowup_factor(0, n), 1);
+    assert_eq!(utils::ce_blowup_factor(n - 1, n), 1);
+    assert_eq!(utils::ce_blowup_factor(n, n), 2);
+    assert_eq!(utils::ce_blowup_factor(2 * n, n), 4);
+}
@@ -0,0 +1,82 @@
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::Hint;
+use ministark::constraints::VerifierChallenge;
+use ministark::Challenge;
+use ministark::Column;
+use ministark::Hint;
+
+#[derive(Clone, Copy, Column)]
+enum BaseColumn {
+    Clk,
+    Value,
+    Dummy,
+}
+
+#[derive(Clone, Copy, Column)]
+#[column(offset = BaseColumn::LAST_TRACE_COL_INDEX + 1)]
+enum ExtensionColumn {
+    Permutation,
+    Evaluation,
+}
+
+#[derive(Challenge)]
+enum PermutationChallenge {
+    Alpha,
+    Gamma,
+}
+
+#[derive(Challenge)]
+#[challenge(offset = PermutationChallenge::NUM_CHALLENGES)]
+enum EvaluationChallenge {
+    Eta,
+}
+
+#[derive(Hint)]
+enum TerminalHint {
+    Input,
+    Output,
+}
+
+#[test]
+fn columns_are_indexed_after_their_offset() {
+    assert_eq!(BaseColumn::NUM_TRACE_COLUMNS, 3);
+    assert_eq!(BaseColumn::FIRST_TRACE_COL_INDEX, 0);
+    assert_eq!(BaseColumn::LAST_TRACE_COL_INDEX, 2);
+    assert_eq!(BaseColumn::Clk.index(), 0);
+    assert_eq!(BaseColumn::Value.index(), 1);
+    assert_eq!(ExtensionColumn::NUM_TRACE_COLUMNS, 2);
+    assert_eq!(ExtensionColumn::FIRST_TRACE_COL_INDEX, 3);
+    assert_eq!(ExtensionColumn::LAST_TRACE_COL_INDEX, 4);
+    assert_eq!(ExtensionColumn::Permutation.index(), 3);
+    assert_eq!(ExtensionColumn::Evaluation.index(), 4);
+    assert_eq!(
+        ExtensionColumn::VARIANTS.map(|column| column.index()),
+        [3, 4]
+    );
+}
+
+#[test]
+fn challenges_and_hints_are_indexed_in_order() {
+    assert_eq!(PermutationChallenge::NUM_CHALLENGES, 2);
+    assert_eq!(PermutationChallenge::Alpha.index(), 0);
+    assert_eq!(PermutationChallenge::Gamma.index(), 1);
+    assert_eq!(EvaluationChallenge::NUM_CHALLENGES, 1);
+    assert_eq!(EvaluationChallenge::Eta.index(), 2);
+    assert_eq!(TerminalHint::NUM_HINTS, 2);
+    assert_eq!(TerminalHint::Input.index(), 0);
+    assert_eq!(TerminalHint::Output.index(), 1);
+}
+
+#[test]
+fn display_names_include_the_enum() {
+    assert_eq!(BaseColumn::Dummy.to_string(), "BaseColumn::Dummy");
+    assert_eq!(
+        ExtensionColumn::Evaluation.to_string(),
+        "ExtensionColumn::Evaluation"
+    );
+    assert_eq!(
+        PermutationChallenge::Gamma.to_string(),
+        "PermutationChallenge::Gamma"
+    );
+    assert_eq!(TerminalHint::Output.to_string(), "TerminalHint::Output");
+}
@@ -0,0 +1,79 @@
+use ark_ff::One;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::constraints::Hint;
+use ministark::constraints::VerifierChallenge;
+use ministark::display::Names;
+use ministark::Challenge;
+use ministark::Column;
+use ministark::Hint;
+
+const TRACE_LEN: usize = 8;
+
+#[derive(Clone, Copy, Column)]
+enum Processor {
+    Ip,
+    Mp,
+}
+
+#[derive(Clone, Copy, Challenge)]
+enum Permutation {
+    Alpha,
+}
+
+#[derive(Clone, Copy, Hint)]
+enum Terminal {
+    Output,
+}
+
+fn names() -> Names {
+    Names::new()
+        .columns(Processor::VARIANTS)
+        .challenges(Permutation::VARIANTS)
+        .hints(Terminal::VARIANTS)
+}
+
+#[test]
+fn text_uses_names_and_minimal_parentheses() {
+    use AlgebraicExpression::X;
+    let one = FieldConstant::Fp(Fp::one());
+    let names = names();
+    let increment: AlgebraicExpression<Fp> = Processor::Ip.next() - Processor::Ip.curr() - one;
+    let rational: AlgebraicExpression<Fp> =
+        ((Processor::Mp.curr() + Permutation::Alpha.challenge()) * Processor::Ip.curr().pow(2)
+            / (X - one))
+            .on_transition(TRACE_LEN);
+    let nested: AlgebraicExpression<Fp> =
+        Terminal::Output.hint() - (3usize.offset(-1) - Processor::Mp.curr());
+
+    assert_eq!(
+        increment.display_with(&names).to_string(),
+        "Processor::Ip' - Processor::Ip - 1"
+    );
+    assert_eq!(
+        rational.display_with(&names).to_string(),
+        "(Processor::Mp + Permutation::Alpha) * Processor::Ip^2 / ((x - 1) * Z(rows 0..7))"
+    );
+    assert_eq!(
+        nested.display_with(&names).to_string(),
+        "Terminal::Output - trace[3][-1] + Processor::Mp"
+    );
+}
+
+#[test]
+fn latex_uses_fractions() {
+    let one = FieldConstant::Fp(Fp::one());
+    let names = names();
+    let constraint: AlgebraicExpression<Fp> =
+        (Processor::Ip.next() - Processor::Ip.curr() - one) / Processor::Mp.curr().pow(2);
+
+    assert_eq!(
+        constraint.latex_with(&names).to_string(),
+        concat!(
+            "\\frac{\\texttt{Processor::Ip}' - \\texttt{Processor::Ip} - 1}",
+            "{\\texttt{Processor::Mp}^{2}}"
+        )
+    );
+}
@@ -0,0 +1,196 @@
+#![feature(allocator_api)]
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use common::Fib;
+use common::TestAir;
+use ministark::format::ProofFormatError;
+use ministark::format::ProofHeader;
+use ministark::format::MAGIC;
+use ministark::format::VERSION;
+use ministark::rescue_prime::RescuePrime;
+use ministark::Proof;
+use ministark::VerificationPolicy;
+use sha2::Sha256;
+
+mod common;
+
+fn header() -> ProofHeader {
+    ProofHeader {
+        field_modulus: vec![1, 0, 0, 0, 255, 255, 255, 255],
+        extension_degree: 3,
+        hash_fn: "sha256".to_string(),
+        air: "brainfuck".to_string(),
+    }
+}
+
+fn encode(version: u16, header: &ProofHeader) -> Vec<u8> {
+    let mut section = Vec::new();
+    header.serialize_compressed(&mut section).unwrap();
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.extend_from_slice(&(section.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&section);
+    bytes
+}
+
+#[test]
+fn header_round_trips() {
+    let bytes = encode(VERSION, &header());
+
+    assert_eq!(header(), ProofHeader::read(&bytes).unwrap());
+}
+
+#[test]
+fn rejects_invalid_magic() {
+    let mut bytes = encode(VERSION, &header());
+    bytes[0] ^= 1;
+
+    assert!(matches!(
+        ProofHeader::read(&bytes),
+        Err(ProofFormatError::InvalidMagic)
+    ));
+}
+
+#[test]
+fn rejects_unsupported_version() {
+    let bytes = encode(VERSION + 1, &header());
+
+    assert!(matches!(
+        ProofHeader::read(&bytes),
+        Err(ProofFormatError::UnsupportedVersion { version }) if version == VERSION + 1
+    ));
+}
+
+#[test]
+fn rejects_truncated_header() {
+    let bytes = encode(VERSION, &header());
+
+    for len in 0..bytes.len() {
+        assert!(matches!(
+            ProofHeader::read(&bytes[..len]),
+            Err(ProofFormatError::Truncated { .. })
+        ));
+    }
+}
+
+fn proof_bytes() -> Vec<u8> {
+    common::prove::<Fib>(Fib::columns()).unwrap().to_bytes()
+}
+
+#[test]
+fn proof_round_trips() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let bytes = proof.to_bytes();
+
+    let decoded = Proof::<TestAir<Fib>>::from_bytes(&bytes).unwrap();
+
+    assert_eq!(bytes, decoded.to_bytes());
+    decoded
+        .verify(&proof.public_inputs, &VerificationPolicy::new(0))
+        .unwrap();
+}
+
+#[test]
+fn rejects_truncated_section() {
+    let bytes = proof_bytes();
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ProofFormatError::Truncated { section: "queries" })
+    ));
+}
+
+#[test]
+fn rejects_trailing_bytes() {
+    let mut bytes = proof_bytes();
+    bytes.extend_from_slice(&[0; 3]);
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_bytes(&bytes),
+        Err(ProofFormatError::TrailingBytes { num_bytes: 3 })
+    ));
+}
+
+/// Contents of each length prefixed section of a serialized proof
+#[test]
+fn proof_has_a_section_for_each_part() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let bytes = proof.to_bytes();
+
+    let sections = common::sections(&bytes);
+
+    assert_eq!(8, sections.len());
+    let mut header = sections[0];
+    assert_eq!(
+        ProofHeader::new::<TestAir<Fib>, Sha256>(),
+        ProofHeader::deserialize_compressed(&mut header).unwrap()
+    );
+    assert_eq!(proof.options.compressed_size(), sections[1].len());
+    assert_eq!(proof.trace_info.compressed_size(), sections[2].len());
+    // a single Goldilocks element
+    assert_eq!(8, sections[3].len());
+}
+
+#[test]
+fn rejects_malformed_section() {
+    let mut bytes = proof_bytes();
+    // contents of the commitments section start after the four sections before it
+    let preceding = common::sections(&bytes)[..4]
+        .iter()
+        .map(|s| 8 + s.len())
+        .sum::<usize>();
+    let commitments = MAGIC.len() + 2 + preceding + 8;
+    // the option tag of the extension trace commitment follows the length
+    // prefixed 32 byte base trace commitment
+    bytes[commitments + 8 + 32] = 2;
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_bytes(&bytes),
+        Err(ProofFormatError::MalformedSection {
+            section: "commitments"
+        })
+    ));
+}
+
+#[test]
+fn rejects_proof_with_another_hash_function() {
+    assert!(matches!(
+        Proof::<TestAir<Fib>, RescuePrime>::from_bytes(&proof_bytes()),
+        Err(ProofFormatError::HashFnMismatch {
+            expected: "rescue-prime-goldilocks",
+            actual
+        }) if actual == "sha256"
+    ));
+}
+
+#[test]
+fn rejects_header_of_another_proof() {
+    let expected = ProofHeader::new::<TestAir<Fib>, Sha256>();
+    let check = |header: ProofHeader| header.check::<TestAir<Fib>, Sha256>();
+
+    let mut header = expected.clone();
+    header.field_modulus[0] ^= 1;
+    assert!(matches!(
+        check(header),
+        Err(ProofFormatError::FieldMismatch)
+    ));
+
+    let mut header = expected.clone();
+    header.extension_degree = 3;
+    assert!(matches!(
+        check(header),
+        Err(ProofFormatError::ExtensionDegreeMismatch {
+            expected: 1,
+            actual: 3
+        })
+    ));
+
+    let mut header = expected.clone();
+    header.air = "brainfuck".to_string();
+    assert!(matches!(
+        check(header),
+        Err(ProofFormatError::AirMismatch { actual, .. }) if actual == "brainfuck"
+    ));
+
+    assert!(check(expected).is_ok());
+}
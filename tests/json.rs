@@ -0,0 +1,136 @@
+#![feature(allocator_api)]
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use common::Fib;
+use common::TestAir;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use gpu_poly::fields::p18446744069414584321::Fq3;
+use ministark::json::field_from_json;
+use ministark::json::field_to_json;
+use ministark::json::hex_from_json;
+use ministark::json::hex_to_json;
+use ministark::json::JsonError;
+use ministark::json::Value;
+use ministark::merkle::MerkleProof;
+use ministark::merkle::MerkleTree;
+use ministark::Proof;
+use sha2::Digest;
+use sha2::Sha256;
+
+mod common;
+
+#[test]
+fn field_elements_are_decimal() {
+    let element = Fp::from(18446744069414584320u64);
+
+    assert_eq!(
+        Value::String("18446744069414584320".to_string()),
+        field_to_json(&element)
+    );
+}
+
+#[test]
+fn field_elements_round_trip() {
+    let mut rng = ark_std::test_rng();
+    let fp = Fp::rand(&mut rng);
+    let fq3 = Fq3::rand(&mut rng);
+
+    assert_eq!(fp, field_from_json(&field_to_json(&fp), "fp").unwrap());
+    assert_eq!(fq3, field_from_json(&field_to_json(&fq3), "fq3").unwrap());
+}
+
+#[test]
+fn non_canonical_field_elements_are_rejected() {
+    let modulus = Value::String("18446744069414584321".to_string());
+    let leading_zero = Value::String("01".to_string());
+    let empty = Value::String(String::new());
+
+    for value in [modulus, leading_zero, empty] {
+        assert!(matches!(
+            field_from_json::<Fp>(&value, "fp"),
+            Err(JsonError::InvalidValue { field: "fp" })
+        ));
+    }
+}
+
+#[test]
+fn hex_round_trips() {
+    let bytes = (0..=255).collect::<Vec<u8>>();
+
+    assert_eq!(bytes, hex_from_json(&hex_to_json(&bytes), "bytes").unwrap());
+    assert!(hex_from_json(&Value::String("abc".to_string()), "bytes").is_err());
+    assert!(hex_from_json(&Value::String("zz".to_string()), "bytes").is_err());
+}
+
+#[test]
+fn merkle_proof_round_trips_with_binary_encoding() {
+    let leaves = (0..16u32)
+        .map(|v| Sha256::new_with_prefix(v.to_le_bytes()).finalize())
+        .collect();
+    let tree = MerkleTree::<Sha256>::new(leaves).unwrap();
+    let proof = tree.prove_batch(&[1, 6, 7]).unwrap();
+    let mut expected_bytes = Vec::new();
+    proof.serialize_compressed(&mut expected_bytes).unwrap();
+
+    let decoded = MerkleProof::from_json(&proof.to_json()).unwrap();
+    let mut actual_bytes = Vec::new();
+    decoded.serialize_compressed(&mut actual_bytes).unwrap();
+
+    assert_eq!(expected_bytes, actual_bytes);
+}
+
+#[test]
+fn proof_round_trips_with_binary_encoding() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let mut expected_bytes = Vec::new();
+    proof.serialize_compressed(&mut expected_bytes).unwrap();
+
+    let json = proof.to_json().to_string();
+    let decoded = Proof::<TestAir<Fib>>::from_json(&json.parse::<Value>().unwrap()).unwrap();
+    let mut actual_bytes = Vec::new();
+    decoded.serialize_compressed(&mut actual_bytes).unwrap();
+
+    assert_eq!(expected_bytes, actual_bytes);
+}
+
+fn proof_json() -> Value {
+    common::prove::<Fib>(Fib::columns()).unwrap().to_json()
+}
+
+#[test]
+fn proof_without_fri_proof_is_rejected() {
+    let mut json = proof_json();
+    json.as_object_mut().unwrap().remove("fri_proof");
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_json(&json),
+        Err(JsonError::MissingField { field: "fri_proof" })
+    ));
+}
+
+#[test]
+fn proof_with_invalid_commitment_is_rejected() {
+    let mut json = proof_json();
+    json["commitments"]["base_trace"] = Value::String("zz".to_string());
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_json(&json),
+        Err(JsonError::InvalidValue {
+            field: "base_trace"
+        })
+    ));
+}
+
+#[test]
+fn proof_with_non_canonical_ood_evaluation_is_rejected() {
+    let mut json = proof_json();
+    json["ood_evaluations"]["execution_trace"][0] =
+        Value::String("18446744069414584321".to_string());
+
+    assert!(matches!(
+        Proof::<TestAir<Fib>>::from_json(&json),
+        Err(JsonError::InvalidValue {
+            field: "execution_trace"
+        })
+    ));
+}
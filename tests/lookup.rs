@@ -0,0 +1,109 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use common::Fixture;
+use common::TRACE_LEN;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::hints::Hints;
+use ministark::lookup::LogUp;
+use ministark::Matrix;
+use ministark::VerificationPolicy;
+
+mod common;
+
+// base columns followed by the extension column
+const VALUE: usize = 0;
+const SQUARE: usize = 1;
+const TABLE: usize = 2;
+const MULTIPLICITY: usize = 3;
+const RUNNING_SUM: usize = 4;
+
+// challenges and hints
+const ALPHA: usize = 0;
+const Z: usize = 1;
+const SUM: usize = 0;
+
+/// Looks up each `(value, square)` pair in a table of `(i, i^2)` for all rows
+fn lookup() -> LogUp<Fp> {
+    LogUp::new(
+        vec![VALUE.curr(), SQUARE.curr()],
+        vec![TABLE.curr(), TABLE.curr() * TABLE.curr()],
+        MULTIPLICITY.curr(),
+        RUNNING_SUM,
+        ALPHA,
+        Z,
+        SUM,
+    )
+}
+
+struct Squares;
+
+impl Squares {
+    fn columns(values: &[u64]) -> Matrix<Fp> {
+        let mut multiplicities = vec![0u64; TRACE_LEN];
+        for &value in values {
+            multiplicities[value as usize] += 1;
+        }
+        Matrix::new(vec![
+            common::column(values.iter().copied()),
+            common::column(values.iter().map(|value| value * value)),
+            common::column(0..TRACE_LEN as u64),
+            common::column(multiplicities),
+        ])
+    }
+}
+
+impl Fixture for Squares {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 4;
+    const NUM_EXTENSION_COLUMNS: usize = 1;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        let one = FieldConstant::Fp(Fp::one());
+        let mut constraints = vec![
+            TABLE.curr().on_first_row(n),
+            (TABLE.next() - TABLE.curr() - one).on_transition(n),
+        ];
+        constraints.extend(lookup().constraints(n));
+        constraints
+    }
+
+    fn hints(_: usize, _: &(), _: &Challenges<Fp>) -> Hints<Fp> {
+        Hints::new(lookup().hints())
+    }
+
+    fn build_extension_columns(
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fp>,
+    ) -> Option<Matrix<Fp>> {
+        let running_sum = lookup().build_running_sum(base_columns, challenges);
+        Some(Matrix::new(vec![running_sum]))
+    }
+}
+
+fn values() -> Vec<u64> {
+    (0..TRACE_LEN as u64).map(|i| i * 7 % 256).collect()
+}
+
+#[test]
+fn valid_lookup_verifies() {
+    let proof = common::prove::<Squares>(Squares::columns(&values())).unwrap();
+
+    assert!(proof.verify(&(), &VerificationPolicy::new(0)).is_ok());
+}
+
+#[test]
+fn value_missing_from_table_is_rejected() {
+    let mut columns = Squares::columns(&values());
+    // `(70, 70^2 + 1)` isn't a row of the table
+    columns[SQUARE][10] += Fp::one();
+
+    // the running sum doesn't end on the lookup's sum
+    common::assert_unsatisfied::<Squares>(columns, 4, TRACE_LEN - 1);
+}
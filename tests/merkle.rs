@@ -31,3 +31,68 @@ fn merkle_verify_large_tree() {
 
     assert!(MerkleTree::<Sha256>::verify(commitment, &proof, i).is_ok());
 }
+
+#[test]
+#[cfg(feature = "blake3")]
+fn merkle_verify_blake3() {
+    let leaf_values = (0..1 << 8).collect::<Vec<usize>>();
+    let leaf_nodes = leaf_values
+        .iter()
+        .map(|&v| Digest::finalize(blake3::Hasher::new_with_prefix(v.to_le_bytes())))
+        .collect();
+    let tree = MerkleTree::<blake3::Hasher>::new(leaf_nodes).unwrap();
+    let commitment = tree.root();
+    let i = 42;
+    let proof = tree.prove(i).unwrap().parse::<blake3::Hasher>();
+
+    assert!(MerkleTree::<blake3::Hasher>::verify(commitment, &proof, i).is_ok());
+}
+
+#[test]
+fn merkle_verify_batch() {
+    let leaf_values = (0..1 << 10).collect::<Vec<usize>>();
+    let leaf_nodes = leaf_values
+        .iter()
+        .map(|&v| Sha256::new_with_prefix(v.to_le_bytes()).finalize())
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::<Sha256>::new(leaf_nodes.clone()).unwrap();
+    let commitment = tree.root();
+    let positions = [378, 1, 0, 379, 1023, 1];
+    let leaves = positions.map(|i| leaf_nodes[i]);
+    let proof = tree.prove_batch(&positions).unwrap().parse::<Sha256>();
+
+    assert!(
+        MerkleTree::<Sha256>::verify_batch(commitment, &proof, &positions, &leaves, 1 << 10)
+            .is_ok()
+    );
+}
+
+#[test]
+fn merkle_batch_proof_omits_shared_nodes() {
+    let leaf_nodes = (0..1 << 10)
+        .map(|v: usize| Sha256::new_with_prefix(v.to_le_bytes()).finalize())
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::<Sha256>::new(leaf_nodes).unwrap();
+
+    // siblings share their entire path
+    let proof = tree.prove_batch(&[6, 7]).unwrap().parse::<Sha256>();
+
+    assert_eq!(9, proof.len());
+}
+
+#[test]
+fn merkle_verify_batch_fails_with_wrong_leaf() {
+    let leaf_nodes = (0..1 << 8)
+        .map(|v: usize| Sha256::new_with_prefix(v.to_le_bytes()).finalize())
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::<Sha256>::new(leaf_nodes.clone()).unwrap();
+    let commitment = tree.root();
+    let positions = [3, 42];
+    let leaves = [leaf_nodes[3], leaf_nodes[43]];
+    let proof = tree.prove_batch(&positions).unwrap().parse::<Sha256>();
+
+    assert!(
+        MerkleTree::<Sha256>::verify_batch(commitment, &proof, &positions, &leaves, 1 << 8)
+            .is_err()
+    );
+}
@@ -0,0 +1,42 @@
+#![feature(allocator_api)]
+use common::MerklePathProver;
+use ministark::merkle_path::MerklePathInputs;
+use ministark::merkle_path::MerklePathTrace;
+use ministark::rescue_prime;
+use ministark::Prover;
+use ministark::VerificationPolicy;
+
+mod common;
+
+#[test]
+fn trace_computes_merkle_root() {
+    let (leaves, tree) = common::merkle_tree(16);
+    let index = 11;
+    let siblings = common::merkle_siblings(&tree, index);
+
+    let trace = MerklePathTrace::new(leaves[index], index as u64, &siblings);
+
+    let expected_root = rescue_prime::digest_to_elements(tree.root()).unwrap();
+    assert_eq!(expected_root, trace.inputs().root);
+    assert_eq!(4, trace.inputs().depth);
+}
+
+#[test]
+fn merkle_path_proof_verifies() {
+    let (leaves, tree) = common::merkle_tree(16);
+    let index = 5;
+    let siblings = common::merkle_siblings(&tree, index);
+    let trace = MerklePathTrace::new(leaves[index], index as u64, &siblings);
+    let inputs = trace.inputs().clone();
+    let prover = MerklePathProver::new(common::options());
+
+    let proof = pollster::block_on(prover.generate_proof(trace)).unwrap();
+
+    let policy = VerificationPolicy::new(0);
+    assert!(proof.verify(&inputs, &policy).is_ok());
+    let other_leaf = MerklePathInputs {
+        leaf: leaves[index + 1],
+        ..inputs
+    };
+    assert!(proof.verify(&other_leaf, &policy).is_err());
+}
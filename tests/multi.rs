@@ -0,0 +1,298 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use common::Fixture;
+use common::TestAir;
+use common::TestTrace;
+use gpu_poly::allocator::PageAlignedAllocator;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::constraints::Hint;
+use ministark::constraints::VerifierChallenge;
+use ministark::hints::Hints;
+use ministark::multi::MultiAir;
+use ministark::multi::MultiProver;
+use ministark::multi::TableAir;
+use ministark::multi::TableTrace;
+use ministark::trace::PreprocessedTrace;
+use ministark::Air;
+use ministark::Matrix;
+use ministark::ProofOptions;
+use ministark::TraceInfo;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+use sha2::Sha256;
+
+mod common;
+
+const VALUE: usize = 0;
+const PRODUCT: usize = 1;
+// only in tables with a preprocessed column
+const FIXED: usize = 2;
+
+/// Values `0..n` in ascending or descending order
+fn count(n: usize, descending: bool) -> Matrix<Fp> {
+    let mut column = Vec::new_in(PageAlignedAllocator);
+    column.extend((0..n as u64).map(Fp::from));
+    if descending {
+        column.reverse();
+    }
+    Matrix::new(vec![column])
+}
+
+/// Table with a column that counts through `0..n` and a running product of
+/// `alpha - value` where `alpha` is a challenge shared by all tables. The
+/// values of tables with a preprocessed column must also match that column.
+struct Count<const DESCENDING: bool, const PREPROCESSED: bool = false>;
+
+impl<const DESCENDING: bool, const PREPROCESSED: bool> Fixture for Count<DESCENDING, PREPROCESSED> {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 1;
+    const NUM_EXTENSION_COLUMNS: usize = 1;
+    const NUM_PREPROCESSED_COLUMNS: usize = PREPROCESSED as usize;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        use AlgebraicExpression::X;
+        let trace_xs = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+        let one = FieldConstant::Fp(Fp::one());
+        let first_row: AlgebraicExpression<Fp> = X - FieldConstant::Fp(trace_xs.element(0));
+        let last_row: AlgebraicExpression<Fp> = X - FieldConstant::Fp(trace_xs.element(n - 1));
+        // all rows except the last
+        let transitions = &last_row / (X.pow(n) - one);
+        let alpha: AlgebraicExpression<Fp> = 0.challenge();
+
+        let (first_value, step) = if DESCENDING {
+            (Fp::from(n as u64 - 1), -Fp::one())
+        } else {
+            (Fp::from(0u64), Fp::one())
+        };
+
+        let mut constraints = vec![
+            (VALUE.curr() - FieldConstant::Fp(first_value)) / &first_row,
+            (VALUE.next() - VALUE.curr() - FieldConstant::Fp(step)) * &transitions,
+            (PRODUCT.curr() - (&alpha - VALUE.curr())) / &first_row,
+            (PRODUCT.next() - PRODUCT.curr() * (&alpha - VALUE.next())) * &transitions,
+            (PRODUCT.curr() - 0.hint()) / &last_row,
+        ];
+        if PREPROCESSED {
+            constraints.push((VALUE.curr() - FIXED.curr()) / (X.pow(n) - one));
+        }
+        constraints
+    }
+
+    fn hints(n: usize, _: &(), challenges: &Challenges<Fp>) -> Hints<Fp> {
+        let alpha = challenges[0];
+        let product: Fp = (0..n as u64).map(|value| alpha - Fp::from(value)).product();
+        Hints::new(vec![(0, product)])
+    }
+
+    fn build_extension_columns(
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fp>,
+    ) -> Option<Matrix<Fp>> {
+        let alpha = challenges[0];
+        let mut acc = Fp::one();
+        let mut column = Vec::new_in(PageAlignedAllocator);
+        for value in &base_columns[VALUE] {
+            acc *= alpha - value;
+            column.push(acc);
+        }
+        Some(Matrix::new(vec![column]))
+    }
+}
+
+type Ascending = TestTrace<Count<false>>;
+type Descending = TestTrace<Count<true>>;
+
+/// An ascending table followed by any number of descending tables
+struct CountTablesAir {
+    options: ProofOptions,
+    tables: Vec<Box<TableAir<Self>>>,
+}
+
+impl MultiAir for CountTablesAir {
+    type Fp = Fp;
+    type Fq = Fp;
+    type PublicInputs = ();
+
+    fn new(trace_infos: Vec<TraceInfo>, _: (), options: ProofOptions) -> Self {
+        let tables = trace_infos
+            .into_iter()
+            .enumerate()
+            .map(|(i, info)| -> Box<TableAir<Self>> {
+                if i == 0 {
+                    Box::new(TestAir::<Count<false>>::new(info, (), options))
+                } else if info.num_preprocessed_columns == 0 {
+                    Box::new(TestAir::<Count<true>>::new(info, (), options))
+                } else {
+                    Box::new(TestAir::<Count<true, true>>::new(info, (), options))
+                }
+            })
+            .collect();
+        CountTablesAir { options, tables }
+    }
+
+    fn tables(&self) -> &[Box<TableAir<Self>>] {
+        &self.tables
+    }
+
+    fn pub_inputs(&self) -> &() {
+        &()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    /// Reveals the running product of the first table
+    fn terminal_columns(&self) -> Vec<(usize, usize)> {
+        vec![(0, PRODUCT)]
+    }
+}
+
+/// Optionally holds the preprocessed column of the second table
+struct CountTablesProver(ProofOptions, Option<PreprocessedTrace<Fp, Sha256>>);
+
+impl MultiProver for CountTablesProver {
+    type Fp = Fp;
+    type Fq = Fp;
+    type Air = CountTablesAir;
+    type Digest = Sha256;
+
+    fn new(options: ProofOptions) -> Self {
+        CountTablesProver(options, None)
+    }
+
+    fn options(&self) -> ProofOptions {
+        self.0
+    }
+
+    fn get_pub_inputs(&self, _: &[&dyn TableTrace<Fp, Fp>]) {}
+
+    fn preprocessed_trace(&self, table: usize) -> Option<&PreprocessedTrace<Fp, Sha256>> {
+        self.1.as_ref().filter(|_| table == 1)
+    }
+}
+
+#[test]
+fn tables_of_different_lengths_share_one_proof() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(4096, true));
+    let prover = CountTablesProver::new(common::options());
+
+    let proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    assert_eq!(2, proof.tables.len());
+    assert_eq!(2048, proof.tables[0].trace_info.trace_len);
+    assert_eq!(4096, proof.tables[1].trace_info.trace_len);
+    assert_eq!(1, proof.terminals.len());
+    assert!(proof.verify(&(), &VerificationPolicy::new(0)).is_ok());
+}
+
+#[test]
+fn tampered_table_fails_verification() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(2048, true));
+    let prover = CountTablesProver::new(common::options());
+    let mut proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    proof.tables[1].execution_trace_ood_evals[0] += Fp::one();
+
+    assert!(matches!(
+        proof.verify(&(), &VerificationPolicy::new(0)),
+        Err(VerificationError::InconsistentOodConstraintEvaluations)
+    ));
+}
+
+#[test]
+fn proof_without_tables_is_rejected() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(2048, true));
+    let prover = CountTablesProver::new(common::options());
+    let mut proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    proof.tables.clear();
+
+    assert!(matches!(
+        proof.verify(&(), &VerificationPolicy::new(0)),
+        Err(VerificationError::TableCountMismatch {
+            expected: 0,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn tampered_terminal_fails_verification() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(2048, true));
+    let prover = CountTablesProver::new(common::options());
+    let mut proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    proof.terminals[0] += Fp::one();
+
+    // terminals are absorbed by the public coin and feed the hints
+    assert!(matches!(
+        proof.verify(&(), &VerificationPolicy::new(0)),
+        Err(VerificationError::InconsistentOodConstraintEvaluations)
+    ));
+}
+
+#[test]
+fn extra_terminal_is_rejected() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(2048, true));
+    let prover = CountTablesProver::new(common::options());
+    let mut proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    proof.terminals.push(Fp::one());
+
+    assert!(matches!(
+        proof.verify(&(), &VerificationPolicy::new(0)),
+        Err(VerificationError::TerminalCountMismatch {
+            expected: 1,
+            actual: 2
+        })
+    ));
+}
+
+#[test]
+fn zero_knowledge_tables_verify() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = Descending::new(count(4096, true));
+    let prover = CountTablesProver::new(common::options().with_zero_knowledge().unwrap());
+
+    let proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    assert!(proof.options.zero_knowledge);
+    assert!(proof.verify(&(), &VerificationPolicy::new(0)).is_ok());
+}
+
+#[test]
+fn table_with_preprocessed_columns_verifies_with_key() {
+    let ascending = Ascending::new(count(2048, false));
+    let descending = TestTrace::<Count<true, true>>::new(count(2048, true));
+    let lde_size = 2048 * common::options().lde_blowup_factor as usize;
+    let fixed = PreprocessedTrace::with_lde_size(count(2048, true), lde_size);
+    let key = fixed.verification_key();
+    let prover = CountTablesProver(common::options(), Some(fixed));
+    let policy = VerificationPolicy::new(0);
+
+    let proof = pollster::block_on(prover.generate_proof(&[&ascending, &descending])).unwrap();
+
+    assert_eq!(1, proof.tables[1].trace_info.num_preprocessed_columns);
+    assert!(proof
+        .verify_with_keys(&(), &[None, Some(&key)], &policy)
+        .is_ok());
+    assert!(matches!(
+        proof.verify(&(), &policy),
+        Err(VerificationError::MissingVerificationKey)
+    ));
+}
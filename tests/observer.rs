@@ -0,0 +1,122 @@
+#![feature(allocator_api)]
+use common::Fib;
+use common::TestProver;
+use common::TestTrace;
+use common::TRACE_LEN;
+use core::time::Duration;
+use ministark::observer::ProverObserver;
+use ministark::observer::ProvingStage;
+use ministark::observer::TraceSegment;
+use ministark::Prover;
+
+mod common;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Started(ProvingStage),
+    Finished(ProvingStage),
+}
+
+/// Records every stage the prover reports
+#[derive(Default)]
+struct Recorder(Vec<Event>);
+
+impl ProverObserver for Recorder {
+    fn stage_started(&mut self, stage: &ProvingStage) {
+        self.0.push(Event::Started(*stage));
+    }
+
+    fn stage_finished(&mut self, stage: &ProvingStage, _duration: Option<Duration>) {
+        self.0.push(Event::Finished(*stage));
+    }
+}
+
+#[test]
+fn stages_are_reported_in_order() {
+    let options = common::options();
+    let lde_size = TRACE_LEN * options.lde_blowup_factor as usize;
+    let prover = TestProver::<Fib>::new(options);
+    let mut recorder = Recorder::default();
+
+    pollster::block_on(
+        prover.generate_proof_with_observer(TestTrace::new(Fib::columns()), &mut recorder),
+    )
+    .unwrap();
+
+    // every stage finishes before the next one starts
+    let stages = recorder
+        .0
+        .chunks(2)
+        .map(|events| match events {
+            [Event::Started(started), Event::Finished(finished)] if started == finished => *started,
+            events => panic!("unmatched stage events {events:?}"),
+        })
+        .collect::<Vec<ProvingStage>>();
+
+    assert_eq!(
+        stages[..3],
+        [
+            ProvingStage::Interpolation {
+                segment: TraceSegment::Base,
+                num_cols: 2,
+                trace_len: TRACE_LEN,
+            },
+            ProvingStage::LowDegreeExtension {
+                segment: TraceSegment::Base,
+                num_cols: 2,
+                lde_size,
+            },
+            ProvingStage::Commitment {
+                segment: TraceSegment::Base,
+                num_cols: 2,
+                lde_size,
+            },
+        ]
+    );
+    assert!(matches!(
+        stages[3],
+        ProvingStage::ConstraintEvaluation { ce_size } if ce_size >= TRACE_LEN
+    ));
+    assert!(matches!(
+        stages[4],
+        ProvingStage::LowDegreeExtension {
+            segment: TraceSegment::Composition,
+            lde_size: size,
+            ..
+        } if size == lde_size
+    ));
+    assert!(matches!(
+        stages[5],
+        ProvingStage::Commitment {
+            segment: TraceSegment::Composition,
+            lde_size: size,
+            ..
+        } if size == lde_size
+    ));
+    assert_eq!(stages[6], ProvingStage::DeepComposition { lde_size });
+
+    let (fri_layers, last) = stages[7..].split_at(stages.len() - 9);
+    assert!(!fri_layers.is_empty());
+    let mut domain_size = lde_size;
+    for (i, stage) in fri_layers.iter().enumerate() {
+        assert_eq!(
+            *stage,
+            ProvingStage::FriLayer {
+                layer: i,
+                domain_size
+            }
+        );
+        domain_size /= options.fri_folding_factor as usize;
+    }
+    assert_eq!(
+        last,
+        [
+            ProvingStage::Grinding {
+                grinding_factor: options.grinding_factor
+            },
+            ProvingStage::Queries {
+                num_queries: options.num_queries as usize
+            },
+        ]
+    );
+}
@@ -0,0 +1,41 @@
+use ark_ff::FftField;
+use ark_ff::UniformRand;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::periodic::PeriodicColumn;
+
+#[test]
+fn periodic_column_repeats_over_trace_domain() {
+    let n = 2048;
+    let mut rng = ark_std::test_rng();
+    let column = PeriodicColumn::new((0..8).map(|_| Fp::rand(&mut rng)).collect());
+    let trace_domain = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+
+    for (row, x) in trace_domain.elements().enumerate() {
+        assert_eq!(
+            column.value_at(row),
+            column.evaluate(x, n),
+            "mismatch at row {row}"
+        );
+    }
+}
+
+#[test]
+fn periodic_column_evaluates_over_coset() {
+    let n = 2048;
+    let mut rng = ark_std::test_rng();
+    let column = PeriodicColumn::new((0..16).map(|_| Fp::rand(&mut rng)).collect());
+    let coset = Radix2EvaluationDomain::<Fp>::new_coset(n * 4, Fp::GENERATOR).unwrap();
+
+    let evals = column.evaluate_over(coset, n);
+
+    assert_eq!(16 * 4, evals.len());
+    for (i, x) in coset.elements().enumerate() {
+        assert_eq!(
+            column.evaluate(x, n),
+            evals[i % evals.len()],
+            "mismatch at {i}"
+        );
+    }
+}
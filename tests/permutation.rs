@@ -0,0 +1,115 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use common::Fixture;
+use common::TRACE_LEN;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::permutation::Permutation;
+use ministark::permutation::RunningProduct;
+use ministark::Matrix;
+use ministark::VerificationPolicy;
+
+mod common;
+
+// base columns followed by the extension columns
+const SHUFFLED: usize = 0;
+const SHUFFLED_SQUARE: usize = 1;
+const SORTED: usize = 2;
+const SORTED_SQUARE: usize = 3;
+const SHUFFLED_PRODUCT: usize = 4;
+const SORTED_PRODUCT: usize = 5;
+
+// challenges
+const ALPHA: usize = 0;
+const GAMMA: usize = 1;
+
+/// Shows the `(value, square)` pairs of the shuffled and sorted columns are
+/// permutations of each other
+fn permutation() -> Permutation<Fp> {
+    Permutation::new(
+        RunningProduct::new(
+            vec![SHUFFLED.curr(), SHUFFLED_SQUARE.curr()],
+            SHUFFLED_PRODUCT,
+            ALPHA,
+            GAMMA,
+        ),
+        RunningProduct::new(
+            vec![SORTED.curr(), SORTED_SQUARE.curr()],
+            SORTED_PRODUCT,
+            ALPHA,
+            GAMMA,
+        ),
+    )
+}
+
+struct Shuffle;
+
+impl Shuffle {
+    fn columns() -> Matrix<Fp> {
+        let n = TRACE_LEN as u64;
+        // multiplying by an odd number permutes the integers modulo a power of two
+        let shuffled = (0..n).map(|i| i * 5 % n).collect::<Vec<u64>>();
+        Matrix::new(vec![
+            common::column(shuffled.iter().copied()),
+            common::column(shuffled.iter().map(|v| v * v)),
+            common::column(0..n),
+            common::column((0..n).map(|v| v * v)),
+        ])
+    }
+}
+
+impl Fixture for Shuffle {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 4;
+    const NUM_EXTENSION_COLUMNS: usize = 2;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        permutation().constraints(n)
+    }
+
+    fn build_extension_columns(
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fp>,
+    ) -> Option<Matrix<Fp>> {
+        let (shuffled_product, sorted_product) =
+            permutation().build_columns(base_columns, challenges);
+        Some(Matrix::new(vec![shuffled_product, sorted_product]))
+    }
+}
+
+#[test]
+fn public_product_matches_running_product() {
+    let columns = Shuffle::columns();
+    let mut rng = ark_std::test_rng();
+    let challenges = Challenges::new(&mut rng, 2);
+    let sorted = permutation().right().clone();
+    let tuples = (0..TRACE_LEN as u64)
+        .map(|v| [Fp::from(v), Fp::from(v * v)])
+        .collect::<Vec<[Fp; 2]>>();
+
+    let column = sorted.build_column(&columns, &challenges);
+    let product = sorted.product(tuples.iter().map(|tuple| tuple.as_slice()), &challenges);
+
+    assert_eq!(*column.last().unwrap(), product);
+}
+
+#[test]
+fn permuted_columns_verify() {
+    let proof = common::prove::<Shuffle>(Shuffle::columns()).unwrap();
+
+    assert!(proof.verify(&(), &VerificationPolicy::new(0)).is_ok());
+}
+
+#[test]
+fn non_permuted_columns_are_rejected() {
+    let mut columns = Shuffle::columns();
+    columns[SHUFFLED_SQUARE][7] += Fp::one();
+
+    // the running products end on different values
+    common::assert_unsatisfied::<Shuffle>(columns, 4, TRACE_LEN - 1);
+}
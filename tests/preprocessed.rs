@@ -0,0 +1,134 @@
+#![feature(allocator_api)]
+use common::Fixture;
+use common::TestProver;
+use common::TRACE_LEN;
+use gpu_poly::allocator::PageAlignedAllocator;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::trace::PreprocessedTrace;
+use ministark::Matrix;
+use ministark::Prover;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+use sha2::Sha256;
+
+mod common;
+
+// base column followed by the preprocessed column
+const ACC: usize = 0;
+const CONSTANT: usize = 1;
+
+/// Fixed column of constants that's shared by every proof
+fn constants(step: u64) -> Matrix<Fp> {
+    Matrix::new(vec![common::column(
+        (0..TRACE_LEN as u64).map(|i| i * step % 17),
+    )])
+}
+
+/// Running sum of the constants starting from a public value
+fn accumulate(start: Fp, constants: &Matrix<Fp>) -> Matrix<Fp> {
+    let mut column = Vec::new_in(PageAlignedAllocator);
+    let mut acc = start;
+    for constant in &constants[0] {
+        column.push(acc);
+        acc += constant;
+    }
+    Matrix::new(vec![column])
+}
+
+/// Accumulates the preprocessed constants
+struct Accumulator;
+
+impl Fixture for Accumulator {
+    type PublicInputs = Fp;
+
+    const NUM_BASE_COLUMNS: usize = 1;
+    const NUM_PREPROCESSED_COLUMNS: usize = 1;
+
+    fn pub_inputs(base_columns: &Matrix<Fp>) -> Fp {
+        base_columns[ACC][0]
+    }
+
+    fn constraints(n: usize, start: &Fp) -> Vec<AlgebraicExpression<Fp>> {
+        vec![
+            (ACC.curr() - FieldConstant::Fp(*start)).on_first_row(n),
+            (ACC.next() - ACC.curr() - CONSTANT.curr()).on_transition(n),
+        ]
+    }
+
+    fn preprocessed_columns() -> Option<Matrix<Fp>> {
+        Some(constants(3))
+    }
+}
+
+/// [Accumulator] that passes its own constants as a base column instead of
+/// preprocessed columns
+struct Forged;
+
+impl Fixture for Forged {
+    type PublicInputs = Fp;
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(base_columns: &Matrix<Fp>) -> Fp {
+        Accumulator::pub_inputs(base_columns)
+    }
+
+    fn constraints(n: usize, start: &Fp) -> Vec<AlgebraicExpression<Fp>> {
+        Accumulator::constraints(n, start)
+    }
+}
+
+#[test]
+fn proofs_share_preprocessed_commitment() {
+    let prover = TestProver::<Accumulator>::new(common::options());
+    let key = prover.preprocessed_trace().unwrap().verification_key();
+    let policy = VerificationPolicy::new(0);
+
+    for start in [Fp::from(0u64), Fp::from(1234u64)] {
+        let columns = accumulate(start, &constants(3));
+        let proof = common::prove::<Accumulator>(columns).unwrap();
+
+        assert_eq!(1, proof.trace_info.num_preprocessed_columns);
+        assert!(proof.verify_with_key(&start, &key, &policy).is_ok());
+    }
+}
+
+#[test]
+fn preprocessed_columns_require_matching_key() {
+    let start = Fp::from(7u64);
+    let proof = common::prove::<Accumulator>(accumulate(start, &constants(3))).unwrap();
+    let other_key =
+        PreprocessedTrace::<Fp, Sha256>::new(constants(5), common::options()).verification_key();
+    let policy = VerificationPolicy::new(0);
+
+    assert!(matches!(
+        proof.verify(&start, &policy),
+        Err(VerificationError::MissingVerificationKey)
+    ));
+    assert!(proof.verify_with_key(&start, &other_key, &policy).is_err());
+}
+
+#[test]
+fn preprocessed_columns_passed_as_base_columns_are_rejected() {
+    let key = TestProver::<Accumulator>::new(common::options())
+        .preprocessed_trace()
+        .unwrap()
+        .verification_key();
+    let start = Fp::from(7u64);
+    let mut columns = accumulate(start, &constants(5));
+    columns.append(constants(5));
+    let proof = common::prove::<Forged>(columns).unwrap();
+    let policy = VerificationPolicy::new(0);
+
+    assert!(matches!(
+        proof.verify_with_key(&start, &key, &policy),
+        Err(VerificationError::VerificationKeyMismatch {
+            field: "columns",
+            expected: 1,
+            actual: 0
+        })
+    ));
+}
@@ -0,0 +1,360 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use common::Fib;
+use common::Fixture;
+use common::TRACE_LEN;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::ExecutionTraceColumn;
+use ministark::constraints::FieldConstant;
+use ministark::constraints::Periodic;
+use ministark::periodic::PeriodicColumn;
+use ministark::prover::ProvingError;
+use ministark::Matrix;
+use ministark::ProofOptions;
+use ministark::TraceInfo;
+
+mod common;
+
+/// Fibonacci constraints over a trace with the given dimensions
+macro_rules! fib_fixture {
+    ($name:ident, $($dimension:item)*) => {
+        struct $name;
+
+        impl Fixture for $name {
+            type PublicInputs = Fp;
+
+            $($dimension)*
+
+            fn pub_inputs(base_columns: &Matrix<Fp>) -> Fp {
+                Fib::pub_inputs(base_columns)
+            }
+
+            fn constraints(n: usize, result: &Fp) -> Vec<AlgebraicExpression<Fp>> {
+                Fib::constraints(n, result)
+            }
+        }
+    };
+}
+
+fib_fixture! {
+    NoBaseColumns,
+    const NUM_BASE_COLUMNS: usize = 0;
+}
+
+fib_fixture! {
+    TooWide,
+    const NUM_BASE_COLUMNS: usize = 2;
+    const NUM_EXTENSION_COLUMNS: usize = TraceInfo::MAX_TRACE_WIDTH;
+}
+
+fib_fixture! {
+    TooMuchMeta,
+    const NUM_BASE_COLUMNS: usize = 2;
+    const META: &'static [u8] = &[0; TraceInfo::MAX_META_BYTES + 1];
+}
+
+fib_fixture! {
+    MissingExtension,
+    const NUM_BASE_COLUMNS: usize = 2;
+    const NUM_EXTENSION_COLUMNS: usize = 1;
+}
+
+fib_fixture! {
+    MissingPreprocessed,
+    const NUM_BASE_COLUMNS: usize = 2;
+    const NUM_PREPROCESSED_COLUMNS: usize = 1;
+}
+
+/// Constrains a column the trace doesn't have
+struct UndefinedColumn;
+
+impl Fixture for UndefinedColumn {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        vec![(2usize.curr() - FieldConstant::Fp(Fp::one())).on_first_row(n)]
+    }
+}
+
+/// Constrains a periodic column the AIR doesn't have
+struct UndefinedPeriodicColumn;
+
+impl Fixture for UndefinedPeriodicColumn {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        vec![(Fib::A.curr() - 0.periodic()).on_transition(n)]
+    }
+}
+
+/// Periodic column that repeats less than once over the trace
+struct LongPeriod;
+
+impl Fixture for LongPeriod {
+    type PublicInputs = Fp;
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(base_columns: &Matrix<Fp>) -> Fp {
+        Fib::pub_inputs(base_columns)
+    }
+
+    fn constraints(n: usize, result: &Fp) -> Vec<AlgebraicExpression<Fp>> {
+        Fib::constraints(n, result)
+    }
+
+    fn periodic_columns() -> Vec<PeriodicColumn<Fp>> {
+        vec![PeriodicColumn::new(vec![Fp::one(); TRACE_LEN * 2])]
+    }
+}
+
+/// Constraint of degree `16 * trace_len`
+struct HighDegree;
+
+impl Fixture for HighDegree {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        vec![(Fib::A.curr().pow(16) - Fib::B.curr()).on_transition(n)]
+    }
+}
+
+/// Cubic constraint with a blowup factor that only fits linear constraints
+struct SmallBlowup;
+
+impl Fixture for SmallBlowup {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 2;
+    const CE_BLOWUP_FACTOR: Option<usize> = Some(1);
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        vec![(Fib::A.next() - Fib::B.curr()).pow(3).on_transition(n)]
+    }
+}
+
+/// Opens the first column at 16 different offsets
+struct ManyOffsets;
+
+impl Fixture for ManyOffsets {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 2;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        let sum = (0..16)
+            .map(|offset| Fib::A.offset(offset))
+            .sum::<AlgebraicExpression<Fp>>();
+        vec![(sum - Fib::B.curr()).on_transition(n)]
+    }
+}
+
+fn fib_columns(len: usize) -> Matrix<Fp> {
+    Matrix::new(vec![
+        common::column(0..len as u64),
+        common::column(0..len as u64),
+    ])
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let prove = |options| common::prove_with_options::<Fib>(Fib::columns(), options);
+
+    assert!(matches!(
+        prove(ProofOptions::new(0, 8, 0, 4, 16)),
+        Err(ProvingError::InvalidNumQueries { num_queries: 0 })
+    ));
+    assert!(matches!(
+        prove(ProofOptions::new(32, 6, 0, 4, 16)),
+        Err(ProvingError::InvalidBlowupFactor {
+            lde_blowup_factor: 6
+        })
+    ));
+    assert!(matches!(
+        prove(ProofOptions::new(32, 8, 33, 4, 16)),
+        Err(ProvingError::GrindingFactorTooLarge {
+            grinding_factor: 33
+        })
+    ));
+    assert!(matches!(
+        prove(ProofOptions::new(32, 8, 0, 3, 16)),
+        Err(ProvingError::UnsupportedFriFoldingFactor {
+            fri_folding_factor: 3
+        })
+    ));
+}
+
+#[test]
+fn trace_without_base_columns_is_rejected() {
+    assert!(matches!(
+        common::prove::<NoBaseColumns>(Matrix::new(vec![])),
+        Err(ProvingError::NoBaseColumns)
+    ));
+}
+
+#[test]
+fn wide_trace_is_rejected() {
+    assert!(matches!(
+        common::prove::<TooWide>(Fib::columns()),
+        Err(ProvingError::TraceTooWide { num_columns, max })
+            if num_columns == TraceInfo::MAX_TRACE_WIDTH + 2 && max == TraceInfo::MAX_TRACE_WIDTH
+    ));
+}
+
+#[test]
+fn large_trace_meta_is_rejected() {
+    assert!(matches!(
+        common::prove::<TooMuchMeta>(Fib::columns()),
+        Err(ProvingError::TraceMetaTooLarge { num_bytes, max })
+            if num_bytes == TraceInfo::MAX_META_BYTES + 1 && max == TraceInfo::MAX_META_BYTES
+    ));
+}
+
+#[test]
+fn trace_dimensions_are_checked() {
+    let mut missing_column = Fib::columns();
+    missing_column.0.pop();
+
+    assert!(matches!(
+        common::prove::<Fib>(missing_column),
+        Err(ProvingError::BaseTraceWidth {
+            expected: 2,
+            actual: 1
+        })
+    ));
+    assert!(matches!(
+        common::prove::<Fib>(fib_columns(TRACE_LEN + 1)),
+        Err(ProvingError::TraceLengthNotPowerOfTwo { trace_len }) if trace_len == TRACE_LEN + 1
+    ));
+    assert!(matches!(
+        common::prove::<Fib>(fib_columns(TRACE_LEN / 2)),
+        Err(ProvingError::TraceTooShort { trace_len, min }) if trace_len == TRACE_LEN / 2
+            && min == TraceInfo::MIN_TRACE_LENGTH
+    ));
+}
+
+#[test]
+fn missing_extension_columns_are_rejected() {
+    assert!(matches!(
+        common::prove::<MissingExtension>(Fib::columns()),
+        Err(ProvingError::ExtensionTraceWidth {
+            expected: 1,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn missing_preprocessed_columns_are_rejected() {
+    assert!(matches!(
+        common::prove::<MissingPreprocessed>(Fib::columns()),
+        Err(ProvingError::PreprocessedTraceWidth {
+            expected: 1,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn undefined_column_is_rejected() {
+    assert!(matches!(
+        common::prove::<UndefinedColumn>(Fib::columns()),
+        Err(ProvingError::ColumnOutOfRange {
+            constraint: 0,
+            column: 2,
+            num_columns: 2
+        })
+    ));
+}
+
+#[test]
+fn undefined_periodic_column_is_rejected() {
+    assert!(matches!(
+        common::prove::<UndefinedPeriodicColumn>(Fib::columns()),
+        Err(ProvingError::PeriodicColumnOutOfRange {
+            constraint: 0,
+            column: 0,
+            num_periodic_columns: 0
+        })
+    ));
+}
+
+#[test]
+fn period_longer_than_trace_is_rejected() {
+    assert!(matches!(
+        common::prove::<LongPeriod>(Fib::columns()),
+        Err(ProvingError::PeriodTooLong {
+            column: 0,
+            period,
+            trace_len
+        }) if period == TRACE_LEN * 2 && trace_len == TRACE_LEN
+    ));
+}
+
+#[test]
+fn high_degree_constraint_is_rejected() {
+    assert!(matches!(
+        common::prove::<HighDegree>(Fib::columns()),
+        Err(ProvingError::ConstraintDegreeTooHigh {
+            ce_blowup_factor: 16,
+            lde_blowup_factor: 8
+        })
+    ));
+}
+
+#[test]
+fn constraint_above_composition_degree_is_rejected() {
+    assert!(matches!(
+        common::prove::<SmallBlowup>(Fib::columns()),
+        Err(ProvingError::ConstraintDegree {
+            constraint: 0,
+            degree,
+            max
+        }) if degree == 2 * TRACE_LEN - 2 && max == TRACE_LEN - 1
+    ));
+}
+
+#[test]
+fn too_many_trace_randomizers_are_rejected() {
+    let options = ProofOptions::new(128, 8, 0, 4, 16)
+        .with_zero_knowledge()
+        .unwrap();
+
+    // 129 openings for each of the 16 offsets and the out-of-domain point
+    assert!(matches!(
+        common::prove_with_options::<ManyOffsets>(Fib::columns(), options),
+        Err(ProvingError::TooManyTraceRandomizers {
+            num_trace_randomizers: 2193,
+            trace_len
+        }) if trace_len == TRACE_LEN
+    ));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn unsatisfied_constraint_is_rejected_in_debug_builds() {
+    let mut columns = Fib::columns();
+    columns[Fib::B][100] += Fp::one();
+
+    assert!(matches!(
+        common::prove::<Fib>(columns),
+        Err(ProvingError::UnsatisfiedConstraint { .. })
+    ));
+}
@@ -0,0 +1,33 @@
+use ministark::random::PublicCoin;
+use sha2::Sha256;
+
+#[test]
+fn draw_queries_are_unique_and_in_range() {
+    let mut public_coin = PublicCoin::<Sha256>::new(b"seed");
+
+    let positions = public_coin.draw_queries(64, 100);
+
+    assert_eq!(64, positions.len());
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    assert!(positions.iter().all(|&position| position < 100));
+}
+
+#[test]
+fn draw_queries_saturates_small_domain() {
+    let mut public_coin = PublicCoin::<Sha256>::new(b"seed");
+
+    let positions = public_coin.draw_queries(20, 16);
+
+    assert_eq!((0..16).collect::<Vec<usize>>(), positions);
+}
+
+#[test]
+fn draw_queries_is_deterministic() {
+    let mut public_coin1 = PublicCoin::<Sha256>::new(b"seed");
+    let mut public_coin2 = PublicCoin::<Sha256>::new(b"seed");
+
+    assert_eq!(
+        public_coin1.draw_queries(32, 1 << 14),
+        public_coin2.draw_queries(32, 1 << 14)
+    );
+}
@@ -0,0 +1,66 @@
+#![feature(allocator_api)]
+use common::Fixture;
+use common::TRACE_LEN;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicExpression;
+use ministark::range_check::RangeCheck;
+use ministark::Matrix;
+use ministark::VerificationPolicy;
+
+mod common;
+
+// the byte column followed by the range check's columns
+const BYTE: usize = 0;
+const RANGE_CHECK_COLUMNS: usize = 1;
+
+/// Checks each value of the byte column is in `[0, 256)`
+fn range_check() -> RangeCheck<Fp> {
+    RangeCheck::new(BYTE, 8, RANGE_CHECK_COLUMNS, 0)
+}
+
+struct Bytes;
+
+impl Bytes {
+    fn columns(values: impl Iterator<Item = u64>) -> Matrix<Fp> {
+        Matrix::new(vec![common::column(values)])
+    }
+}
+
+impl Fixture for Bytes {
+    type PublicInputs = ();
+
+    const NUM_BASE_COLUMNS: usize = 1;
+    const NUM_EXTENSION_COLUMNS: usize = RangeCheck::<Fp>::NUM_EXTENSION_COLUMNS;
+
+    fn pub_inputs(_: &Matrix<Fp>) {}
+
+    fn constraints(n: usize, _: &()) -> Vec<AlgebraicExpression<Fp>> {
+        range_check().constraints(n)
+    }
+
+    fn build_extension_columns(
+        base_columns: &Matrix<Fp>,
+        challenges: &Challenges<Fp>,
+    ) -> Option<Matrix<Fp>> {
+        Some(range_check().build_columns(base_columns, challenges))
+    }
+}
+
+#[test]
+fn bytes_pass_range_check() {
+    let columns = Bytes::columns((0..TRACE_LEN as u64).map(|i| i * 13 % 256));
+
+    let proof = common::prove::<Bytes>(columns).unwrap();
+
+    assert!(proof.verify(&(), &VerificationPolicy::new(0)).is_ok());
+}
+
+#[test]
+fn out_of_range_value_is_rejected() {
+    let columns =
+        Bytes::columns((0..TRACE_LEN as u64).map(|i| if i == 100 { 256 } else { i % 256 }));
+
+    // the lookup's running sum doesn't end on zero
+    common::assert_unsatisfied::<Bytes>(columns, 5, TRACE_LEN - 1);
+}
@@ -0,0 +1,106 @@
+#![feature(allocator_api)]
+use common::Fib;
+use common::Fixture;
+use common::TestAir;
+use ministark::format::MAGIC;
+use ministark::Air;
+use ministark::Proof;
+
+mod common;
+
+/// Size of a serialized Sha256 commitment including its length prefix
+const COMMITMENT_SIZE: usize = 8 + 32;
+/// Size of a serialized vector of field elements including its length prefix
+fn vec_size(len: usize) -> usize {
+    8 + 8 * len
+}
+
+fn air(proof: &Proof<TestAir<Fib>>) -> TestAir<Fib> {
+    TestAir::new(proof.trace_info.clone(), proof.public_inputs, proof.options)
+}
+
+#[test]
+fn report_adds_up_to_serialized_proof() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let report = proof.size_report();
+
+    assert_eq!(proof.to_bytes().len(), report.total());
+}
+
+#[test]
+fn report_matches_sections_of_serialized_proof() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let report = proof.size_report();
+    let bytes = proof.to_bytes();
+    let sections = common::sections(&bytes);
+    let fri_layers = report.fri_layers.iter().map(|layer| layer.total());
+
+    // magic, version and a length prefix for each section
+    assert_eq!(MAGIC.len() + 2 + 8 * sections.len(), report.framing);
+    assert_eq!(
+        sections[..3].iter().map(|s| s.len()).sum::<usize>(),
+        report.metadata
+    );
+    assert_eq!(sections[3].len(), report.public_inputs);
+    assert_eq!(sections[4].len(), report.commitments);
+    assert_eq!(sections[5].len(), report.ood_evaluations);
+    assert_eq!(
+        sections[6].len(),
+        fri_layers.sum::<usize>() + report.fri_remainder + report.pow_nonce
+    );
+    assert_eq!(
+        sections[7].len(),
+        report.trace_query_values + report.trace_merkle_paths + report.trace_salts
+    );
+}
+
+#[test]
+fn report_sizes_each_part_of_proof() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let air = air(&proof);
+    let report = proof.size_report();
+    let queries = &proof.trace_queries;
+    let num_positions = queries.base_trace_values.len() / Fib::NUM_BASE_COLUMNS;
+
+    assert_eq!(8, report.public_inputs);
+    // base and composition commitments and the flag of the missing extension
+    assert_eq!(2 * COMMITMENT_SIZE + 1, report.commitments);
+    assert_eq!(
+        vec_size(air.trace_arguments().len()) + vec_size(air.ce_blowup_factor()),
+        report.ood_evaluations
+    );
+    // base and composition values and empty extension and preprocessed values
+    assert_eq!(
+        queries.composition_trace_values.len(),
+        num_positions * air.ce_blowup_factor()
+    );
+    assert_eq!(
+        vec_size(queries.base_trace_values.len())
+            + vec_size(queries.composition_trace_values.len())
+            + 2 * vec_size(0),
+        report.trace_query_values
+    );
+    // three empty vectors of salts
+    assert_eq!(3 * 8, report.trace_salts);
+    assert!(!report.fri_layers.is_empty());
+    for layer in &report.fri_layers {
+        assert_eq!(COMMITMENT_SIZE, layer.commitment);
+    }
+    assert_eq!(8, report.pow_nonce);
+    assert_eq!(49, report.conjectured_security_level);
+}
+
+#[test]
+fn report_sizes_salts_of_zero_knowledge_proof() {
+    let options = common::options().with_zero_knowledge().unwrap();
+    let proof = common::prove_with_options::<Fib>(Fib::columns(), options).unwrap();
+    let report = proof.size_report();
+    let queries = &proof.trace_queries;
+    let num_positions = queries.base_trace_values.len() / Fib::NUM_BASE_COLUMNS;
+
+    // one salt per queried row of the base and composition traces
+    assert_eq!(num_positions, queries.base_trace_salts.len());
+    assert_eq!(num_positions, queries.composition_trace_salts.len());
+    assert_eq!(3 * 8 + 2 * 32 * num_positions, report.trace_salts);
+    assert_eq!(proof.to_bytes().len(), report.total());
+}
@@ -0,0 +1,147 @@
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_ff::Zero;
+use digest::Digest;
+use digest::ExtendableOutput;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::merkle::MerkleTree;
+use ministark::random::PublicCoin;
+use ministark::rescue_prime;
+use ministark::rescue_prime::RescuePrime;
+use ministark::rescue_prime::CAPACITY;
+use ministark::rescue_prime::NUM_ROUNDS;
+use ministark::rescue_prime::STATE_WIDTH;
+use sha3::Shake256;
+
+const MODULUS: u64 = 0xFFFFFFFF00000001;
+
+#[test]
+fn hash_elements_matches_test_vector() {
+    let elements = (1..=8u64).map(Fp::from).collect::<Vec<Fp>>();
+
+    let digest = rescue_prime::hash_elements(&elements);
+
+    // computed with an independent Python implementation of the sponge and
+    // permutation using the constants checked below
+    let expected = [
+        107688695416556312u64,
+        12988222689416087131,
+        3404347572853645027,
+        2519844529727242444,
+    ]
+    .map(Fp::from);
+    assert_eq!(expected, digest);
+}
+
+#[test]
+fn round_constants_match_specification() {
+    let seed = format!("Rescue-XLIX({MODULUS},{STATE_WIDTH},{CAPACITY},128)");
+    let bytes_per_constant = 9;
+    let mut bytes = vec![0; bytes_per_constant * 2 * STATE_WIDTH * NUM_ROUNDS];
+
+    Shake256::digest_xof(seed, &mut bytes);
+
+    let expected = bytes
+        .chunks(bytes_per_constant)
+        .map(Fp::from_le_bytes_mod_order)
+        .collect::<Vec<Fp>>();
+    let constants = rescue_prime::ROUND_CONSTANTS.iter().flatten();
+    assert_eq!(
+        expected,
+        constants.map(|&c| Fp::from(c)).collect::<Vec<Fp>>()
+    );
+}
+
+#[test]
+fn mds_matrix_matches_specification() {
+    let g = Fp::from(7u64);
+    let m = STATE_WIDTH;
+    let mut matrix = (0..m)
+        .map(|i| (0..2 * m).map(|j| g.pow([(i * j) as u64])).collect())
+        .collect::<Vec<Vec<Fp>>>();
+
+    // reduce to the echelon form [I | A]
+    for r in 0..m {
+        let pivot = (r..m).find(|&i| !matrix[i][r].is_zero()).unwrap();
+        matrix.swap(r, pivot);
+        let inv = matrix[r][r].inverse().unwrap();
+        matrix[r].iter_mut().for_each(|v| *v *= inv);
+        let pivot_row = matrix[r].clone();
+        for (i, row) in matrix.iter_mut().enumerate() {
+            let factor = row[r];
+            if i != r {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, p)| *v -= factor * p);
+            }
+        }
+    }
+
+    // the MDS matrix is the transpose of A
+    for (i, row) in rescue_prime::MDS.iter().enumerate() {
+        for (j, &v) in row.iter().enumerate() {
+            assert_eq!(matrix[j][m + i], Fp::from(v));
+        }
+    }
+}
+
+#[test]
+fn public_coin_draws_digest_elements() {
+    let mut public_coin = PublicCoin::<RescuePrime>::new(b"seed");
+    let seed = rescue_prime::digest_to_elements(&public_coin.seed).unwrap();
+
+    let element = public_coin.draw::<Fp>();
+
+    // the first draw hashes the seed with a counter of one
+    let counter = Fp::from(1u64);
+    let digest = rescue_prime::hash_elements(&[seed[0], seed[1], seed[2], seed[3], counter]);
+    assert_eq!(digest[0], element);
+}
+
+#[test]
+fn byte_digest_of_digests_matches_merge() {
+    let a = rescue_prime::hash_elements(&[Fp::from(1u64)]);
+    let b = rescue_prime::hash_elements(&[Fp::from(2u64)]);
+
+    let mut hasher = RescuePrime::new();
+    hasher.update(rescue_prime::elements_to_digest(&a));
+    hasher.update(rescue_prime::elements_to_digest(&b));
+    let digest = hasher.finalize();
+
+    let expected = rescue_prime::merge(&a, &b);
+    assert_eq!(Some(expected), rescue_prime::digest_to_elements(&digest));
+}
+
+#[test]
+fn byte_inputs_are_domain_separated() {
+    let a = RescuePrime::new_with_prefix([1u8]).finalize();
+    let b = RescuePrime::new_with_prefix([1u8, 0]).finalize();
+
+    assert_ne!(a, b);
+}
+
+#[test]
+fn merkle_verify_rescue_prime() {
+    let leaf_values = (0..1 << 6).collect::<Vec<usize>>();
+    let leaf_nodes = leaf_values
+        .iter()
+        .map(|&v| RescuePrime::new_with_prefix(v.to_le_bytes()).finalize())
+        .collect();
+    let tree = MerkleTree::<RescuePrime>::new(leaf_nodes).unwrap();
+    let commitment = tree.root();
+    let i = 13;
+    let proof = tree.prove(i).unwrap().parse::<RescuePrime>();
+
+    assert!(MerkleTree::<RescuePrime>::verify(commitment, &proof, i).is_ok());
+}
+
+#[test]
+fn public_coin_rescue_prime() {
+    let mut coin1 = PublicCoin::<RescuePrime>::new(b"seed");
+    let mut coin2 = PublicCoin::<RescuePrime>::new(b"seed");
+
+    coin1.reseed(&Fp::from(5u64));
+    coin2.reseed(&Fp::from(5u64));
+
+    assert_eq!(coin1.draw::<Fp>(), coin2.draw::<Fp>());
+}
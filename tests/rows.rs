@@ -0,0 +1,88 @@
+use ark_ff::FftField;
+use ark_ff::One;
+use ark_ff::UniformRand;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::constraints::AlgebraicExpression;
+use ministark::constraints::FieldConstant;
+use ministark::constraints::RowSet;
+
+const TRACE_LEN: usize = 2048;
+
+fn eval(expr: &AlgebraicExpression<Fp>, x: Fp) -> Option<Fp> {
+    expr.check(
+        &FieldConstant::Fp(x),
+        &|_| unreachable!(),
+        &|_| unreachable!(),
+        &|_| unreachable!(),
+        &|_, _| unreachable!(),
+    )
+    .map(|v| v.as_fq())
+}
+
+#[test]
+fn row_sets_evaluate_over_coset() {
+    let coset = Radix2EvaluationDomain::<Fp>::new_coset(TRACE_LEN * 4, Fp::GENERATOR).unwrap();
+    let row_sets = [
+        RowSet::Transition {
+            trace_len: TRACE_LEN,
+        },
+        RowSet::EveryNth {
+            trace_len: TRACE_LEN,
+            step: 8,
+            offset: 3,
+        },
+        RowSet::Rows {
+            trace_len: TRACE_LEN,
+            rows: vec![0, 5, TRACE_LEN - 1],
+        },
+    ];
+
+    for rows in row_sets {
+        let evals = rows.evaluate_over(coset);
+
+        assert_eq!(coset.size(), evals.len());
+        for (i, x) in coset.elements().enumerate() {
+            let expected = rows.evaluate::<Fp, Fp>(FieldConstant::Fp(x)).unwrap();
+            assert_eq!(expected.as_fq(), evals[i], "{rows} mismatch at {i}");
+        }
+    }
+}
+
+#[test]
+fn transition_matches_explicit_divisor() {
+    use AlgebraicExpression::*;
+    let mut rng = ark_std::test_rng();
+    let trace_xs = Radix2EvaluationDomain::<Fp>::new(TRACE_LEN).unwrap();
+    let one = FieldConstant::Fp(Fp::one());
+    let last_x = FieldConstant::Fp(trace_xs.element(TRACE_LEN - 1));
+    let constraint: AlgebraicExpression<Fp> = X.pow(3) + one;
+    let explicit = &constraint * ((X - last_x) / (X.pow(TRACE_LEN) - one));
+    let helper = constraint.on_transition(TRACE_LEN);
+    let x = Fp::rand(&mut rng);
+
+    assert_eq!(explicit.degree(TRACE_LEN - 1), helper.degree(TRACE_LEN - 1));
+    assert_eq!(eval(&explicit, x), eval(&helper, x));
+}
+
+#[test]
+fn row_constraints_are_only_enforced_on_their_rows() {
+    use AlgebraicExpression::*;
+    let trace_xs = Radix2EvaluationDomain::<Fp>::new(TRACE_LEN).unwrap();
+    let constraint: AlgebraicExpression<Fp> = Constant(FieldConstant::Fp(Fp::one()));
+    let on_rows = constraint.on_rows(TRACE_LEN, &[3, 100]);
+    let every_nth = constraint.every_nth_row(TRACE_LEN, 16, 5);
+    let first = constraint.on_first_row(TRACE_LEN);
+    let last = constraint.on_last_row(TRACE_LEN);
+    let transition = constraint.on_transition(TRACE_LEN);
+
+    for (row, x) in trace_xs.elements().enumerate() {
+        // a nonzero constraint is only violated on the rows it's enforced on
+        assert_eq!(row == 3 || row == 100, eval(&on_rows, x).is_none());
+        assert_eq!(row % 16 == 5, eval(&every_nth, x).is_none());
+        assert_eq!(row == 0, eval(&first, x).is_none());
+        assert_eq!(row == TRACE_LEN - 1, eval(&last, x).is_none());
+        assert_eq!(row != TRACE_LEN - 1, eval(&transition, x).is_none());
+    }
+}
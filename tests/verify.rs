@@ -0,0 +1,149 @@
+#![feature(allocator_api)]
+use ark_ff::One;
+use common::Fib;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::fri;
+use ministark::fri::FriProof;
+use ministark::fri::FriProofLayer;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+
+mod common;
+
+#[test]
+fn proof_verifies() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+
+    proof
+        .verify(&proof.public_inputs, &VerificationPolicy::new(0))
+        .unwrap();
+}
+
+#[test]
+fn different_public_inputs_are_rejected() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let public_inputs = proof.public_inputs + Fp::one();
+
+    assert!(matches!(
+        proof.verify(&public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::PublicInputsMismatch)
+    ));
+}
+
+#[test]
+fn insecure_proof_is_rejected() {
+    let proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    let security_level = proof.conjectured_security_level();
+    let policy = VerificationPolicy::new(security_level + 1);
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &policy),
+        Err(VerificationError::InsufficientSecurity {
+            security_level: actual,
+            min_security_level
+        }) if actual == security_level && min_security_level == security_level + 1
+    ));
+}
+
+#[test]
+fn tampered_blowup_factor_is_rejected() {
+    let mut proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    proof.options.lde_blowup_factor = 1;
+
+    // queries over a domain without blowup add no security
+    assert_eq!(proof.conjectured_security_level(), 0);
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::InvalidBlowupFactor {
+            lde_blowup_factor: 1,
+            fri_blowup_factor: 1
+        })
+    ));
+
+    proof.options.lde_blowup_factor = 0;
+
+    assert_eq!(proof.conjectured_security_level(), 0);
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::InvalidBlowupFactor {
+            lde_blowup_factor: 0,
+            fri_blowup_factor: 0
+        })
+    ));
+}
+
+#[test]
+fn tampered_folding_factor_is_rejected() {
+    let mut proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    proof.options.fri_folding_factor = 3;
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::UnsupportedFriFoldingFactor {
+            fri_folding_factor: 3
+        })
+    ));
+}
+
+#[test]
+fn short_commitment_is_rejected() {
+    let mut proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    proof.base_trace_commitment.pop();
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::CommitmentLength {
+            commitment: "base trace",
+            expected: 32,
+            actual: 31
+        })
+    ));
+}
+
+/// Copy of a FRI proof with the commitment of `layer` and the remainder
+/// commitment truncated to the given lengths
+fn truncate_fri_commitments(
+    fri_proof: &FriProof<Fp>,
+    layer: usize,
+    layer_commitment_len: usize,
+    remainder_commitment_len: usize,
+) -> FriProof<Fp> {
+    let mut layers = fri_proof.layers().to_vec();
+    let values = layers[layer].values().iter().map(|v| [*v]).collect();
+    let mut commitment = layers[layer].commitment().to_vec();
+    commitment.truncate(layer_commitment_len);
+    layers[layer] = FriProofLayer::new::<1>(values, layers[layer].proof().clone(), commitment);
+    let mut remainder_commitment = fri_proof.remainder_commitment().to_vec();
+    remainder_commitment.truncate(remainder_commitment_len);
+    FriProof::new(layers, remainder_commitment, fri_proof.remainder().to_vec())
+}
+
+#[test]
+fn short_fri_commitments_are_rejected() {
+    let mut proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    proof.fri_proof = truncate_fri_commitments(&proof.fri_proof, 1, 16, 32);
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::FriVerification {
+            source: fri::VerificationError::LayerCommitmentLength {
+                layer: 1,
+                expected: 32,
+                actual: 16
+            }
+        })
+    ));
+
+    let mut proof = common::prove::<Fib>(Fib::columns()).unwrap();
+    proof.fri_proof = truncate_fri_commitments(&proof.fri_proof, 0, 32, 0);
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::FriVerification {
+            source: fri::VerificationError::RemainderCommitmentLength {
+                expected: 32,
+                actual: 0
+            }
+        })
+    ));
+}
@@ -0,0 +1,107 @@
+#![feature(allocator_api)]
+use ark_ff::UniformRand;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use common::Fib;
+use common::TestAir;
+use gpu_poly::allocator::PageAlignedAllocator;
+use gpu_poly::fields::p18446744069414584321::Fp;
+use ministark::prover::ProvingError;
+use ministark::utils;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark::VerificationError;
+use ministark::VerificationPolicy;
+
+mod common;
+
+fn zk_options() -> ProofOptions {
+    common::options().with_zero_knowledge().unwrap()
+}
+
+fn prove_zk() -> Proof<TestAir<Fib>> {
+    common::prove_with_options::<Fib>(Fib::columns(), zk_options()).unwrap()
+}
+
+#[test]
+fn randomized_poly_is_unchanged_over_trace_domain() {
+    let mut rng = ark_std::test_rng();
+    let n = 2048;
+    let domain = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+    let evals = (0..n).map(|_| Fp::rand(&mut rng)).collect::<Vec<Fp>>();
+    let mut coeffs = domain.ifft(&evals).to_vec_in(PageAlignedAllocator);
+
+    utils::randomize_poly(&mut coeffs, 100, &mut rng);
+
+    assert_eq!(2 * n, coeffs.len());
+    for (x, expected) in domain.elements().zip(evals) {
+        assert_eq!(expected, utils::horner_evaluate(&coeffs, &x));
+    }
+}
+
+#[test]
+fn zero_knowledge_halves_fri_blowup_factor() {
+    let options = ProofOptions::new(32, 8, 0, 2, 16);
+
+    let zk_options = options.with_zero_knowledge().unwrap();
+
+    assert_eq!(8, options.fri_blowup_factor());
+    assert_eq!(4, zk_options.fri_blowup_factor());
+}
+
+#[test]
+fn zero_knowledge_needs_blowup() {
+    let options = ProofOptions::new(32, 1, 0, 2, 16);
+
+    assert!(matches!(
+        options.with_zero_knowledge(),
+        Err(ProvingError::ZeroKnowledgeBlowupFactor {
+            lde_blowup_factor: 1
+        })
+    ));
+}
+
+#[test]
+fn zero_knowledge_needs_fri_blowup() {
+    // half of the blowup is used by the randomized trace polynomials
+    let options = ProofOptions::new(32, 2, 0, 2, 16);
+
+    assert!(matches!(
+        options.with_zero_knowledge(),
+        Err(ProvingError::ZeroKnowledgeBlowupFactor {
+            lde_blowup_factor: 2
+        })
+    ));
+    assert_eq!(
+        2,
+        ProofOptions::new(32, 4, 0, 2, 16)
+            .with_zero_knowledge()
+            .unwrap()
+            .fri_blowup_factor()
+    );
+}
+
+#[test]
+fn zero_knowledge_proofs_verify_and_differ() {
+    let policy = VerificationPolicy::new(0);
+
+    let proof1 = prove_zk();
+    let proof2 = prove_zk();
+
+    assert!(proof1.verify(&proof1.public_inputs, &policy).is_ok());
+    assert!(proof2.verify(&proof2.public_inputs, &policy).is_ok());
+    // traces are randomized so proofs of the same trace don't share commitments
+    assert_ne!(proof1.base_trace_commitment, proof2.base_trace_commitment);
+}
+
+#[test]
+fn tampered_zero_knowledge_proof_fails() {
+    let mut proof = prove_zk();
+
+    proof.trace_queries.base_trace_salts[0][0] ^= 1;
+
+    assert!(matches!(
+        proof.verify(&proof.public_inputs, &VerificationPolicy::new(0)),
+        Err(VerificationError::BaseTraceQueryDoesNotMatchCommitment)
+    ));
+}